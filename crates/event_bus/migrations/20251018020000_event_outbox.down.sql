@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE `event_record`
+  DROP INDEX `idx_status_next_retry_at`,
+  DROP COLUMN `next_retry_at`;
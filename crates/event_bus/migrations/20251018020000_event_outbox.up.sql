@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE `event_record`
+  ADD COLUMN `next_retry_at` TIMESTAMP NULL COMMENT '下次重试时间' AFTER `retry_count`,
+  ADD INDEX `idx_status_next_retry_at` (`status`, `next_retry_at`, `created_at`);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE `event_record`
+  MODIFY COLUMN `status` VARCHAR(20) NOT NULL DEFAULT 'pending' COMMENT '事件状态（pending, processing, published, failed）',
+  DROP COLUMN `last_error`;
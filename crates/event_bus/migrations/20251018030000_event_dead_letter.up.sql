@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE `event_record`
+  MODIFY COLUMN `status` VARCHAR(20) NOT NULL DEFAULT 'pending' COMMENT '事件状态（pending, processing, published, failed, dead_letter）',
+  ADD COLUMN `last_error` TEXT NULL COMMENT '最后一次投递失败的错误信息' AFTER `next_retry_at`;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE `event_record`
+  DROP INDEX `uk_seq`,
+  DROP COLUMN `seq`;
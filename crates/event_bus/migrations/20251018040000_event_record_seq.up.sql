@@ -0,0 +1,11 @@
+-- Add up migration script here
+ALTER TABLE `event_record`
+  ADD COLUMN `seq` BIGINT NULL COMMENT '全局递增序号，回放事件时按此排序' AFTER `event_id`;
+
+-- 已有事件按创建时间编号
+SET @seq := 0;
+UPDATE `event_record` SET `seq` = (@seq := @seq + 1) ORDER BY `created_at`, `event_id`;
+
+ALTER TABLE `event_record`
+  MODIFY COLUMN `seq` BIGINT NOT NULL AUTO_INCREMENT COMMENT '全局递增序号，回放事件时按此排序',
+  ADD UNIQUE INDEX `uk_seq` (`seq`);
@@ -0,0 +1,9 @@
+-- Add down migration script here
+ALTER TABLE `event_record`
+  DROP INDEX `idx_correlation_id`,
+  DROP INDEX `idx_aggregate`,
+  DROP COLUMN `causation_id`,
+  DROP COLUMN `correlation_id`,
+  DROP COLUMN `actor_id`,
+  DROP COLUMN `aggregate_id`,
+  DROP COLUMN `aggregate_type`;
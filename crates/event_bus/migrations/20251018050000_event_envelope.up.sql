@@ -0,0 +1,9 @@
+-- Add up migration script here
+ALTER TABLE `event_record`
+  ADD COLUMN `aggregate_type` VARCHAR(50) NOT NULL DEFAULT '' COMMENT '聚合类型' AFTER `event_type`,
+  ADD COLUMN `aggregate_id` VARCHAR(64) NOT NULL DEFAULT '' COMMENT '聚合 id' AFTER `aggregate_type`,
+  ADD COLUMN `actor_id` VARCHAR(64) NULL COMMENT '操作人 user_id' AFTER `aggregate_id`,
+  ADD COLUMN `correlation_id` VARCHAR(64) NULL COMMENT '同一请求产生的事件共用' AFTER `actor_id`,
+  ADD COLUMN `causation_id` VARCHAR(64) NULL COMMENT '触发该事件的事件 id' AFTER `correlation_id`,
+  ADD INDEX `idx_aggregate` (`aggregate_type`, `aggregate_id`, `seq`),
+  ADD INDEX `idx_correlation_id` (`correlation_id`);
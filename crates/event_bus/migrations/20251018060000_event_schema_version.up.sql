@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE `event_record`
+  ADD COLUMN `schema_version` INT NOT NULL DEFAULT 1 COMMENT 'payload 结构版本' AFTER `causation_id`;
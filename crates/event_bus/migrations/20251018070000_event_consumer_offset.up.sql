@@ -0,0 +1,7 @@
+-- Add up migration script here
+CREATE TABLE `event_consumer_offset` (
+  `consumer` VARCHAR(100) NOT NULL COMMENT '消费者名称',
+  `last_seq` BIGINT NOT NULL DEFAULT 0 COMMENT '已处理到的 event_record.seq',
+  `updated_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+  PRIMARY KEY (`consumer`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='跨服务事件消费位置';
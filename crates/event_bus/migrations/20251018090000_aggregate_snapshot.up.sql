@@ -0,0 +1,10 @@
+-- Add up migration script here
+CREATE TABLE `aggregate_snapshot` (
+  `aggregate_type` VARCHAR(50) NOT NULL COMMENT '聚合类型',
+  `aggregate_id` VARCHAR(64) NOT NULL COMMENT '聚合 id',
+  `version` BIGINT NOT NULL COMMENT '快照包含的事件数',
+  `last_seq` BIGINT NOT NULL COMMENT '快照包含的最后一个事件的 event_record.seq',
+  `state` JSON NOT NULL COMMENT '聚合状态',
+  `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  PRIMARY KEY (`aggregate_type`, `aggregate_id`, `version`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='聚合快照';
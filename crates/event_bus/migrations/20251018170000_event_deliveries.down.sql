@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE `event_record`
+  DROP COLUMN `deliveries`;
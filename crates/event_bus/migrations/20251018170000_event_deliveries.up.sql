@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE `event_record`
+  ADD COLUMN `deliveries` JSON NULL COMMENT '各监听器的投递结果，重试时只执行未成功的监听器' AFTER `last_error`;
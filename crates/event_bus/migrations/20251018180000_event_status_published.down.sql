@@ -0,0 +1,3 @@
+-- Add down migration script here
+-- 无法区分哪些事件原来是 publish，保留 published
+SELECT 1;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- 引入 outbox 之前的事件状态为 publish，统一为 published，回放和浏览时才能按状态找到
+UPDATE `event_record` SET `status` = 'published' WHERE `status` = 'publish';
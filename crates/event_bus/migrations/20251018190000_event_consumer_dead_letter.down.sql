@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE `event_consumer_offset`
+  DROP COLUMN `last_error`,
+  DROP COLUMN `dead_letter_seq`,
+  DROP COLUMN `dead_letter_event_id`;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+ALTER TABLE `event_consumer_offset`
+  ADD COLUMN `dead_letter_event_id` CHAR(36) NULL COMMENT '重试次数用完的事件，消费者停在这里直到重新投递或跳过' AFTER `last_seq`,
+  ADD COLUMN `dead_letter_seq` BIGINT NULL COMMENT '死信事件的 event_record.seq' AFTER `dead_letter_event_id`,
+  ADD COLUMN `last_error` TEXT NULL COMMENT '死信事件最后一次处理失败的错误信息' AFTER `dead_letter_seq`;
@@ -0,0 +1,38 @@
+use std::future::Future;
+
+tokio::task_local! {
+    static EVENT_CONTEXT: EventContext;
+}
+
+// 当前请求/事件的上下文，生成事件信封时读取
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventContext {
+    // 操作人
+    pub actor_id: Option<String>,
+    // 同一个请求产生的事件共用一个 correlation_id
+    pub correlation_id: Option<String>,
+    // 触发当前事件的上一个事件
+    pub causation_id: Option<String>,
+}
+
+impl EventContext {
+    pub fn new(actor_id: Option<String>, correlation_id: Option<String>) -> Self {
+        Self {
+            actor_id,
+            correlation_id,
+            causation_id: None,
+        }
+    }
+
+    // 当前任务的上下文，没有设置时为空
+    pub fn current() -> Self {
+        EVENT_CONTEXT
+            .try_with(|context| context.clone())
+            .unwrap_or_default()
+    }
+
+    // 在上下文中执行 future，期间产生的事件都会带上这些信息
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        EVENT_CONTEXT.scope(self, future).await
+    }
+}
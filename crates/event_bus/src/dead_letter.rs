@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, DbConn, DbErr, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder,
+};
+use shared_dto::table_data::{TableDataRequest, TableDataResponse};
+
+use crate::{
+    entitiy::{event_consumer_offset, event_record},
+    outbox::{STATUS_DEAD_LETTER, STATUS_PENDING},
+    AsyncEventBus, Deliveries,
+};
+
+// 死信列表，按时间倒序
+pub async fn list_dead_letters(
+    pool: &DbConn,
+    page: u64,
+    page_size: u64,
+) -> Result<(Vec<event_record::Model>, u64), DbErr> {
+    let paginator = event_record::Entity::find()
+        .filter(event_record::Column::Status.eq(STATUS_DEAD_LETTER))
+        .order_by_desc(event_record::Column::UpdatedAt)
+        .paginate(pool, page_size.clamp(1, 1000));
+
+    let total = paginator.num_items().await?;
+    let data = paginator.fetch_page(page.max(1) - 1).await?;
+
+    Ok((data, total))
+}
+
+// 重放死信：重置为 pending，由 OutboxDispatcher 重新投递，已经成功的监听器不再执行
+pub async fn replay_dead_letter(pool: &DbConn, event_id: &str) -> Result<bool, DbErr> {
+    let Some(record) = event_record::Entity::find_by_id(event_id)
+        .filter(event_record::Column::Status.eq(STATUS_DEAD_LETTER))
+        .one(pool)
+        .await?
+    else {
+        return Ok(false);
+    };
+
+    let mut deliveries = Deliveries::from_json(record.deliveries.as_ref());
+    deliveries.reset_dead_letters();
+
+    let result = event_record::Entity::update_many()
+        .col_expr(event_record::Column::Status, Expr::value(STATUS_PENDING))
+        .col_expr(event_record::Column::RetryCount, Expr::value(0))
+        .col_expr(
+            event_record::Column::NextRetryAt,
+            Expr::value(None::<chrono::DateTime<Utc>>),
+        )
+        .col_expr(
+            event_record::Column::Deliveries,
+            Expr::value(deliveries.to_json()),
+        )
+        .col_expr(event_record::Column::UpdatedAt, Expr::value(Utc::now()))
+        .filter(event_record::Column::EventId.eq(event_id))
+        .filter(event_record::Column::Status.eq(STATUS_DEAD_LETTER))
+        .exec(pool)
+        .await?;
+
+    Ok(result.rows_affected == 1)
+}
+
+// 停在死信事件上的跨服务消费者
+pub async fn list_consumer_dead_letters(
+    pool: &DbConn,
+) -> Result<Vec<event_consumer_offset::Model>, DbErr> {
+    event_consumer_offset::Entity::find()
+        .filter(event_consumer_offset::Column::DeadLetterEventId.is_not_null())
+        .order_by_asc(event_consumer_offset::Column::Consumer)
+        .all(pool)
+        .await
+}
+
+// 处理消费者的死信：skip 为 true 时跳过该事件，否则清除死信，消费者从该事件重新处理
+pub async fn resolve_consumer_dead_letter(
+    pool: &DbConn,
+    consumer: &str,
+    skip: bool,
+) -> Result<bool, DbErr> {
+    let Some(offset) = event_consumer_offset::Entity::find_by_id(consumer)
+        .filter(event_consumer_offset::Column::DeadLetterEventId.is_not_null())
+        .one(pool)
+        .await?
+    else {
+        return Ok(false);
+    };
+
+    let mut update = event_consumer_offset::Entity::update_many()
+        .col_expr(
+            event_consumer_offset::Column::DeadLetterEventId,
+            Expr::value(None::<String>),
+        )
+        .col_expr(
+            event_consumer_offset::Column::DeadLetterSeq,
+            Expr::value(None::<i64>),
+        )
+        .col_expr(
+            event_consumer_offset::Column::LastError,
+            Expr::value(None::<String>),
+        )
+        .col_expr(
+            event_consumer_offset::Column::UpdatedAt,
+            Expr::value(Utc::now()),
+        )
+        .filter(event_consumer_offset::Column::Consumer.eq(consumer))
+        .filter(event_consumer_offset::Column::DeadLetterEventId.eq(offset.dead_letter_event_id));
+    if skip {
+        let last_seq = offset
+            .last_seq
+            .max(offset.dead_letter_seq.unwrap_or_default());
+        update = update.col_expr(
+            event_consumer_offset::Column::LastSeq,
+            Expr::value(last_seq),
+        );
+    }
+
+    Ok(update.exec(pool).await?.rows_affected == 1)
+}
+
+// 死信接口，各服务共用
+pub struct DeadLetterService {
+    pool: Arc<DbConn>,
+    event_bus: Arc<AsyncEventBus>,
+}
+
+impl DeadLetterService {
+    pub fn new(pool: Arc<DbConn>, event_bus: Arc<AsyncEventBus>) -> Self {
+        Self { pool, event_bus }
+    }
+
+    // 重试次数用完的事件
+    pub async fn find_all(
+        &self,
+        data: TableDataRequest,
+    ) -> anyhow::Result<TableDataResponse<event_record::Model>> {
+        let (list, total) =
+            list_dead_letters(self.pool.as_ref(), data.page, data.page_size).await?;
+
+        Ok(TableDataResponse::new(list, total))
+    }
+
+    // 重新投递死信
+    pub async fn replay(&self, event_id: &str) -> anyhow::Result<()> {
+        if !replay_dead_letter(self.pool.as_ref(), event_id).await? {
+            return Err(anyhow::anyhow!("死信事件不存在"));
+        }
+
+        self.event_bus.notify();
+
+        Ok(())
+    }
+
+    // 停在死信事件上的跨服务消费者
+    pub async fn find_consumers(&self) -> anyhow::Result<Vec<event_consumer_offset::Model>> {
+        Ok(list_consumer_dead_letters(self.pool.as_ref()).await?)
+    }
+
+    // 消费者的死信：skip 为 true 时跳过该事件，否则下次轮询重新处理
+    pub async fn resolve_consumer(&self, consumer: &str, skip: bool) -> anyhow::Result<()> {
+        if !resolve_consumer_dead_letter(self.pool.as_ref(), consumer, skip).await? {
+            return Err(anyhow::anyhow!("消费者没有死信事件"));
+        }
+
+        Ok(())
+    }
+}
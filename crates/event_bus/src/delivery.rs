@@ -0,0 +1,213 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    outbox::{STATUS_DEAD_LETTER, STATUS_FAILED, STATUS_PUBLISHED},
+    RetryPolicy,
+};
+
+// 一次投递中单个监听器的结果
+pub struct ListenerOutcome {
+    pub listener: String,
+    pub policy: RetryPolicy,
+    pub result: anyhow::Result<()>,
+}
+
+// 单个监听器的投递状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListenerDelivery {
+    // published / failed / dead_letter
+    pub status: String,
+    // 失败次数
+    pub attempts: i32,
+    pub next_retry_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+// 监听器名 -> 投递状态，存在 event_record.deliveries
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Deliveries(BTreeMap<String, ListenerDelivery>);
+
+impl Deliveries {
+    pub fn from_json(value: Option<&serde_json::Value>) -> Self {
+        value
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    // 是否需要执行：没有成功过、没有进入死信，并且到了重试时间
+    pub fn is_due(&self, listener: &str, now: DateTime<Utc>) -> bool {
+        match self.0.get(listener) {
+            None => true,
+            Some(delivery) => {
+                delivery.status == STATUS_FAILED
+                    && delivery.next_retry_at.is_none_or(|at| at <= now)
+            }
+        }
+    }
+
+    // 记录一次投递的结果，按各监听器自己的重试策略计算下次重试时间
+    pub fn record(&mut self, outcomes: Vec<ListenerOutcome>, now: DateTime<Utc>) {
+        for outcome in outcomes {
+            let attempts = self
+                .0
+                .get(&outcome.listener)
+                .map(|delivery| delivery.attempts)
+                .unwrap_or_default();
+
+            let delivery = match outcome.result {
+                Ok(()) => ListenerDelivery {
+                    status: STATUS_PUBLISHED.to_string(),
+                    attempts,
+                    next_retry_at: None,
+                    last_error: None,
+                },
+                Err(err) => {
+                    let attempts = attempts + 1;
+                    let exhausted = outcome.policy.is_exhausted(attempts);
+                    ListenerDelivery {
+                        status: if exhausted {
+                            STATUS_DEAD_LETTER
+                        } else {
+                            STATUS_FAILED
+                        }
+                        .to_string(),
+                        attempts,
+                        next_retry_at: (!exhausted).then(|| {
+                            now + chrono::Duration::from_std(outcome.policy.backoff(attempts))
+                                .unwrap_or_default()
+                        }),
+                        last_error: Some(format!("{:#}", err)),
+                    }
+                }
+            };
+            self.0.insert(outcome.listener, delivery);
+        }
+    }
+
+    // 事件的整体状态：还有监听器等待重试时为 failed，下次重试取最早的时间；
+    // 没有等待重试的，有监听器进入死信时为 dead_letter，否则为 published
+    pub fn status(&self) -> (&'static str, Option<DateTime<Utc>>) {
+        let next_retry_at = self
+            .0
+            .values()
+            .filter(|delivery| delivery.status == STATUS_FAILED)
+            .filter_map(|delivery| delivery.next_retry_at)
+            .min();
+        if next_retry_at.is_some() {
+            return (STATUS_FAILED, next_retry_at);
+        }
+
+        if self.failed_listeners().is_empty() {
+            (STATUS_PUBLISHED, None)
+        } else {
+            (STATUS_DEAD_LETTER, None)
+        }
+    }
+
+    // 没有成功的监听器及其错误
+    pub fn failed_listeners(&self) -> Vec<(&str, &ListenerDelivery)> {
+        self.0
+            .iter()
+            .filter(|(_, delivery)| delivery.status != STATUS_PUBLISHED)
+            .map(|(listener, delivery)| (listener.as_str(), delivery))
+            .collect()
+    }
+
+    // 汇总的错误信息，写入 event_record.last_error
+    pub fn last_error(&self) -> Option<String> {
+        let errors: Vec<String> = self
+            .failed_listeners()
+            .into_iter()
+            .filter_map(|(listener, delivery)| {
+                delivery
+                    .last_error
+                    .as_ref()
+                    .map(|err| format!("{}: {}", listener, err))
+            })
+            .collect();
+
+        (!errors.is_empty()).then(|| errors.join("; "))
+    }
+
+    // 重放死信：进入死信的监听器重新计数，已成功的监听器不再执行
+    pub fn reset_dead_letters(&mut self) {
+        self.0
+            .retain(|_, delivery| delivery.status != STATUS_DEAD_LETTER);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::Utc;
+
+    use super::{Deliveries, ListenerOutcome};
+    use crate::{
+        outbox::{STATUS_DEAD_LETTER, STATUS_FAILED, STATUS_PUBLISHED},
+        RetryPolicy,
+    };
+
+    fn outcome(listener: &str, max_attempts: i32, ok: bool) -> ListenerOutcome {
+        ListenerOutcome {
+            listener: listener.to_string(),
+            policy: RetryPolicy::new(
+                max_attempts,
+                Duration::from_secs(1),
+                Duration::from_secs(60),
+            ),
+            result: if ok {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!("db down"))
+            },
+        }
+    }
+
+    #[test]
+    fn test_retry_only_failed_listeners() {
+        let now = Utc::now();
+        let mut deliveries = Deliveries::default();
+        deliveries.record(
+            vec![
+                outcome("LoginEventListener", 3, true),
+                outcome("Audit", 2, false),
+            ],
+            now,
+        );
+
+        // 成功的监听器不再执行，失败的按自己的策略等待重试
+        assert!(!deliveries.is_due("LoginEventListener", now));
+        assert!(!deliveries.is_due("Audit", now));
+        let (status, next_retry_at) = deliveries.status();
+        assert_eq!(status, STATUS_FAILED);
+        assert_eq!(next_retry_at, Some(now + chrono::Duration::seconds(1)));
+        assert!(deliveries.is_due("Audit", next_retry_at.unwrap()));
+        assert!(deliveries.is_due("NewListener", now));
+
+        // 第二次失败用完 Audit 的次数，进入死信
+        deliveries.record(vec![outcome("Audit", 2, false)], now);
+        assert_eq!(deliveries.status(), (STATUS_DEAD_LETTER, None));
+        assert_eq!(deliveries.last_error().unwrap(), "Audit: db down");
+
+        // 重放死信只重新执行 Audit
+        deliveries.reset_dead_letters();
+        assert!(deliveries.is_due("Audit", now));
+        assert!(!deliveries.is_due("LoginEventListener", now));
+
+        deliveries.record(vec![outcome("Audit", 2, true)], now);
+        assert_eq!(deliveries.status(), (STATUS_PUBLISHED, None));
+
+        let json = deliveries.to_json();
+        assert_eq!(json["Audit"]["status"], STATUS_PUBLISHED);
+        assert_eq!(Deliveries::from_json(Some(&json)), deliveries);
+    }
+}
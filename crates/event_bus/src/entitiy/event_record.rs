@@ -1,16 +1,29 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.12
 
 use sea_orm::entity::prelude::*;
+use serde::Serialize;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
 #[sea_orm(table_name = "event_record")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub event_id: String,
+    #[sea_orm(unique)]
+    pub seq: i64,
     pub event_type: String,
+    pub aggregate_type: String,
+    pub aggregate_id: String,
+    pub actor_id: Option<String>,
+    pub correlation_id: Option<String>,
+    pub causation_id: Option<String>,
+    pub schema_version: i32,
     pub payload: Json,
     pub status: String,
     pub retry_count: i32,
+    pub next_retry_at: Option<DateTimeUtc>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub last_error: Option<String>,
+    pub deliveries: Option<Json>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
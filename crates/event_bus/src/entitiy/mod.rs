@@ -1 +1,3 @@
+pub mod aggregate_snapshot;
+pub mod event_consumer_offset;
 pub mod event_record;
@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{context::EventContext, entitiy::event_record, Event};
+
+// 事件信封：事件本身加上元数据，持久化和订阅者拿到的都是它
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventEnvelope<T> {
+    pub event_id: String,
+    // 稳定的事件名，见 Event::NAME
+    pub event_type: String,
+    pub aggregate_type: String,
+    pub aggregate_id: String,
+    // event_record 中的全局序号，没有落库的事件为 0
+    pub sequence: i64,
+    // payload 结构版本，见 Event::VERSION
+    pub schema_version: i32,
+    // 操作人 user_id
+    pub actor_id: Option<String>,
+    pub correlation_id: String,
+    pub causation_id: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    pub payload: T,
+}
+
+impl<T: Event> EventEnvelope<T> {
+    // 用当前上下文包装事件
+    pub fn new(event: T) -> Self {
+        let context = EventContext::current();
+        let event_id = Uuid::new_v4().to_string();
+
+        Self {
+            event_type: T::NAME.to_string(),
+            aggregate_type: T::AGGREGATE_TYPE.to_string(),
+            aggregate_id: event.aggregate_id(),
+            sequence: 0,
+            schema_version: T::VERSION,
+            actor_id: context.actor_id,
+            // 没有上下文时事件自己开启一条链路
+            correlation_id: context.correlation_id.unwrap_or_else(|| event_id.clone()),
+            causation_id: context.causation_id,
+            occurred_at: Utc::now(),
+            event_id,
+            payload: event,
+        }
+    }
+}
+
+impl<T> EventEnvelope<T> {
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> EventEnvelope<U> {
+        self.try_map(|payload| Ok::<_, std::convert::Infallible>(f(payload)))
+            .unwrap()
+    }
+
+    pub fn try_map<U, E, F: FnOnce(T) -> Result<U, E>>(self, f: F) -> Result<EventEnvelope<U>, E> {
+        Ok(EventEnvelope {
+            event_id: self.event_id,
+            event_type: self.event_type,
+            aggregate_type: self.aggregate_type,
+            aggregate_id: self.aggregate_id,
+            sequence: self.sequence,
+            schema_version: self.schema_version,
+            actor_id: self.actor_id,
+            correlation_id: self.correlation_id,
+            causation_id: self.causation_id,
+            occurred_at: self.occurred_at,
+            payload: f(self.payload)?,
+        })
+    }
+
+    // 由这个事件触发的后续事件使用的上下文
+    pub fn context(&self) -> EventContext {
+        EventContext {
+            actor_id: self.actor_id.clone(),
+            correlation_id: Some(self.correlation_id.clone()),
+            causation_id: Some(self.event_id.clone()),
+        }
+    }
+}
+
+impl From<event_record::Model> for EventEnvelope<serde_json::Value> {
+    fn from(record: event_record::Model) -> Self {
+        Self {
+            correlation_id: record
+                .correlation_id
+                .unwrap_or_else(|| record.event_id.clone()),
+            event_id: record.event_id,
+            event_type: record.event_type,
+            aggregate_type: record.aggregate_type,
+            aggregate_id: record.aggregate_id,
+            sequence: record.seq,
+            schema_version: record.schema_version,
+            actor_id: record.actor_id,
+            causation_id: record.causation_id,
+            occurred_at: record.created_at,
+            payload: record.payload,
+        }
+    }
+}
@@ -1,88 +1,339 @@
+pub mod context;
+pub mod dead_letter;
+pub mod delivery;
 pub mod entitiy;
+pub mod envelope;
+pub mod outbox;
+pub mod replay;
+pub mod retry;
+pub mod snapshot;
+pub mod store;
+pub mod stream;
+pub mod subscriber;
+pub mod transport;
+pub mod upcast;
+
 use std::{
     any::{Any, TypeId},
     collections::HashMap,
     future::Future,
     pin::Pin,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 
-use sea_orm::{ActiveModelTrait, ActiveValue::Set, DbConn, DbErr};
-use serde::Serialize;
-use uuid::Uuid;
+use sea_orm::{DbConn, DbErr};
+use serde::{de::DeserializeOwned, Serialize};
+use subscriber::Subscriber;
+use tokio::sync::Notify;
+
+pub use context::EventContext;
+pub use delivery::{Deliveries, ListenerOutcome};
+pub use envelope::EventEnvelope;
+pub use outbox::{OutboxDispatcher, OutboxEvent};
+pub use replay::{EventReplayer, TableBackup};
+pub use retry::RetryPolicy;
+pub use snapshot::{Aggregate, SnapshotStore};
+pub use stream::load_stream;
+pub use subscriber::SubscriberOptions;
+pub use transport::{EventTransport, InMemoryTransport, MysqlPollingTransport, TransportConsumer};
+pub use upcast::{Upcaster, UpcasterRegistry};
+
+pub trait Event: Any + Send + Sync + Clone + Serialize + DeserializeOwned {
+    // 稳定的事件名，写入 event_record.event_type，不随模块路径变化
+    const NAME: &'static str;
+    // 聚合类型，例如 house
+    const AGGREGATE_TYPE: &'static str;
+    // payload 结构版本，不兼容的改动要加一，并在 upcasters 里提供旧版本的升级函数
+    const VERSION: i32 = 1;
+
+    fn aggregate_id(&self) -> String;
+
+    // 旧版本 payload 的升级函数，读取 event_record 时按版本依次执行
+    fn upcasters() -> Vec<Upcaster> {
+        vec![]
+    }
+}
 
-pub trait Event: Any + Send + Sync + Clone + Serialize {}
-impl<T> Event for T where T: Any + Send + Sync + Clone + Serialize {}
+pub(crate) type HandleFuture = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
 
-type AsyncCallback<T> = Box<dyn Fn(T) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+type AsyncCallback<T> = Box<dyn Fn(EventEnvelope<T>) -> HandleFuture + Send + Sync>;
 
 pub struct AsyncEventBus {
-    subscribers: Arc<RwLock<HashMap<TypeId, Vec<AsyncCallbackBox>>>>,
+    subscribers: Arc<RwLock<HashMap<TypeId, Vec<Arc<Subscriber>>>>>,
+    // 事件名 -> (类型, 当前版本, 反序列化函数)，outbox 投递时用来还原事件
+    decoders: Arc<RwLock<HashMap<String, Decoder>>>,
+    // 旧版本 payload 的升级函数
+    upcasters: Arc<RwLock<UpcasterRegistry>>,
     pool: Option<Arc<DbConn>>,
+    notify: Arc<Notify>,
 }
 
-type AsyncCallbackBox =
-    Box<dyn Fn(Box<dyn Any + Send>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+type Decoder = (TypeId, i32, EventDecoder);
+
+type EventDecoder = Box<
+    dyn Fn(EventEnvelope<serde_json::Value>) -> serde_json::Result<Box<dyn Any + Send>>
+        + Send
+        + Sync,
+>;
 
 impl AsyncEventBus {
     pub fn new(pool: Option<Arc<DbConn>>) -> Self {
         Self {
             subscribers: Arc::new(RwLock::new(HashMap::new())),
+            decoders: Arc::new(RwLock::new(HashMap::new())),
+            upcasters: Arc::new(RwLock::new(UpcasterRegistry::default())),
             pool,
+            notify: Arc::new(Notify::new()),
         }
     }
 
-    pub async fn publish<T: Event>(&self, event: T) {
-        let type_id = TypeId::of::<T>();
-        let subscribers = self.subscribers.read().unwrap();
-        if let Some(callbacks) = subscribers.get(&type_id) {
-            for callback in callbacks {
-                let event_box = Box::new(event.clone()) as Box<dyn Any + Send>;
-                callback(event_box).await;
+    // 并发执行订阅者，等待全部完成
+    pub async fn publish<T: Event>(&self, event: T) -> anyhow::Result<()> {
+        subscriber::join(self.prepare_publish(event)).await
+    }
+
+    // 后台执行订阅者，不等待结果，失败只记录日志
+    pub fn publish_detached<T: Event>(&self, event: T) {
+        let futures = self.prepare_publish(event);
+        tokio::spawn(async move {
+            if let Err(err) = subscriber::join(futures).await {
+                tracing::error!("事件 {} 处理失败: {}", T::NAME, err);
             }
-        }
+        });
+    }
+
+    fn prepare_publish<T: Event>(&self, event: T) -> Vec<HandleFuture> {
+        let envelope = EventEnvelope::new(event);
+        let subscribers = self
+            .snapshot(&TypeId::of::<T>())
+            .into_iter()
+            .map(|subscriber| {
+                let event: Box<dyn Any + Send> = Box::new(envelope.clone());
+                (subscriber, event)
+            })
+            .collect();
+
+        subscriber::prepare(subscribers, T::NAME, &envelope.context())
+    }
+
+    // 复制一份订阅者列表，执行期间不持有锁，订阅者可以随时注册
+    fn snapshot(&self, type_id: &TypeId) -> Vec<Arc<Subscriber>> {
+        self.subscribers
+            .read()
+            .unwrap()
+            .get(type_id)
+            .cloned()
+            .unwrap_or_default()
     }
 
     pub fn subscribe<T: Event + 'static>(&self, callback: AsyncCallback<T>) {
+        self.subscribe_with_options(callback, SubscriberOptions::default());
+    }
+
+    pub fn subscribe_with_options<T: Event + 'static>(
+        &self,
+        callback: AsyncCallback<T>,
+        options: SubscriberOptions,
+    ) {
         let type_id = TypeId::of::<T>();
         let mut subscribers = self.subscribers.write().unwrap();
-        let entry = subscribers.entry(type_id).or_insert_with(Vec::new);
+        let subscribers = subscribers.entry(type_id).or_default();
+
+        // 监听器名用来记录各自的投递结果，同一事件下重名的加上序号
+        let mut options = options;
+        let same_name = subscribers
+            .iter()
+            .filter(|subscriber| {
+                subscriber.options.name == options.name
+                    || subscriber
+                        .options
+                        .name
+                        .starts_with(&format!("{}#", options.name))
+            })
+            .count();
+        if same_name > 0 {
+            options.name = format!("{}#{}", options.name, same_name + 1);
+        }
+
         // 包装成统一的 Box<dyn Fn(Box<dyn Any + Send>)>
-        let wrapper: AsyncCallbackBox = Box::new(move |event: Box<dyn Any + Send>| {
-            let event = *event.downcast::<T>().unwrap();
-            callback(event)
-        });
-        entry.push(wrapper);
+        let subscriber = Subscriber {
+            options,
+            callback: Box::new(move |event: Box<dyn Any + Send>| {
+                let event = *event.downcast::<EventEnvelope<T>>().unwrap();
+                callback(event)
+            }),
+        };
+        subscribers.push(Arc::new(subscriber));
+
+        // 注册反序列化函数，outbox 才能把 payload 还原成 T
+        self.decoders
+            .write()
+            .unwrap()
+            .entry(T::NAME.to_string())
+            .or_insert_with(|| {
+                let mut upcasters = self.upcasters.write().unwrap();
+                for upcaster in T::upcasters() {
+                    upcasters.register(T::NAME, upcaster);
+                }
+
+                (
+                    type_id,
+                    T::VERSION,
+                    Box::new(|envelope| {
+                        envelope
+                            .try_map(serde_json::from_value::<T>)
+                            .map(|envelope| Box::new(envelope) as Box<dyn Any + Send>)
+                    }),
+                )
+            });
     }
 
-    pub async fn persist_and_publish<T: Event>(&self, event: T) -> Result<(), DbErr> {
-        self.persist(event.clone()).await?;
-        self.publish(event).await;
-        Ok(())
+    // 注册升级函数，事件定义之外的补充
+    pub fn register_upcaster(&self, event_type: &str, upcaster: Upcaster) {
+        self.upcasters
+            .write()
+            .unwrap()
+            .register(event_type, upcaster);
     }
 
-    pub async fn persist<T: Event>(&self, event: T) -> Result<(), DbErr> {
-        if self.pool.is_none() {
-            return Ok(());
+    // 已注册订阅者的事件类型
+    pub fn event_types(&self) -> Vec<String> {
+        self.decoders.read().unwrap().keys().cloned().collect()
+    }
+
+    // 把 outbox 中的事件投递给订阅者，任意一个订阅者失败都算投递失败
+    pub async fn deliver(&self, envelope: EventEnvelope<serde_json::Value>) -> anyhow::Result<()> {
+        let errors: Vec<String> = self
+            .deliver_to(envelope, |_| true)
+            .await?
+            .into_iter()
+            .filter_map(|outcome| {
+                outcome
+                    .result
+                    .err()
+                    .map(|err| format!("{}: {}", outcome.listener, err))
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(errors.join("; ")))
         }
+    }
+
+    // 只投递给 should_run 返回 true 的订阅者，返回每个订阅者的结果和重试策略，
+    // 重试时跳过已经成功的订阅者。每个订阅者在独立任务里执行，panic 也只算它自己失败
+    pub async fn deliver_to(
+        &self,
+        envelope: EventEnvelope<serde_json::Value>,
+        should_run: impl Fn(&str) -> bool,
+    ) -> anyhow::Result<Vec<ListenerOutcome>> {
+        let context = envelope.context();
+        let event_type = envelope.event_type.clone();
+        let subscribers = {
+            let decoders = self.decoders.read().unwrap();
+            let (type_id, version, decoder) = decoders
+                .get(&envelope.event_type)
+                .ok_or_else(|| anyhow::anyhow!("未注册的事件类型: {}", envelope.event_type))?;
+
+            // 旧版本的 payload 先升级到当前版本
+            let envelope = if envelope.schema_version == *version {
+                Ok(envelope)
+            } else {
+                let upcasters = self.upcasters.read().unwrap();
+                let from_version = envelope.schema_version;
+                envelope
+                    .try_map(|payload| {
+                        upcasters.upcast(&event_type, from_version, *version, payload)
+                    })
+                    .map(|mut envelope| {
+                        envelope.schema_version = *version;
+                        envelope
+                    })
+            };
 
-        let model = entitiy::event_record::ActiveModel {
-            event_id: Set(Uuid::new_v4().to_string()),
-            event_type: Set(format!("{}", std::any::type_name::<T>())),
-            payload: Set(serde_json::to_value(&event).unwrap()),
-            status: Set("publish".to_string()),
-            ..Default::default()
+            // 每个订阅者拿到各自解码的事件，升级或解码失败算作这些订阅者失败
+            self.snapshot(type_id)
+                .into_iter()
+                .filter(|subscriber| should_run(&subscriber.options.name))
+                .map(|subscriber| {
+                    let event = match &envelope {
+                        Ok(envelope) => decoder(envelope.clone()).map_err(anyhow::Error::from),
+                        Err(err) => Err(anyhow::anyhow!("{:#}", err)),
+                    };
+                    (subscriber, event)
+                })
+                .collect::<Vec<_>>()
         };
 
-        model.insert(self.pool.as_ref().unwrap().as_ref()).await?;
+        let tasks = subscribers.into_iter().map(|(subscriber, event)| {
+            let listener = subscriber.options.name.clone();
+            let policy = subscriber.options.retry_policy;
+            let task = event.map(|event| {
+                tokio::spawn(subscriber::run(
+                    subscriber,
+                    event,
+                    event_type.clone(),
+                    context.clone(),
+                ))
+            });
+
+            async move {
+                let result = match task {
+                    Ok(task) => task
+                        .await
+                        .map_err(|err| anyhow::anyhow!("订阅者执行异常: {}", err))
+                        .and_then(|result| result),
+                    Err(err) => Err(err),
+                };
+
+                ListenerOutcome {
+                    listener,
+                    policy,
+                    result,
+                }
+            }
+        });
+
+        Ok(futures::future::join_all(tasks).await)
+    }
+
+    // 写入 outbox（独立连接），由 OutboxDispatcher 异步投递
+    pub async fn persist<T: Event>(&self, event: T) -> Result<(), DbErr> {
+        if let Some(pool) = &self.pool {
+            OutboxEvent::append(pool.as_ref(), &[OutboxEvent::from_event(&event)]).await?;
+            self.notify();
+        }
+
         Ok(())
     }
+
+    // 唤醒 OutboxDispatcher，事务提交后调用
+    pub fn notify(&self) {
+        self.notify.notify_one();
+    }
+
+    pub(crate) async fn notified(&self) {
+        self.notify.notified().await;
+    }
 }
 
 // 事件监听 trait
 #[async_trait::async_trait]
 pub trait EventListener<E: Event>: Send + Sync {
-    async fn handle(&self, event: E);
+    async fn handle(&self, event: EventEnvelope<E>) -> anyhow::Result<()>;
+
+    // 重试策略，监听器可以按需覆盖
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::default()
+    }
+
+    // 单次处理的超时时间
+    fn timeout(&self) -> Duration {
+        SubscriberOptions::default().timeout
+    }
 
     fn subscribe(self: Arc<Self>, bus: Arc<AsyncEventBus>)
     where
@@ -90,38 +341,61 @@ pub trait EventListener<E: Event>: Send + Sync {
         E: 'static,
     {
         let listener = self.clone();
-        bus.subscribe::<E>(Box::new(move |event: E| {
-            let listener = listener.clone();
-            Box::pin(async move {
-                listener.handle(event).await;
-            })
-        }));
+        let options = SubscriberOptions {
+            name: listener_name::<Self>(),
+            timeout: self.timeout(),
+            retry_policy: self.retry_policy(),
+        };
+        bus.subscribe_with_options::<E>(
+            Box::new(move |event: EventEnvelope<E>| {
+                let listener = listener.clone();
+                Box::pin(async move { listener.handle(event).await })
+            }),
+            options,
+        );
     }
 }
 
+// 去掉模块路径的类型名
+fn listener_name<T>() -> String {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name).to_string()
+}
+
 // ================== 测试 ==================
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde::Deserialize;
     use std::sync::atomic::{AtomicUsize, Ordering};
     use tokio::time::{sleep, Duration};
 
-    #[derive(Debug, Clone, Serialize)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     struct UserRegisteredEvent {
         pub username: String,
     }
 
+    impl Event for UserRegisteredEvent {
+        const NAME: &'static str = "UserRegisteredEvent";
+        const AGGREGATE_TYPE: &'static str = "user";
+
+        fn aggregate_id(&self) -> String {
+            self.username.clone()
+        }
+    }
+
     struct UserListener {
         pub count: Arc<AtomicUsize>,
     }
 
     #[async_trait::async_trait]
     impl EventListener<UserRegisteredEvent> for UserListener {
-        async fn handle(&self, event: UserRegisteredEvent) {
+        async fn handle(&self, event: EventEnvelope<UserRegisteredEvent>) -> anyhow::Result<()> {
             self.count.fetch_add(1, Ordering::SeqCst);
 
-            println!("User registered: {}", event.username);
+            println!("User registered: {}", event.payload.username);
             sleep(Duration::from_millis(100)).await;
+            Ok(())
         }
     }
 
@@ -137,8 +411,429 @@ mod tests {
         bus.publish(UserRegisteredEvent {
             username: "alice".to_string(),
         })
-        .await;
+        .await
+        .unwrap();
+
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_outbox_payload() {
+        let bus = Arc::new(AsyncEventBus::new(None));
+        let count = Arc::new(AtomicUsize::new(0));
+        Arc::new(UserListener {
+            count: count.clone(),
+        })
+        .subscribe(bus.clone());
+
+        let record = OutboxEvent::from_event(&UserRegisteredEvent {
+            username: "bob".to_string(),
+        });
+        assert_eq!(bus.event_types(), vec![record.event_type.clone()]);
+
+        bus.deliver(record.clone()).await.unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        let unknown = EventEnvelope {
+            event_type: "unknown".to_string(),
+            ..record
+        };
+        assert!(bus.deliver(unknown).await.is_err());
+    }
+
+    struct FailingListener;
+
+    #[async_trait::async_trait]
+    impl EventListener<UserRegisteredEvent> for FailingListener {
+        async fn handle(&self, _event: EventEnvelope<UserRegisteredEvent>) -> anyhow::Result<()> {
+            Err(anyhow::anyhow!("db down"))
+        }
+
+        fn retry_policy(&self) -> RetryPolicy {
+            RetryPolicy::new(8, Duration::from_secs(2), Duration::from_secs(60))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failing_listener() {
+        let bus = Arc::new(AsyncEventBus::new(None));
+        let count = Arc::new(AtomicUsize::new(0));
+        Arc::new(UserListener {
+            count: count.clone(),
+        })
+        .subscribe(bus.clone());
+        Arc::new(FailingListener).subscribe(bus.clone());
+
+        let err = bus
+            .publish(UserRegisteredEvent {
+                username: "carol".to_string(),
+            })
+            .await
+            .unwrap_err();
 
+        // 其他监听器照常执行
         assert_eq!(count.load(Ordering::SeqCst), 1);
+        assert_eq!(err.to_string(), "FailingListener: db down");
+
+        // outbox 重试只执行失败的监听器，各自使用自己的重试策略
+        let record = OutboxEvent::from_event(&UserRegisteredEvent {
+            username: "carol".to_string(),
+        });
+        let outcomes = bus
+            .deliver_to(record, |listener| listener != "UserListener")
+            .await
+            .unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].listener, "FailingListener");
+        assert!(outcomes[0].result.is_err());
+        assert_eq!(outcomes[0].policy.max_attempts, 8);
+        assert_eq!(outcomes[0].policy.max_backoff, Duration::from_secs(60));
+    }
+
+    struct CausationListener {
+        pub received: Arc<std::sync::Mutex<Vec<EventEnvelope<UserRegisteredEvent>>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl EventListener<UserRegisteredEvent> for CausationListener {
+        async fn handle(&self, event: EventEnvelope<UserRegisteredEvent>) -> anyhow::Result<()> {
+            // 监听器里产生的新事件继承 actor/correlation，causation 指向当前事件
+            let follow_up = EventEnvelope::new(event.payload.clone());
+            assert_eq!(follow_up.actor_id, event.actor_id);
+            assert_eq!(follow_up.correlation_id, event.correlation_id);
+            assert_eq!(follow_up.causation_id, Some(event.event_id.clone()));
+
+            self.received.lock().unwrap().push(event);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_envelope_context() {
+        let bus = Arc::new(AsyncEventBus::new(None));
+        let received = Arc::new(std::sync::Mutex::new(vec![]));
+        Arc::new(CausationListener {
+            received: received.clone(),
+        })
+        .subscribe(bus.clone());
+
+        let context = EventContext::new(Some("u1".to_string()), Some("req-1".to_string()));
+        context
+            .scope(bus.publish(UserRegisteredEvent {
+                username: "dave".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        let received = received.lock().unwrap();
+        let envelope = &received[0];
+        assert_eq!(envelope.event_type, "UserRegisteredEvent");
+        assert_eq!(envelope.aggregate_type, "user");
+        assert_eq!(envelope.aggregate_id, "dave");
+        assert_eq!(envelope.actor_id.as_deref(), Some("u1"));
+        assert_eq!(envelope.correlation_id, "req-1");
+        assert_eq!(envelope.causation_id, None);
+    }
+
+    // v1: {"name": ..}，v2 改名为 username
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct UserRenamedEvent {
+        pub username: String,
+    }
+
+    impl Event for UserRenamedEvent {
+        const NAME: &'static str = "UserRenamedEvent";
+        const AGGREGATE_TYPE: &'static str = "user";
+        const VERSION: i32 = 2;
+
+        fn aggregate_id(&self) -> String {
+            self.username.clone()
+        }
+
+        fn upcasters() -> Vec<Upcaster> {
+            vec![Upcaster::new(1, |mut payload| {
+                let name = payload["name"].take();
+                Ok(serde_json::json!({ "username": name }))
+            })]
+        }
+    }
+
+    struct RenamedListener {
+        pub received: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl EventListener<UserRenamedEvent> for RenamedListener {
+        async fn handle(&self, event: EventEnvelope<UserRenamedEvent>) -> anyhow::Result<()> {
+            assert_eq!(event.schema_version, 2);
+            self.received.lock().unwrap().push(event.payload.username);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upcast_old_payload() {
+        let bus = Arc::new(AsyncEventBus::new(None));
+        let received = Arc::new(std::sync::Mutex::new(vec![]));
+        Arc::new(RenamedListener {
+            received: received.clone(),
+        })
+        .subscribe(bus.clone());
+
+        let current = OutboxEvent::from_event(&UserRenamedEvent {
+            username: "erin".to_string(),
+        });
+        let old = EventEnvelope {
+            schema_version: 1,
+            payload: serde_json::json!({ "name": "frank" }),
+            ..current.clone()
+        };
+
+        bus.deliver(current).await.unwrap();
+        bus.deliver(old).await.unwrap();
+        assert_eq!(*received.lock().unwrap(), vec!["erin", "frank"]);
+    }
+
+    struct SlowListener {
+        pub delay: Duration,
+        pub count: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl EventListener<UserRegisteredEvent> for SlowListener {
+        async fn handle(&self, _event: EventEnvelope<UserRegisteredEvent>) -> anyhow::Result<()> {
+            sleep(self.delay).await;
+            self.count.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn timeout(&self) -> Duration {
+            Duration::from_millis(200)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_fan_out() {
+        let bus = Arc::new(AsyncEventBus::new(None));
+        let count = Arc::new(AtomicUsize::new(0));
+        for _ in 0..3 {
+            Arc::new(SlowListener {
+                delay: Duration::from_millis(100),
+                count: count.clone(),
+            })
+            .subscribe(bus.clone());
+        }
+
+        // 三个监听器并发执行，总耗时接近单个监听器
+        let started = std::time::Instant::now();
+        bus.publish(UserRegisteredEvent {
+            username: "gina".to_string(),
+        })
+        .await
+        .unwrap();
+        assert!(started.elapsed() < Duration::from_millis(250));
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+
+        // 超时的监听器算失败，不影响其他监听器
+        Arc::new(SlowListener {
+            delay: Duration::from_secs(5),
+            count: count.clone(),
+        })
+        .subscribe(bus.clone());
+        let err = bus
+            .publish(UserRegisteredEvent {
+                username: "hank".to_string(),
+            })
+            .await
+            .unwrap_err();
+        // 同名监听器按注册顺序加上序号
+        assert!(err.to_string().starts_with("SlowListener#4: 执行超过"));
+        assert_eq!(count.load(Ordering::SeqCst), 6);
+    }
+
+    #[tokio::test]
+    async fn test_publish_detached() {
+        let bus = Arc::new(AsyncEventBus::new(None));
+        let count = Arc::new(AtomicUsize::new(0));
+        Arc::new(SlowListener {
+            delay: Duration::from_millis(50),
+            count: count.clone(),
+        })
+        .subscribe(bus.clone());
+
+        bus.publish_detached(UserRegisteredEvent {
+            username: "ivy".to_string(),
+        });
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+
+        sleep(Duration::from_millis(150)).await;
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_transport_consumer_offsets() {
+        let transport = Arc::new(InMemoryTransport::new());
+        transport
+            .send(&[
+                OutboxEvent::from_event(&UserRegisteredEvent {
+                    username: "jack".to_string(),
+                }),
+                OutboxEvent::from_event(&UserRegisteredEvent {
+                    username: "kate".to_string(),
+                }),
+            ])
+            .await
+            .unwrap();
+
+        // 两个消费者各自记录消费位置
+        let mut counts = vec![];
+        for name in ["domus", "filestore"] {
+            let bus = Arc::new(AsyncEventBus::new(None));
+            let count = Arc::new(AtomicUsize::new(0));
+            Arc::new(UserListener {
+                count: count.clone(),
+            })
+            .subscribe(bus.clone());
+
+            let consumer = TransportConsumer::new(name, transport.clone(), bus);
+            assert_eq!(consumer.consume_pending().await.unwrap(), 2);
+            assert_eq!(consumer.consume_pending().await.unwrap(), 0);
+            counts.push(count.load(Ordering::SeqCst));
+        }
+        assert_eq!(counts, vec![2, 2]);
+
+        transport
+            .send(&[OutboxEvent::from_event(&UserRegisteredEvent {
+                username: "liam".to_string(),
+            })])
+            .await
+            .unwrap();
+        let events = transport
+            .poll("domus", &["UserRegisteredEvent".to_string()], 10)
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].sequence, 3);
+    }
+
+    struct RejectingListener {
+        pub count: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl EventListener<UserRegisteredEvent> for RejectingListener {
+        async fn handle(&self, event: EventEnvelope<UserRegisteredEvent>) -> anyhow::Result<()> {
+            if event.payload.username == "mallory" {
+                return Err(anyhow::anyhow!("rejected"));
+            }
+            self.count.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn retry_policy(&self) -> RetryPolicy {
+            RetryPolicy::new(1, Duration::from_millis(10), Duration::from_millis(10))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transport_consumer_dead_letter() {
+        let transport = Arc::new(InMemoryTransport::new());
+        transport
+            .send(&[
+                OutboxEvent::from_event(&UserRegisteredEvent {
+                    username: "mallory".to_string(),
+                }),
+                OutboxEvent::from_event(&UserRegisteredEvent {
+                    username: "nina".to_string(),
+                }),
+            ])
+            .await
+            .unwrap();
+
+        let bus = Arc::new(AsyncEventBus::new(None));
+        let count = Arc::new(AtomicUsize::new(0));
+        Arc::new(RejectingListener {
+            count: count.clone(),
+        })
+        .subscribe(bus.clone());
+        let consumer = TransportConsumer::new("domus", transport.clone(), bus);
+
+        // 次数用完的事件转为死信，不确认，后面的事件也不处理
+        assert_eq!(consumer.consume_pending().await.unwrap(), 0);
+        assert!(transport.dead_letter_of("domus").await.unwrap().is_some());
+        assert_eq!(consumer.consume_pending().await.unwrap(), 0);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+
+        // 重新投递仍然失败，再次停在死信
+        assert!(transport.resolve_dead_letter("domus", false).await.unwrap());
+        assert_eq!(consumer.consume_pending().await.unwrap(), 0);
+        assert!(transport.dead_letter_of("domus").await.unwrap().is_some());
+
+        // 跳过后继续处理后面的事件
+        assert!(transport.resolve_dead_letter("domus", true).await.unwrap());
+        assert_eq!(consumer.consume_pending().await.unwrap(), 1);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_replay_dead_letter_events() {
+        use crate::{
+            entitiy::event_record,
+            outbox::{STATUS_DEAD_LETTER, STATUS_FAILED, STATUS_PUBLISHED},
+        };
+        use sea_orm::{DbBackend, MockDatabase};
+
+        let record = |seq: i64, username: &str, status: &str| event_record::Model {
+            event_id: format!("e{}", seq),
+            seq,
+            event_type: "UserRegisteredEvent".to_string(),
+            aggregate_type: "user".to_string(),
+            aggregate_id: username.to_string(),
+            actor_id: None,
+            correlation_id: None,
+            causation_id: None,
+            schema_version: 1,
+            payload: serde_json::json!({ "username": username }),
+            status: status.to_string(),
+            retry_count: 0,
+            next_retry_at: None,
+            last_error: None,
+            deliveries: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        // 投影已经应用过，因为其他监听器失败而停在 dead_letter / failed 的事件
+        let pool = Arc::new(
+            MockDatabase::new(DbBackend::MySql)
+                .append_query_results([vec![
+                    record(1, "olivia", STATUS_PUBLISHED),
+                    record(2, "paul", STATUS_DEAD_LETTER),
+                    record(3, "quinn", STATUS_FAILED),
+                ]])
+                .append_query_results([Vec::<event_record::Model>::new()])
+                .into_connection(),
+        );
+
+        let bus = Arc::new(AsyncEventBus::new(None));
+        let count = Arc::new(AtomicUsize::new(0));
+        Arc::new(UserListener {
+            count: count.clone(),
+        })
+        .subscribe(bus.clone());
+
+        let replayed = EventReplayer::new(pool.clone())
+            .replay(&bus, |_| {})
+            .await
+            .unwrap();
+        assert_eq!(replayed, 3);
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+
+        // 查询不按投递状态过滤
+        let log = Arc::try_unwrap(pool).unwrap().into_transaction_log();
+        assert_eq!(log.len(), 2);
+        assert!(log
+            .iter()
+            .all(|transaction| !format!("{:?}", transaction).contains("`status` IN")));
     }
 }
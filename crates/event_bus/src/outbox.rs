@@ -0,0 +1,263 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use sea_orm::{
+    sea_query::Expr, ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait, DbConn, DbErr,
+    EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use tokio::task::JoinHandle;
+
+use crate::{entitiy::event_record, AsyncEventBus, Deliveries, Event, EventEnvelope, RetryPolicy};
+
+// 事件状态
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_PROCESSING: &str = "processing";
+pub const STATUS_PUBLISHED: &str = "published";
+// 引入 outbox 之前写入的已发布事件
+pub const STATUS_LEGACY_PUBLISHED: &str = "publish";
+pub const STATUS_FAILED: &str = "failed";
+// 重试次数用完，需要人工处理
+pub const STATUS_DEAD_LETTER: &str = "dead_letter";
+
+// 待写入 outbox 的事件，和聚合在同一个事务里落库
+pub type OutboxEvent = EventEnvelope<serde_json::Value>;
+
+impl OutboxEvent {
+    pub fn from_event<T: Event>(event: &T) -> Self {
+        EventEnvelope::new(event.clone()).map(|event| serde_json::to_value(event).unwrap())
+    }
+
+    pub fn from_events<T: Event>(events: &[T]) -> Vec<Self> {
+        events.iter().map(Self::from_event).collect()
+    }
+
+    // 写入 event_record，conn 可以是事务
+    pub async fn append<C: ConnectionTrait>(conn: &C, events: &[OutboxEvent]) -> Result<(), DbErr> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let models = events.iter().map(|event| event_record::ActiveModel {
+            event_id: Set(event.event_id.clone()),
+            event_type: Set(event.event_type.clone()),
+            aggregate_type: Set(event.aggregate_type.clone()),
+            aggregate_id: Set(event.aggregate_id.clone()),
+            actor_id: Set(event.actor_id.clone()),
+            correlation_id: Set(Some(event.correlation_id.clone())),
+            causation_id: Set(event.causation_id.clone()),
+            schema_version: Set(event.schema_version),
+            payload: Set(event.payload.clone()),
+            status: Set(STATUS_PENDING.to_string()),
+            created_at: Set(event.occurred_at),
+            ..Default::default()
+        });
+
+        event_record::Entity::insert_many(models).exec(conn).await?;
+        Ok(())
+    }
+}
+
+// 轮询 event_record，把未投递的事件交给订阅者
+pub struct OutboxDispatcher {
+    bus: Arc<AsyncEventBus>,
+    pool: Arc<DbConn>,
+    // 每次拉取的事件数
+    batch_size: u64,
+    // 轮询间隔
+    poll_interval: Duration,
+    // processing 超过这个时间视为投递进程已经挂掉，可以重新认领
+    lease: Duration,
+}
+
+impl OutboxDispatcher {
+    pub fn new(bus: Arc<AsyncEventBus>, pool: Arc<DbConn>) -> Self {
+        Self {
+            bus,
+            pool,
+            batch_size: 100,
+            poll_interval: Duration::from_secs(1),
+            lease: Duration::from_secs(300),
+        }
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(self.run())
+    }
+
+    pub async fn run(self) {
+        loop {
+            match self.dispatch_pending().await {
+                // 一批满了，说明可能还有积压，马上继续
+                Ok(count) if count as u64 >= self.batch_size => continue,
+                Ok(_) => {}
+                Err(err) => tracing::error!("outbox 投递失败: {}", err),
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(self.poll_interval) => {}
+                _ = self.bus.notified() => {}
+            }
+        }
+    }
+
+    // 投递一批到期的事件，返回处理的条数
+    pub async fn dispatch_pending(&self) -> Result<usize, DbErr> {
+        let event_types = self.bus.event_types();
+        if event_types.is_empty() {
+            return Ok(0);
+        }
+
+        let now = Utc::now();
+        let lease_expired = now - chrono::Duration::from_std(self.lease).unwrap_or_default();
+
+        let records = event_record::Entity::find()
+            .filter(event_record::Column::EventType.is_in(event_types))
+            .filter(
+                Condition::any()
+                    .add(event_record::Column::Status.eq(STATUS_PENDING))
+                    .add(
+                        Condition::all()
+                            .add(event_record::Column::Status.eq(STATUS_FAILED))
+                            .add(event_record::Column::NextRetryAt.lte(now)),
+                    )
+                    .add(
+                        Condition::all()
+                            .add(event_record::Column::Status.eq(STATUS_PROCESSING))
+                            .add(event_record::Column::UpdatedAt.lt(lease_expired)),
+                    ),
+            )
+            .order_by_asc(event_record::Column::CreatedAt)
+            .limit(self.batch_size)
+            .all(self.pool.as_ref())
+            .await?;
+
+        let count = records.len();
+        for record in records {
+            if !self.claim(&record).await? {
+                continue;
+            }
+
+            self.dispatch(record).await?;
+        }
+
+        Ok(count)
+    }
+
+    // 抢占事件，避免多个实例重复投递
+    async fn claim(&self, record: &event_record::Model) -> Result<bool, DbErr> {
+        let result = event_record::Entity::update_many()
+            .col_expr(event_record::Column::Status, Expr::value(STATUS_PROCESSING))
+            .col_expr(event_record::Column::UpdatedAt, Expr::value(Utc::now()))
+            .filter(event_record::Column::EventId.eq(record.event_id.clone()))
+            .filter(event_record::Column::Status.eq(record.status.clone()))
+            .filter(event_record::Column::UpdatedAt.eq(record.updated_at))
+            .exec(self.pool.as_ref())
+            .await?;
+
+        Ok(result.rows_affected == 1)
+    }
+
+    // 只执行还没有成功的监听器，每个监听器按自己的重试策略重试，结果记在 deliveries
+    async fn dispatch(&self, record: event_record::Model) -> Result<(), DbErr> {
+        let now = Utc::now();
+        let mut deliveries = Deliveries::from_json(record.deliveries.as_ref());
+        let envelope = EventEnvelope::from(record.clone());
+
+        let update = event_record::Entity::update_many()
+            .filter(event_record::Column::EventId.eq(record.event_id.clone()));
+
+        let update = match self
+            .bus
+            .deliver_to(envelope, |listener| deliveries.is_due(listener, now))
+            .await
+        {
+            Ok(outcomes) => {
+                let failed = outcomes.iter().any(|outcome| outcome.result.is_err());
+                deliveries.record(outcomes, now);
+                let (status, next_retry_at) = deliveries.status();
+                let last_error = deliveries.last_error();
+
+                match status {
+                    STATUS_DEAD_LETTER => tracing::error!(
+                        "事件 {} ({}) 投递失败次数用完，转入死信: {}",
+                        record.event_id,
+                        record.event_type,
+                        last_error.as_deref().unwrap_or_default()
+                    ),
+                    STATUS_FAILED if failed => tracing::warn!(
+                        "事件 {} ({}) 第 {} 次投递失败: {}",
+                        record.event_id,
+                        record.event_type,
+                        record.retry_count + 1,
+                        last_error.as_deref().unwrap_or_default()
+                    ),
+                    _ => {}
+                }
+
+                let update = if failed {
+                    update.col_expr(
+                        event_record::Column::RetryCount,
+                        Expr::value(record.retry_count + 1),
+                    )
+                } else {
+                    update
+                };
+
+                update
+                    .col_expr(event_record::Column::Status, Expr::value(status))
+                    .col_expr(
+                        event_record::Column::NextRetryAt,
+                        Expr::value(next_retry_at),
+                    )
+                    .col_expr(event_record::Column::LastError, Expr::value(last_error))
+                    .col_expr(
+                        event_record::Column::Deliveries,
+                        Expr::value(deliveries.to_json()),
+                    )
+            }
+            // 事件类型没有注册订阅者，按默认策略稍后重试
+            Err(err) => {
+                let retry_count = record.retry_count + 1;
+                let policy = RetryPolicy::default();
+                tracing::warn!(
+                    "事件 {} ({}) 第 {} 次投递失败: {}",
+                    record.event_id,
+                    record.event_type,
+                    retry_count,
+                    err
+                );
+
+                let (status, next_retry_at) = if policy.is_exhausted(retry_count) {
+                    (STATUS_DEAD_LETTER, None)
+                } else {
+                    (
+                        STATUS_FAILED,
+                        Some(
+                            now + chrono::Duration::from_std(policy.backoff(retry_count))
+                                .unwrap_or_default(),
+                        ),
+                    )
+                };
+
+                update
+                    .col_expr(event_record::Column::RetryCount, Expr::value(retry_count))
+                    .col_expr(event_record::Column::Status, Expr::value(status))
+                    .col_expr(
+                        event_record::Column::NextRetryAt,
+                        Expr::value(next_retry_at),
+                    )
+                    .col_expr(
+                        event_record::Column::LastError,
+                        Expr::value(format!("{:#}", err)),
+                    )
+            }
+        };
+
+        update
+            .col_expr(event_record::Column::UpdatedAt, Expr::value(Utc::now()))
+            .exec(self.pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+}
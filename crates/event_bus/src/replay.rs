@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, ConnectionTrait, DbConn, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect,
+};
+
+use crate::{entitiy::event_record, AsyncEventBus, EventEnvelope};
+
+// 按写入顺序回放事件，用来重建读模型。不区分投递状态：投递状态按监听器记录，
+// 其他监听器失败时事件仍是 failed / dead_letter，但投影可能已经应用过，只要写入了 event_record 事件就已经发生
+pub struct EventReplayer {
+    pool: Arc<DbConn>,
+    // 每次拉取的事件数
+    batch_size: u64,
+}
+
+impl EventReplayer {
+    pub fn new(pool: Arc<DbConn>) -> Self {
+        Self {
+            pool,
+            batch_size: 500,
+        }
+    }
+
+    // 需要回放的事件数，只统计 bus 上有订阅者的事件类型
+    pub async fn count(&self, bus: &AsyncEventBus) -> Result<u64, DbErr> {
+        event_record::Entity::find()
+            .filter(event_record::Column::EventType.is_in(bus.event_types()))
+            .count(self.pool.as_ref())
+            .await
+    }
+
+    // 有事件的聚合数，少于读模型的行数说明有数据早于事件流，回放会丢失
+    pub async fn count_aggregates(&self, bus: &AsyncEventBus) -> Result<u64, DbErr> {
+        let count = event_record::Entity::find()
+            .select_only()
+            .column_as(
+                Expr::col(event_record::Column::AggregateId).count_distinct(),
+                "count",
+            )
+            .filter(event_record::Column::EventType.is_in(bus.event_types()))
+            .into_tuple::<i64>()
+            .one(self.pool.as_ref())
+            .await?
+            .unwrap_or_default();
+
+        Ok(count as u64)
+    }
+
+    // 逐批回放，每批结束后回调 on_progress(已回放数)，遇到失败立即停止
+    pub async fn replay<F>(&self, bus: &AsyncEventBus, mut on_progress: F) -> anyhow::Result<u64>
+    where
+        F: FnMut(u64),
+    {
+        let event_types = bus.event_types();
+        let mut last_seq = 0;
+        let mut replayed = 0;
+
+        loop {
+            let records = event_record::Entity::find()
+                .filter(event_record::Column::EventType.is_in(event_types.clone()))
+                .filter(event_record::Column::Seq.gt(last_seq))
+                .order_by_asc(event_record::Column::Seq)
+                .limit(self.batch_size)
+                .all(self.pool.as_ref())
+                .await?;
+
+            let Some(last) = records.last() else {
+                break;
+            };
+            last_seq = last.seq;
+
+            for record in records {
+                let envelope = EventEnvelope::from(record);
+                let event_id = envelope.event_id.clone();
+                let event_type = envelope.event_type.clone();
+                bus.deliver(envelope).await.map_err(|err| {
+                    anyhow::anyhow!("回放事件 {} ({}) 失败: {}", event_id, event_type, err)
+                })?;
+                replayed += 1;
+            }
+
+            on_progress(replayed);
+        }
+
+        Ok(replayed)
+    }
+}
+
+// 重建前备份读模型，回放失败时换回原表
+pub struct TableBackup {
+    table: String,
+    backup: String,
+}
+
+impl TableBackup {
+    pub async fn create(pool: &DbConn, table: &str) -> Result<Self, DbErr> {
+        let backup = format!("{}_rebuild_backup", table);
+        pool.execute_unprepared(&format!("DROP TABLE IF EXISTS `{backup}`"))
+            .await?;
+        pool.execute_unprepared(&format!("CREATE TABLE `{backup}` LIKE `{table}`"))
+            .await?;
+        pool.execute_unprepared(&format!("INSERT INTO `{backup}` SELECT * FROM `{table}`"))
+            .await?;
+
+        Ok(Self {
+            table: table.to_string(),
+            backup,
+        })
+    }
+
+    // 回放失败：原子地把备份换回原表，丢弃回放了一半的数据
+    pub async fn restore(self, pool: &DbConn) -> Result<(), DbErr> {
+        let Self { table, backup } = self;
+        let failed = format!("{}_rebuild_failed", table);
+        pool.execute_unprepared(&format!("DROP TABLE IF EXISTS `{failed}`"))
+            .await?;
+        pool.execute_unprepared(&format!(
+            "RENAME TABLE `{table}` TO `{failed}`, `{backup}` TO `{table}`"
+        ))
+        .await?;
+        pool.execute_unprepared(&format!("DROP TABLE `{failed}`"))
+            .await?;
+
+        Ok(())
+    }
+
+    // 回放成功后删除备份
+    pub async fn drop(self, pool: &DbConn) -> Result<(), DbErr> {
+        pool.execute_unprepared(&format!("DROP TABLE `{}`", self.backup))
+            .await?;
+        Ok(())
+    }
+}
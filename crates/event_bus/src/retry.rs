@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+// 监听器的重试策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    // 最多投递次数（含第一次），用完后进入死信
+    pub max_attempts: i32,
+    // 第一次重试的等待时间，之后指数增长
+    pub base_backoff: Duration,
+    // 最长等待时间
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(300),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: i32, base_backoff: Duration, max_backoff: Duration) -> Self {
+        Self {
+            max_attempts,
+            base_backoff,
+            max_backoff,
+        }
+    }
+
+    // 第 attempt 次失败后的等待时间: base, base*2, base*4 ... 不超过 max_backoff
+    pub fn backoff(&self, attempt: i32) -> Duration {
+        let exp = attempt.clamp(1, 16) as u32 - 1;
+        self.base_backoff
+            .saturating_mul(1 << exp)
+            .min(self.max_backoff)
+    }
+
+    // 失败次数是否已用完
+    pub fn is_exhausted(&self, attempt: i32) -> bool {
+        attempt >= self.max_attempts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_test() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(5), Duration::from_secs(16));
+        assert_eq!(policy.backoff(100), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn exhausted_test() {
+        let policy = RetryPolicy::new(3, Duration::from_secs(1), Duration::from_secs(10));
+        assert!(!policy.is_exhausted(2));
+        assert!(policy.is_exhausted(3));
+    }
+}
@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue::Set, ColumnTrait, DbConn, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    entitiy::{aggregate_snapshot, event_record},
+    stream::load_stream,
+    Event, EventEnvelope,
+};
+
+// 可以由事件流重建的聚合
+pub trait Aggregate: Serialize + DeserializeOwned + Send + Sync {
+    type Event: Event;
+
+    // 尚未应用任何事件时的状态
+    fn initial(aggregate_id: &str) -> Self;
+
+    fn apply(&mut self, event: &EventEnvelope<Self::Event>);
+}
+
+// 聚合快照：保存最新快照之后超过 interval 个事件时写入新快照，
+// 加载时从最新快照开始只回放之后的事件
+pub struct SnapshotStore {
+    pool: Arc<DbConn>,
+    interval: i64,
+}
+
+impl SnapshotStore {
+    pub fn new(pool: Arc<DbConn>) -> Self {
+        Self {
+            pool,
+            interval: 100,
+        }
+    }
+
+    pub fn interval(mut self, interval: i64) -> Self {
+        self.interval = interval.max(1);
+        self
+    }
+
+    // 最新快照 + 之后的事件，聚合没有任何事件时返回 None
+    pub async fn load<A: Aggregate>(&self, aggregate_id: &str) -> anyhow::Result<Option<A>> {
+        let snapshot = aggregate_snapshot::Entity::find()
+            .filter(aggregate_snapshot::Column::AggregateType.eq(A::Event::AGGREGATE_TYPE))
+            .filter(aggregate_snapshot::Column::AggregateId.eq(aggregate_id))
+            .order_by_desc(aggregate_snapshot::Column::Version)
+            .one(self.pool.as_ref())
+            .await?;
+
+        let has_snapshot = snapshot.is_some();
+        let (mut aggregate, mut version, mut last_seq) = match snapshot {
+            Some(snapshot) => (
+                serde_json::from_value::<A>(snapshot.state)?,
+                snapshot.version,
+                snapshot.last_seq,
+            ),
+            None => (A::initial(aggregate_id), 0, 0),
+        };
+
+        let events = load_stream::<A::Event, _>(self.pool.as_ref(), aggregate_id, last_seq).await?;
+        if !has_snapshot && events.is_empty() {
+            return Ok(None);
+        }
+
+        let replayed = events.len() as i64;
+        for event in &events {
+            aggregate.apply(event);
+            last_seq = event.sequence;
+        }
+        version += replayed;
+
+        if replayed >= self.interval {
+            self.save(aggregate_id, version, last_seq, &aggregate)
+                .await?;
+        }
+
+        Ok(Some(aggregate))
+    }
+
+    // 为早于事件流的聚合写入版本为 0 的基线快照，之后的事件在它之上回放
+    pub async fn seed<A: Aggregate>(
+        &self,
+        aggregate_id: &str,
+        aggregate: &A,
+    ) -> anyhow::Result<()> {
+        self.save(aggregate_id, 0, 0, aggregate).await
+    }
+
+    // 丢弃已有快照（保留基线快照），从完整事件流为每个聚合重新生成一个快照，返回处理的聚合数
+    pub async fn rebuild<A: Aggregate, F>(&self, mut on_progress: F) -> anyhow::Result<u64>
+    where
+        F: FnMut(u64, u64),
+    {
+        let aggregate_ids = event_record::Entity::find()
+            .select_only()
+            .column(event_record::Column::AggregateId)
+            .distinct()
+            .filter(event_record::Column::EventType.eq(A::Event::NAME))
+            .filter(event_record::Column::AggregateType.eq(A::Event::AGGREGATE_TYPE))
+            .into_tuple::<String>()
+            .all(self.pool.as_ref())
+            .await?;
+
+        aggregate_snapshot::Entity::delete_many()
+            .filter(aggregate_snapshot::Column::AggregateType.eq(A::Event::AGGREGATE_TYPE))
+            .filter(aggregate_snapshot::Column::Version.gt(0))
+            .exec(self.pool.as_ref())
+            .await?;
+
+        let total = aggregate_ids.len() as u64;
+        let mut rebuilt = 0;
+        for aggregate_id in aggregate_ids {
+            let events = load_stream::<A::Event, _>(self.pool.as_ref(), &aggregate_id, 0).await?;
+            let Some(last) = events.last() else {
+                continue;
+            };
+            let last_seq = last.sequence;
+
+            // 有基线快照的从基线开始回放
+            let baseline = aggregate_snapshot::Entity::find_by_id((
+                A::Event::AGGREGATE_TYPE.to_string(),
+                aggregate_id.clone(),
+                0,
+            ))
+            .one(self.pool.as_ref())
+            .await?;
+            let mut aggregate = match baseline {
+                Some(baseline) => serde_json::from_value::<A>(baseline.state)?,
+                None => A::initial(&aggregate_id),
+            };
+            for event in &events {
+                aggregate.apply(event);
+            }
+
+            self.save(&aggregate_id, events.len() as i64, last_seq, &aggregate)
+                .await?;
+            rebuilt += 1;
+            on_progress(rebuilt, total);
+        }
+
+        Ok(rebuilt)
+    }
+
+    // 每个聚合只保留最新的快照和基线快照，返回删除的快照数
+    pub async fn compact<A: Aggregate>(&self) -> anyhow::Result<u64> {
+        let snapshots = aggregate_snapshot::Entity::find()
+            .select_only()
+            .column(aggregate_snapshot::Column::AggregateId)
+            .column(aggregate_snapshot::Column::Version)
+            .filter(aggregate_snapshot::Column::AggregateType.eq(A::Event::AGGREGATE_TYPE))
+            .filter(aggregate_snapshot::Column::Version.gt(0))
+            .order_by_asc(aggregate_snapshot::Column::AggregateId)
+            .order_by_desc(aggregate_snapshot::Column::Version)
+            .into_tuple::<(String, i64)>()
+            .all(self.pool.as_ref())
+            .await?;
+
+        let mut deleted = 0;
+        let mut latest: Option<String> = None;
+        for (aggregate_id, version) in snapshots {
+            // 按版本倒序，每个聚合的第一条是最新快照
+            if latest.as_deref() != Some(aggregate_id.as_str()) {
+                latest = Some(aggregate_id);
+                continue;
+            }
+
+            deleted += aggregate_snapshot::Entity::delete_many()
+                .filter(aggregate_snapshot::Column::AggregateType.eq(A::Event::AGGREGATE_TYPE))
+                .filter(aggregate_snapshot::Column::AggregateId.eq(aggregate_id))
+                .filter(aggregate_snapshot::Column::Version.eq(version))
+                .exec(self.pool.as_ref())
+                .await?
+                .rows_affected;
+        }
+
+        Ok(deleted)
+    }
+
+    async fn save<A: Aggregate>(
+        &self,
+        aggregate_id: &str,
+        version: i64,
+        last_seq: i64,
+        aggregate: &A,
+    ) -> anyhow::Result<()> {
+        let model = aggregate_snapshot::ActiveModel {
+            aggregate_type: Set(A::Event::AGGREGATE_TYPE.to_string()),
+            aggregate_id: Set(aggregate_id.to_string()),
+            version: Set(version),
+            last_seq: Set(last_seq),
+            state: Set(serde_json::to_value(aggregate)?),
+            created_at: Set(chrono::Utc::now()),
+        };
+
+        // 并发加载可能同时写入同一版本的快照，内容相同，忽略即可
+        aggregate_snapshot::Entity::insert(model)
+            .on_conflict(
+                OnConflict::columns([
+                    aggregate_snapshot::Column::AggregateType,
+                    aggregate_snapshot::Column::AggregateId,
+                    aggregate_snapshot::Column::Version,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(self.pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ColumnTrait, Condition, DbConn, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+use shared_dto::table_data::{TableDataRequest, TableDataResponse};
+
+use crate::entitiy::event_record;
+
+// 浏览 event_record 的过滤条件，为空的条件不参与过滤，时间范围按事件发生时间
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct EventQuery {
+    pub event_type: Option<String>,
+    pub aggregate_type: Option<String>,
+    pub aggregate_id: Option<String>,
+    pub actor_id: Option<String>,
+    pub correlation_id: Option<String>,
+    pub status: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl EventQuery {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from > to {
+                return Err(anyhow::anyhow!("开始时间不能晚于结束时间"));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn condition(&self) -> Condition {
+        let mut condition = Condition::all();
+
+        if let Some(event_type) = &self.event_type {
+            condition = condition.add(event_record::Column::EventType.eq(event_type));
+        }
+        if let Some(aggregate_type) = &self.aggregate_type {
+            condition = condition.add(event_record::Column::AggregateType.eq(aggregate_type));
+        }
+        if let Some(aggregate_id) = &self.aggregate_id {
+            condition = condition.add(event_record::Column::AggregateId.eq(aggregate_id));
+        }
+        if let Some(actor_id) = &self.actor_id {
+            condition = condition.add(event_record::Column::ActorId.eq(actor_id));
+        }
+        if let Some(correlation_id) = &self.correlation_id {
+            condition = condition.add(event_record::Column::CorrelationId.eq(correlation_id));
+        }
+        if let Some(status) = &self.status {
+            condition = condition.add(event_record::Column::Status.eq(status));
+        }
+        if let Some(from) = self.from {
+            condition = condition.add(event_record::Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = self.to {
+            condition = condition.add(event_record::Column::CreatedAt.lte(to));
+        }
+
+        condition
+    }
+}
+
+// 给人看的事件记录，payload 格式化为多行 JSON
+#[derive(Debug, Clone, Serialize)]
+pub struct EventRecordView {
+    pub event_id: String,
+    pub seq: i64,
+    pub event_type: String,
+    pub aggregate_type: String,
+    pub aggregate_id: String,
+    pub actor_id: Option<String>,
+    pub correlation_id: Option<String>,
+    pub causation_id: Option<String>,
+    pub schema_version: i32,
+    pub status: String,
+    pub retry_count: i32,
+    pub last_error: Option<String>,
+    // 各监听器的投递结果
+    pub deliveries: Option<serde_json::Value>,
+    pub payload: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<event_record::Model> for EventRecordView {
+    fn from(record: event_record::Model) -> Self {
+        Self {
+            payload: serde_json::to_string_pretty(&record.payload)
+                .unwrap_or_else(|_| record.payload.to_string()),
+            event_id: record.event_id,
+            seq: record.seq,
+            event_type: record.event_type,
+            aggregate_type: record.aggregate_type,
+            aggregate_id: record.aggregate_id,
+            actor_id: record.actor_id,
+            correlation_id: record.correlation_id,
+            causation_id: record.causation_id,
+            schema_version: record.schema_version,
+            status: record.status,
+            retry_count: record.retry_count,
+            last_error: record.last_error,
+            deliveries: record.deliveries,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+        }
+    }
+}
+
+// 按条件分页浏览事件，最新的在前
+pub async fn list_events(
+    pool: &DbConn,
+    query: &EventQuery,
+    page: u64,
+    page_size: u64,
+) -> Result<(Vec<EventRecordView>, u64), DbErr> {
+    let paginator = event_record::Entity::find()
+        .filter(query.condition())
+        .order_by_desc(event_record::Column::Seq)
+        .paginate(pool, page_size.clamp(1, 1000));
+
+    let total = paginator.num_items().await?;
+    let data = paginator.fetch_page(page.max(1) - 1).await?;
+
+    Ok((data.into_iter().map(EventRecordView::from).collect(), total))
+}
+
+pub async fn find_event(pool: &DbConn, event_id: &str) -> Result<Option<EventRecordView>, DbErr> {
+    Ok(event_record::Entity::find_by_id(event_id)
+        .one(pool)
+        .await?
+        .map(EventRecordView::from))
+}
+
+// 浏览 event_record，排查聚合为什么发生了变化，各服务的事件接口共用
+pub struct EventStoreService {
+    pool: Arc<DbConn>,
+}
+
+impl EventStoreService {
+    pub fn new(pool: Arc<DbConn>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_all(
+        &self,
+        query: EventQuery,
+        data: TableDataRequest,
+    ) -> anyhow::Result<TableDataResponse<EventRecordView>> {
+        query.validate()?;
+
+        let (list, total) =
+            list_events(self.pool.as_ref(), &query, data.page, data.page_size).await?;
+
+        Ok(TableDataResponse::new(list, total))
+    }
+
+    pub async fn find_by_id(&self, event_id: &str) -> anyhow::Result<EventRecordView> {
+        find_event(self.pool.as_ref(), event_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("事件不存在"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::{EventQuery, EventRecordView};
+    use crate::entitiy::event_record;
+
+    #[test]
+    fn test_pretty_payload_and_time_range() {
+        let now = Utc::now();
+        let view = EventRecordView::from(event_record::Model {
+            event_id: "e1".to_string(),
+            seq: 1,
+            event_type: "HouseEvent".to_string(),
+            aggregate_type: "house".to_string(),
+            aggregate_id: "h1".to_string(),
+            actor_id: None,
+            correlation_id: None,
+            causation_id: None,
+            schema_version: 1,
+            payload: serde_json::json!({"Deleted": {"id": "h1"}}),
+            status: "published".to_string(),
+            retry_count: 0,
+            next_retry_at: None,
+            last_error: None,
+            deliveries: None,
+            created_at: now,
+            updated_at: now,
+        });
+        assert_eq!(
+            view.payload,
+            "{\n  \"Deleted\": {\n    \"id\": \"h1\"\n  }\n}"
+        );
+
+        let query = EventQuery {
+            from: Some(now),
+            to: Some(now - Duration::hours(1)),
+            ..Default::default()
+        };
+        assert!(query.validate().is_err());
+    }
+}
@@ -0,0 +1,45 @@
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{entitiy::event_record, upcast::UpcasterRegistry, Event, EventEnvelope};
+
+// 按 seq 顺序读取某个聚合的事件流，用于从事件重建聚合。
+// 不区分投递状态：只要写入了 event_record，事件就已经发生。
+pub async fn load_stream<T: Event, C: ConnectionTrait>(
+    conn: &C,
+    aggregate_id: &str,
+    after_seq: i64,
+) -> anyhow::Result<Vec<EventEnvelope<T>>> {
+    let records = event_record::Entity::find()
+        .filter(event_record::Column::EventType.eq(T::NAME))
+        .filter(event_record::Column::AggregateType.eq(T::AGGREGATE_TYPE))
+        .filter(event_record::Column::AggregateId.eq(aggregate_id))
+        .filter(event_record::Column::Seq.gt(after_seq))
+        .order_by_asc(event_record::Column::Seq)
+        .all(conn)
+        .await?;
+
+    let mut upcasters = UpcasterRegistry::default();
+    for upcaster in T::upcasters() {
+        upcasters.register(T::NAME, upcaster);
+    }
+
+    records
+        .into_iter()
+        .map(|record| decode(&upcasters, EventEnvelope::from(record)))
+        .collect()
+}
+
+// 把落库的 payload 升级到当前版本后反序列化
+fn decode<T: Event>(
+    upcasters: &UpcasterRegistry,
+    envelope: EventEnvelope<serde_json::Value>,
+) -> anyhow::Result<EventEnvelope<T>> {
+    let schema_version = envelope.schema_version;
+    let event_id = envelope.event_id.clone();
+
+    envelope.try_map(|payload| {
+        let payload = upcasters.upcast(T::NAME, schema_version, T::VERSION, payload)?;
+        serde_json::from_value::<T>(payload)
+            .map_err(|err| anyhow::anyhow!("解析事件 {} 失败: {}", event_id, err))
+    })
+}
@@ -0,0 +1,101 @@
+use std::{
+    any::Any,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use futures::future::join_all;
+
+use crate::{context::EventContext, HandleFuture, RetryPolicy};
+
+pub(crate) type AsyncCallbackBox = Box<dyn Fn(Box<dyn Any + Send>) -> HandleFuture + Send + Sync>;
+
+// 订阅选项
+#[derive(Debug, Clone)]
+pub struct SubscriberOptions {
+    // 日志里显示的监听器名
+    pub name: String,
+    // 单次执行的超时时间，超时算作失败
+    pub timeout: Duration,
+    pub retry_policy: RetryPolicy,
+}
+
+impl Default for SubscriberOptions {
+    fn default() -> Self {
+        Self {
+            name: "anonymous".to_string(),
+            timeout: Duration::from_secs(30),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+pub(crate) struct Subscriber {
+    pub options: SubscriberOptions,
+    pub callback: AsyncCallbackBox,
+}
+
+// 为每个订阅者生成带超时和耗时日志的 future，错误信息带上监听器名，调用方已经释放了订阅者列表的锁
+pub(crate) fn prepare(
+    subscribers: Vec<(Arc<Subscriber>, Box<dyn Any + Send>)>,
+    event_type: &str,
+    context: &EventContext,
+) -> Vec<HandleFuture> {
+    subscribers
+        .into_iter()
+        .map(|(subscriber, event)| {
+            let name = subscriber.options.name.clone();
+            let future = run(subscriber, event, event_type.to_string(), context.clone());
+            Box::pin(async move {
+                future
+                    .await
+                    .map_err(|err| anyhow::anyhow!("{}: {}", name, err))
+            }) as HandleFuture
+        })
+        .collect()
+}
+
+// 订阅者在事件的上下文中执行，它再产生的事件会记录 causation_id
+pub(crate) async fn run(
+    subscriber: Arc<Subscriber>,
+    event: Box<dyn Any + Send>,
+    event_type: String,
+    context: EventContext,
+) -> anyhow::Result<()> {
+    let name = &subscriber.options.name;
+    let timeout = subscriber.options.timeout;
+    let started = Instant::now();
+    let future = (subscriber.callback)(event);
+
+    let result = match tokio::time::timeout(timeout, context.scope(future)).await {
+        Ok(result) => result,
+        Err(_) => Err(anyhow::anyhow!("执行超过 {:?}", timeout)),
+    };
+
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    match &result {
+        Ok(()) => {
+            tracing::debug!(listener = %name, event_type = %event_type, elapsed_ms, "监听器执行完成")
+        }
+        Err(err) => {
+            tracing::warn!(listener = %name, event_type = %event_type, elapsed_ms, error = %err, "监听器执行失败")
+        }
+    }
+
+    result
+}
+
+// 并发执行所有订阅者，收集所有失败
+pub(crate) async fn join(futures: Vec<HandleFuture>) -> anyhow::Result<()> {
+    let errors: Vec<String> = join_all(futures)
+        .await
+        .into_iter()
+        .filter_map(|result| result.err().map(|err| err.to_string()))
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(errors.join("; ")))
+    }
+}
@@ -0,0 +1,390 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::Utc;
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue::Set, ColumnTrait, DbConn, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+use serde_json::Value;
+use tokio::task::JoinHandle;
+
+use crate::{
+    dead_letter,
+    entitiy::{event_consumer_offset, event_record},
+    outbox::{STATUS_DEAD_LETTER, STATUS_FAILED},
+    AsyncEventBus, Deliveries, EventEnvelope, OutboxEvent,
+};
+
+// 跨服务传递事件，每个消费者记录自己的消费位置，至少投递一次
+#[async_trait::async_trait]
+pub trait EventTransport: Send + Sync {
+    // 发送事件
+    async fn send(&self, events: &[OutboxEvent]) -> anyhow::Result<()>;
+
+    // 拉取 consumer 尚未确认的事件，按 sequence 升序
+    async fn poll(
+        &self,
+        consumer: &str,
+        event_types: &[String],
+        limit: u64,
+    ) -> anyhow::Result<Vec<EventEnvelope<Value>>>;
+
+    // 确认 consumer 已处理到 sequence
+    async fn ack(&self, consumer: &str, sequence: i64) -> anyhow::Result<()>;
+
+    // 重试次数用完的事件转为死信，不确认，consumer 停在这个事件直到重新投递或跳过
+    async fn dead_letter(
+        &self,
+        consumer: &str,
+        event: &EventEnvelope<Value>,
+        error: &str,
+    ) -> anyhow::Result<()>;
+
+    // consumer 停住的死信事件 id
+    async fn dead_letter_of(&self, consumer: &str) -> anyhow::Result<Option<String>>;
+
+    // 处理死信：skip 为 true 时跳过该事件，否则重新投递，返回是否有死信
+    async fn resolve_dead_letter(&self, consumer: &str, skip: bool) -> anyhow::Result<bool>;
+}
+
+// 进程内实现，测试和单体部署用
+#[derive(Default)]
+pub struct InMemoryTransport {
+    events: Mutex<Vec<EventEnvelope<Value>>>,
+    offsets: Mutex<HashMap<String, i64>>,
+    // consumer -> (死信事件 id, sequence)
+    dead_letters: Mutex<HashMap<String, (String, i64)>>,
+}
+
+impl InMemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl EventTransport for InMemoryTransport {
+    async fn send(&self, events: &[OutboxEvent]) -> anyhow::Result<()> {
+        let mut stored = self.events.lock().unwrap();
+        for event in events {
+            let mut event = event.clone();
+            event.sequence = stored.len() as i64 + 1;
+            stored.push(event);
+        }
+
+        Ok(())
+    }
+
+    async fn poll(
+        &self,
+        consumer: &str,
+        event_types: &[String],
+        limit: u64,
+    ) -> anyhow::Result<Vec<EventEnvelope<Value>>> {
+        let offset = self
+            .offsets
+            .lock()
+            .unwrap()
+            .get(consumer)
+            .copied()
+            .unwrap_or_default();
+
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.sequence > offset && event_types.contains(&event.event_type))
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn ack(&self, consumer: &str, sequence: i64) -> anyhow::Result<()> {
+        let mut offsets = self.offsets.lock().unwrap();
+        let offset = offsets.entry(consumer.to_string()).or_default();
+        *offset = (*offset).max(sequence);
+
+        Ok(())
+    }
+
+    async fn dead_letter(
+        &self,
+        consumer: &str,
+        event: &EventEnvelope<Value>,
+        _error: &str,
+    ) -> anyhow::Result<()> {
+        self.dead_letters.lock().unwrap().insert(
+            consumer.to_string(),
+            (event.event_id.clone(), event.sequence),
+        );
+
+        Ok(())
+    }
+
+    async fn dead_letter_of(&self, consumer: &str) -> anyhow::Result<Option<String>> {
+        Ok(self
+            .dead_letters
+            .lock()
+            .unwrap()
+            .get(consumer)
+            .map(|(event_id, _)| event_id.clone()))
+    }
+
+    async fn resolve_dead_letter(&self, consumer: &str, skip: bool) -> anyhow::Result<bool> {
+        let Some((_, sequence)) = self.dead_letters.lock().unwrap().remove(consumer) else {
+            return Ok(false);
+        };
+        if skip {
+            self.ack(consumer, sequence).await?;
+        }
+
+        Ok(true)
+    }
+}
+
+// 轮询另一个服务的 event_record，消费位置记在本服务的 event_consumer_offset
+pub struct MysqlPollingTransport {
+    // event_record 所在的库
+    source: Arc<DbConn>,
+    // event_consumer_offset 所在的库，可以和 source 相同
+    offsets: Arc<DbConn>,
+    // seq 在插入时分配、提交时才可见，只读取写入一段时间后的事件，避免跳过晚提交的事务
+    settle: Duration,
+}
+
+impl MysqlPollingTransport {
+    pub fn new(source: Arc<DbConn>, offsets: Arc<DbConn>) -> Self {
+        Self {
+            source,
+            offsets,
+            settle: Duration::from_secs(5),
+        }
+    }
+
+    async fn offset(&self, consumer: &str) -> anyhow::Result<i64> {
+        let offset = event_consumer_offset::Entity::find_by_id(consumer.to_string())
+            .one(self.offsets.as_ref())
+            .await?
+            .map(|model| model.last_seq)
+            .unwrap_or_default();
+
+        Ok(offset)
+    }
+}
+
+#[async_trait::async_trait]
+impl EventTransport for MysqlPollingTransport {
+    // 写入 event_record，通常由 outbox 在业务事务里完成
+    async fn send(&self, events: &[OutboxEvent]) -> anyhow::Result<()> {
+        OutboxEvent::append(self.source.as_ref(), events).await?;
+        Ok(())
+    }
+
+    async fn poll(
+        &self,
+        consumer: &str,
+        event_types: &[String],
+        limit: u64,
+    ) -> anyhow::Result<Vec<EventEnvelope<Value>>> {
+        let offset = self.offset(consumer).await?;
+        let settled_at = Utc::now() - chrono::Duration::from_std(self.settle)?;
+
+        let records = event_record::Entity::find()
+            .filter(event_record::Column::Seq.gt(offset))
+            .filter(event_record::Column::EventType.is_in(event_types.to_vec()))
+            .filter(event_record::Column::CreatedAt.lte(settled_at))
+            .order_by_asc(event_record::Column::Seq)
+            .limit(limit)
+            .all(self.source.as_ref())
+            .await?;
+
+        Ok(records.into_iter().map(EventEnvelope::from).collect())
+    }
+
+    async fn ack(&self, consumer: &str, sequence: i64) -> anyhow::Result<()> {
+        let model = event_consumer_offset::ActiveModel {
+            consumer: Set(consumer.to_string()),
+            last_seq: Set(sequence),
+            updated_at: Set(Utc::now()),
+            ..Default::default()
+        };
+
+        event_consumer_offset::Entity::insert(model)
+            .on_conflict(
+                OnConflict::column(event_consumer_offset::Column::Consumer)
+                    .update_columns([
+                        event_consumer_offset::Column::LastSeq,
+                        event_consumer_offset::Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(self.offsets.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    async fn dead_letter(
+        &self,
+        consumer: &str,
+        event: &EventEnvelope<Value>,
+        error: &str,
+    ) -> anyhow::Result<()> {
+        let model = event_consumer_offset::ActiveModel {
+            consumer: Set(consumer.to_string()),
+            last_seq: Set(self.offset(consumer).await?),
+            dead_letter_event_id: Set(Some(event.event_id.clone())),
+            dead_letter_seq: Set(Some(event.sequence)),
+            last_error: Set(Some(error.to_string())),
+            updated_at: Set(Utc::now()),
+        };
+
+        event_consumer_offset::Entity::insert(model)
+            .on_conflict(
+                OnConflict::column(event_consumer_offset::Column::Consumer)
+                    .update_columns([
+                        event_consumer_offset::Column::DeadLetterEventId,
+                        event_consumer_offset::Column::DeadLetterSeq,
+                        event_consumer_offset::Column::LastError,
+                        event_consumer_offset::Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(self.offsets.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    async fn dead_letter_of(&self, consumer: &str) -> anyhow::Result<Option<String>> {
+        Ok(
+            event_consumer_offset::Entity::find_by_id(consumer.to_string())
+                .one(self.offsets.as_ref())
+                .await?
+                .and_then(|model| model.dead_letter_event_id),
+        )
+    }
+
+    async fn resolve_dead_letter(&self, consumer: &str, skip: bool) -> anyhow::Result<bool> {
+        Ok(
+            dead_letter::resolve_consumer_dead_letter(self.offsets.as_ref(), consumer, skip)
+                .await?,
+        )
+    }
+}
+
+// 从 transport 拉取事件交给 bus 上的订阅者，处理成功后确认，重试次数用完的事件转为死信并停在那里
+pub struct TransportConsumer {
+    name: String,
+    transport: Arc<dyn EventTransport>,
+    bus: Arc<AsyncEventBus>,
+    // 每次拉取的事件数
+    batch_size: u64,
+    // 没有新事件时的轮询间隔
+    poll_interval: Duration,
+}
+
+impl TransportConsumer {
+    pub fn new(
+        name: impl Into<String>,
+        transport: Arc<dyn EventTransport>,
+        bus: Arc<AsyncEventBus>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            transport,
+            bus,
+            batch_size: 100,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    pub async fn run(&self) {
+        loop {
+            match self.consume_pending().await {
+                Ok(0) => tokio::time::sleep(self.poll_interval).await,
+                Ok(_) => {}
+                Err(err) => {
+                    tracing::error!("消费者 {} 拉取事件失败: {}", self.name, err);
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+            }
+        }
+    }
+
+    // 处理一批事件，返回处理的数量；有死信时停在死信事件，不再处理后面的事件
+    pub async fn consume_pending(&self) -> anyhow::Result<usize> {
+        if let Some(event_id) = self.transport.dead_letter_of(&self.name).await? {
+            tracing::debug!("消费者 {} 停在死信事件 {}，等待处理", self.name, event_id);
+            return Ok(0);
+        }
+
+        let events = self
+            .transport
+            .poll(&self.name, &self.bus.event_types(), self.batch_size)
+            .await?;
+
+        let mut count = 0;
+        for event in events {
+            if let Some(error) = self.deliver(event.clone()).await? {
+                tracing::error!(
+                    "消费者 {} 处理事件 {} ({}) 次数用完仍失败，转入死信: {}",
+                    self.name,
+                    event.event_id,
+                    event.event_type,
+                    error
+                );
+                self.transport
+                    .dead_letter(&self.name, &event, &error)
+                    .await?;
+                break;
+            }
+
+            self.transport.ack(&self.name, event.sequence).await?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    // 失败的监听器按各自的重试策略重试，已经成功的不再执行；次数用完后返回错误信息
+    async fn deliver(&self, event: EventEnvelope<Value>) -> anyhow::Result<Option<String>> {
+        let mut deliveries = Deliveries::default();
+
+        loop {
+            let now = Utc::now();
+            let outcomes = self
+                .bus
+                .deliver_to(event.clone(), |listener| deliveries.is_due(listener, now))
+                .await?;
+            deliveries.record(outcomes, now);
+
+            let (status, next_retry_at) = deliveries.status();
+            let last_error = deliveries.last_error().unwrap_or_default();
+            match (status, next_retry_at) {
+                (STATUS_FAILED, Some(next_retry_at)) => {
+                    tracing::warn!(
+                        "消费者 {} 处理事件 {} ({}) 失败: {}",
+                        self.name,
+                        event.event_id,
+                        event.event_type,
+                        last_error
+                    );
+                    let wait = (next_retry_at - Utc::now()).to_std().unwrap_or_default();
+                    tokio::time::sleep(wait).await;
+                }
+                (STATUS_DEAD_LETTER, _) => return Ok(Some(last_error)),
+                _ => return Ok(None),
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+// 把某个版本的 payload 升级到下一个版本
+pub type UpcastFn = fn(Value) -> anyhow::Result<Value>;
+
+#[derive(Clone, Copy)]
+pub struct Upcaster {
+    // 输入的版本，输出为 from_version + 1
+    pub from_version: i32,
+    pub upcast: UpcastFn,
+}
+
+impl Upcaster {
+    pub fn new(from_version: i32, upcast: UpcastFn) -> Self {
+        Self {
+            from_version,
+            upcast,
+        }
+    }
+}
+
+// 事件名 + 版本 -> 升级函数
+#[derive(Default)]
+pub struct UpcasterRegistry {
+    upcasters: HashMap<(String, i32), UpcastFn>,
+}
+
+impl UpcasterRegistry {
+    pub fn register(&mut self, event_type: &str, upcaster: Upcaster) {
+        self.upcasters.insert(
+            (event_type.to_string(), upcaster.from_version),
+            upcaster.upcast,
+        );
+    }
+
+    // 逐个版本升级到 to_version
+    pub fn upcast(
+        &self,
+        event_type: &str,
+        from_version: i32,
+        to_version: i32,
+        mut payload: Value,
+    ) -> anyhow::Result<Value> {
+        if from_version > to_version {
+            return Err(anyhow::anyhow!(
+                "事件 {} 的版本 {} 高于当前版本 {}",
+                event_type,
+                from_version,
+                to_version
+            ));
+        }
+
+        for version in from_version..to_version {
+            let upcast = self
+                .upcasters
+                .get(&(event_type.to_string(), version))
+                .ok_or_else(|| {
+                    anyhow::anyhow!("事件 {} 缺少 v{} 的升级函数", event_type, version)
+                })?;
+            payload = upcast(payload)?;
+        }
+
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn rename_name(mut payload: Value) -> anyhow::Result<Value> {
+        let name = payload["name"].take();
+        payload["title"] = name;
+        payload.as_object_mut().unwrap().remove("name");
+        Ok(payload)
+    }
+
+    fn add_tags(mut payload: Value) -> anyhow::Result<Value> {
+        payload["tags"] = json!([]);
+        Ok(payload)
+    }
+
+    #[test]
+    fn upcast_test() {
+        let mut registry = UpcasterRegistry::default();
+        registry.register("HouseEvent", Upcaster::new(1, rename_name));
+        registry.register("HouseEvent", Upcaster::new(2, add_tags));
+
+        let payload = registry
+            .upcast("HouseEvent", 1, 3, json!({"name": "a"}))
+            .unwrap();
+        assert_eq!(payload, json!({"title": "a", "tags": []}));
+
+        // 当前版本不需要升级
+        let payload = registry
+            .upcast("HouseEvent", 3, 3, json!({"title": "b"}))
+            .unwrap();
+        assert_eq!(payload, json!({"title": "b"}));
+
+        assert!(registry.upcast("OwnerEvent", 1, 2, json!({})).is_err());
+        assert!(registry.upcast("HouseEvent", 4, 3, json!({})).is_err());
+    }
+}
@@ -0,0 +1,4 @@
+-- Add down migration script here
+UPDATE `event_record` SET `event_type` = 'domus::domain::house::events::house::HouseEvent' WHERE `event_type` = 'HouseEvent';
+UPDATE `event_record` SET `event_type` = 'domus::domain::owner::events::owner::OwnerEvent' WHERE `event_type` = 'OwnerEvent';
+UPDATE `event_record` SET `event_type` = 'domus::domain::community::events::community::CommunityEvent' WHERE `event_type` = 'CommunityEvent';
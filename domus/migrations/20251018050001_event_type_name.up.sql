@@ -0,0 +1,33 @@
+-- Add up migration script here
+-- 事件名从 Rust 类型路径改为稳定名称，并补齐已有事件的聚合 id
+UPDATE `event_record`
+SET `event_type` = 'HouseEvent',
+    `aggregate_type` = 'house',
+    `aggregate_id` = COALESCE(
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.Created.id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.Updated.id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.Published.house_id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.Unpublished.id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.Deleted.id')),
+      '')
+WHERE `event_type` = 'domus::domain::house::events::house::HouseEvent';
+
+UPDATE `event_record`
+SET `event_type` = 'OwnerEvent',
+    `aggregate_type` = 'owner',
+    `aggregate_id` = COALESCE(
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.Created.id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.Updated.id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.Deleted.owner_id')),
+      '')
+WHERE `event_type` = 'domus::domain::owner::events::owner::OwnerEvent';
+
+UPDATE `event_record`
+SET `event_type` = 'CommunityEvent',
+    `aggregate_type` = 'community',
+    `aggregate_id` = COALESCE(
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.Created.id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.Updated.id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.Deleted.id')),
+      '')
+WHERE `event_type` = 'domus::domain::community::events::community::CommunityEvent';
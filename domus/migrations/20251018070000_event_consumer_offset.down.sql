@@ -0,0 +1 @@
+../../crates/event_bus/migrations/20251018070000_event_consumer_offset.down.sql
\ No newline at end of file
@@ -0,0 +1,12 @@
+-- Add up migration script here
+ALTER TABLE `house_aggregate`
+  ADD COLUMN `version` BIGINT NOT NULL DEFAULT 0 COMMENT '版本号，等于已写入的事件数' AFTER `deleted_at`;
+
+-- 已有房源的版本号按事件流长度补齐，保证与从事件重建的结果一致
+UPDATE `house_aggregate` h
+SET h.`version` = (
+  SELECT COUNT(*) FROM `event_record` e
+  WHERE e.`event_type` = 'HouseEvent'
+    AND e.`aggregate_type` = 'house'
+    AND e.`aggregate_id` = h.`house_id`
+);
@@ -0,0 +1 @@
+../../crates/event_bus/migrations/20251018090000_aggregate_snapshot.down.sql
\ No newline at end of file
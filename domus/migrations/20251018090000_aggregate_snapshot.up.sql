@@ -0,0 +1 @@
+../../crates/event_bus/migrations/20251018090000_aggregate_snapshot.up.sql
\ No newline at end of file
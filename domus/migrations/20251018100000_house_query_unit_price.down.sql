@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE `house_query`
+  DROP INDEX `idx_house_query_building_area`,
+  DROP INDEX `idx_house_query_sale_price`,
+  DROP INDEX `idx_house_query_unit_price`,
+  DROP COLUMN `unit_price`;
@@ -0,0 +1,10 @@
+-- Add up migration script here
+ALTER TABLE `house_query`
+  ADD COLUMN `unit_price` DOUBLE DEFAULT NULL COMMENT '单价，售价/建筑面积，由投影维护' AFTER `sale_price`,
+  ADD INDEX `idx_house_query_unit_price` (`unit_price`),
+  ADD INDEX `idx_house_query_sale_price` (`sale_price`),
+  ADD INDEX `idx_house_query_building_area` (`building_area`);
+
+UPDATE `house_query`
+SET `unit_price` = `sale_price` / `building_area`
+WHERE `sale_price` IS NOT NULL AND `building_area` > 0;
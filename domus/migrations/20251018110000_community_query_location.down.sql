@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE `community_query`
+  DROP INDEX `idx_community_query_lat_lng`;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE `community_query`
+  ADD INDEX `idx_community_query_lat_lng` (`lat`, `lng`);
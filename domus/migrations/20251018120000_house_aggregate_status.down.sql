@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE `house_aggregate` DROP COLUMN `house_status`;
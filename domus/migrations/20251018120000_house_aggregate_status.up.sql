@@ -0,0 +1,8 @@
+-- Add up migration script here
+ALTER TABLE `house_aggregate`
+  ADD COLUMN `house_status` VARCHAR(20) DEFAULT NULL COMMENT '房源状态，由状态变更维护' AFTER `deleted_at`;
+
+-- 已有房源的状态取自读模型
+UPDATE `house_aggregate` h
+JOIN `house_query` q ON q.`id` = h.`house_id`
+SET h.`house_status` = q.`house_status`;
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS `house_price_history`;
+
+ALTER TABLE `house_aggregate`
+  DROP COLUMN `rent_price`,
+  DROP COLUMN `sale_price`;
@@ -0,0 +1,24 @@
+-- Add up migration script here
+ALTER TABLE `house_aggregate`
+  ADD COLUMN `sale_price` DOUBLE DEFAULT NULL COMMENT '售价，用于识别价格变化' AFTER `house_status`,
+  ADD COLUMN `rent_price` DOUBLE DEFAULT NULL COMMENT '租金，用于识别价格变化' AFTER `sale_price`;
+
+UPDATE `house_aggregate` h
+JOIN `house_query` q ON q.`id` = h.`house_id`
+SET h.`sale_price` = q.`sale_price`, h.`rent_price` = q.`rent_price`;
+
+CREATE TABLE `house_price_history` (
+  `id` BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+  `event_id` CHAR(36) NOT NULL COMMENT '来源事件，重复投递时忽略',
+  `house_id` CHAR(36) NOT NULL COMMENT '房源ID',
+  `field` VARCHAR(20) NOT NULL COMMENT '价格字段：sale_price 售价，rent_price 租金',
+  `old_price` DOUBLE DEFAULT NULL COMMENT '变更前价格',
+  `new_price` DOUBLE DEFAULT NULL COMMENT '变更后价格',
+  `change_rate` DOUBLE DEFAULT NULL COMMENT '涨跌幅，(新价格 - 原价格) / 原价格',
+  `changed_at` TIMESTAMP NOT NULL COMMENT '变更时间',
+  `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_house_price_history_event_id` (`event_id`),
+  KEY `idx_house_price_history_house_id` (`house_id`, `changed_at`),
+  KEY `idx_house_price_history_changed_at` (`changed_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='房源价格变化记录';
@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE `house_export_log` (
+  `id` BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+  `operator_id` VARCHAR(36) NOT NULL COMMENT '操作人ID',
+  `format` VARCHAR(10) NOT NULL COMMENT '导出格式：csv、xlsx',
+  `columns` JSON NOT NULL COMMENT '导出的列',
+  `filter` JSON NULL COMMENT '筛选条件',
+  `include_owner` TINYINT(1) NOT NULL DEFAULT 0 COMMENT '是否包含业主电话、身份证号',
+  `row_count` BIGINT UNSIGNED NOT NULL DEFAULT 0 COMMENT '导出的房源数',
+  `ip_address` VARCHAR(64) NULL COMMENT '操作来源IP',
+  `user_agent` VARCHAR(255) NULL COMMENT '操作来源设备/浏览器',
+  `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '导出时间',
+  PRIMARY KEY (`id`),
+  KEY `idx_house_export_log_operator_id` (`operator_id`, `created_at`),
+  KEY `idx_house_export_log_created_at` (`created_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='房源导出记录';
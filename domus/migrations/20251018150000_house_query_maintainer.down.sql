@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE `house_query`
+  DROP INDEX `idx_house_query_maintainer_id`,
+  DROP COLUMN `maintainer_id`;
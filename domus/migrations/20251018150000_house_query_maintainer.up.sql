@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE `house_query`
+  ADD COLUMN `maintainer_id` VARCHAR(36) DEFAULT NULL COMMENT '维护人，负责跟进房源的经纪人' AFTER `created_by`,
+  ADD INDEX `idx_house_query_maintainer_id` (`maintainer_id`);
+
+UPDATE `house_query` SET `maintainer_id` = `created_by` WHERE `maintainer_id` IS NULL;
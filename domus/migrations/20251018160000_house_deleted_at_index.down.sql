@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE `house_aggregate` DROP INDEX `idx_house_aggregate_deleted_at`;
+
+ALTER TABLE `house_query` DROP INDEX `idx_house_query_deleted_at`;
@@ -0,0 +1,7 @@
+-- Add up migration script here
+-- 回收站按删除时间列出、定时清理过期房源
+ALTER TABLE `house_aggregate`
+  ADD INDEX `idx_house_aggregate_deleted_at` (`deleted_at`);
+
+ALTER TABLE `house_query`
+  ADD INDEX `idx_house_query_deleted_at` (`deleted_at`);
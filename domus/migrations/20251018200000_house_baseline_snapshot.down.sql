@@ -0,0 +1,2 @@
+-- Add down migration script here
+DELETE FROM `aggregate_snapshot` WHERE `aggregate_type` = 'house' AND `version` = 0;
@@ -0,0 +1,33 @@
+-- Add up migration script here
+-- 早于事件流的房源没有事件，写入版本为 0 的基线快照，从事件重建时在它之上回放之后的事件
+INSERT INTO `aggregate_snapshot` (`aggregate_type`, `aggregate_id`, `version`, `last_seq`, `state`)
+SELECT 'house', h.`house_id`, 0, 0, JSON_OBJECT(
+  'house_id', h.`house_id`,
+  'community_id', h.`community_id`,
+  'house_address', h.`house_address`,
+  'publish_at', DATE_FORMAT(CONVERT_TZ(h.`publish_at`, @@session.time_zone, '+00:00'), '%Y-%m-%dT%H:%i:%sZ'),
+  'unpublish_at', DATE_FORMAT(CONVERT_TZ(h.`unpublish_at`, @@session.time_zone, '+00:00'), '%Y-%m-%dT%H:%i:%sZ'),
+  'deleted_at', DATE_FORMAT(CONVERT_TZ(h.`deleted_at`, @@session.time_zone, '+00:00'), '%Y-%m-%dT%H:%i:%sZ'),
+  -- 只保留能识别的状态（规范值或别名），其余为空
+  'status', CASE
+    WHEN h.`house_status` IN ('草稿', '在售', '出售中', '已预定', '已定', '预定', '已售', '已出售', '成交',
+                              '已租', '已出租', '暂停', '暂缓', '停售', '已过期', '过期', '无效')
+    THEN h.`house_status`
+  END,
+  'sale_price', h.`sale_price`,
+  'rent_price', h.`rent_price`,
+  'version', 0
+)
+FROM `house_aggregate` h
+WHERE h.`version` = 0
+  AND NOT EXISTS (
+    SELECT 1 FROM `event_record` e
+    WHERE e.`event_type` = 'HouseEvent'
+      AND e.`aggregate_type` = 'house'
+      AND e.`aggregate_id` = h.`house_id`
+  )
+  AND NOT EXISTS (
+    SELECT 1 FROM `aggregate_snapshot` s
+    WHERE s.`aggregate_type` = 'house'
+      AND s.`aggregate_id` = h.`house_id`
+  );
@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use event_bus::{AsyncEventBus, OutboxEvent};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::repositories::house_repository_aggregate::HouseRepositoryAggregate,
+    domain::house::{
+        events::house_patched::HousePatchedEvent,
+        value_objects::{dictionary::HouseStatus, house::House},
+    },
+};
+
+// 批量操作，每套房源单独通过聚合执行
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum HouseBatchAction {
+    // 上架
+    Publish,
+    // 下架
+    Unpublish {
+        reason: Option<String>,
+    },
+    // 变更状态
+    ChangeStatus {
+        status: HouseStatus,
+        reason: Option<String>,
+    },
+    // 删除
+    Delete,
+    // 更换维护人
+    ReassignMaintainer {
+        maintainer_id: String,
+    },
+    // 添加标签
+    AddTags {
+        tags: Vec<String>,
+    },
+    // 移除标签
+    RemoveTags {
+        tags: Vec<String>,
+    },
+}
+
+impl HouseBatchAction {
+    // 对应的操作日志类型
+    pub fn operation_type(&self) -> u8 {
+        match self {
+            HouseBatchAction::Publish => 4,
+            HouseBatchAction::Unpublish { .. } => 5,
+            HouseBatchAction::ChangeStatus { .. } => 6,
+            HouseBatchAction::Delete => 3,
+            _ => 2,
+        }
+    }
+
+    // 是否修改房源内容，这类操作需要房源的当前内容，保存为一次更新
+    pub fn is_edit(&self) -> bool {
+        self.operation_type() == 2
+    }
+
+    // 修改类操作的事件，只带本次修改的内容
+    pub fn patch(&self, house_id: &str) -> HousePatchedEvent {
+        let trim = |tags: &[String]| -> Vec<String> {
+            let mut trimmed: Vec<String> = Vec::new();
+            for tag in tags.iter().map(|tag| tag.trim()) {
+                if !tag.is_empty() && !trimmed.iter().any(|t| t == tag) {
+                    trimmed.push(tag.to_string());
+                }
+            }
+            trimmed
+        };
+
+        let mut patch = HousePatchedEvent::new(house_id.to_string());
+        match self {
+            HouseBatchAction::ReassignMaintainer { maintainer_id } => {
+                patch.maintainer_id = Some(maintainer_id.clone());
+            }
+            HouseBatchAction::AddTags { tags } => patch.add_tags = trim(tags),
+            HouseBatchAction::RemoveTags { tags } => patch.remove_tags = trim(tags),
+            _ => {}
+        }
+        patch
+    }
+
+    // 按当前内容预览修改，没有变化时返回错误
+    pub fn edit(&self, house: &mut House) -> anyhow::Result<()> {
+        let maintainer_id = house.maintainer_id.clone();
+        let tags = house.tags.clone().unwrap_or_default();
+        self.patch(house.id.as_deref().unwrap_or_default())
+            .apply(house);
+
+        if house.maintainer_id == maintainer_id && house.tags.clone().unwrap_or_default() == tags {
+            return Err(anyhow::anyhow!(match self {
+                HouseBatchAction::ReassignMaintainer { .. } => "维护人没有变化",
+                HouseBatchAction::AddTags { .. } => "房源已有这些标签",
+                _ => "房源没有这些标签",
+            }));
+        }
+
+        Ok(())
+    }
+}
+
+pub struct BatchHouseCommand {
+    pub house_id: String,
+    pub action: HouseBatchAction,
+    pub actor_id: Option<String>,
+}
+
+pub struct BatchHouseCommandHandler {
+    pub house_repository: Arc<dyn HouseRepositoryAggregate>,
+    pub event_bus: Arc<AsyncEventBus>,
+}
+
+impl BatchHouseCommandHandler {
+    pub fn new(
+        house_repository: Arc<dyn HouseRepositoryAggregate>,
+        event_bus: Arc<AsyncEventBus>,
+    ) -> Self {
+        Self {
+            house_repository,
+            event_bus,
+        }
+    }
+
+    // 执行一套房源的操作，返回新的版本号
+    pub async fn handle(&self, command: BatchHouseCommand) -> anyhow::Result<i64> {
+        let mut aggregate = self.house_repository.find_by_id(&command.house_id).await?;
+        let expected_version = aggregate.version;
+
+        if aggregate.is_deleted() {
+            return Err(anyhow::anyhow!("房源已删除"));
+        }
+
+        let events = match command.action {
+            HouseBatchAction::Publish => {
+                if !aggregate.is_unpublished() {
+                    return Err(anyhow::anyhow!("房源已上架"));
+                }
+                vec![aggregate.publish()?]
+            }
+            HouseBatchAction::Unpublish { reason } => {
+                if aggregate.is_unpublished() {
+                    return Err(anyhow::anyhow!("房源已下架"));
+                }
+                vec![aggregate.unpublish(reason.as_deref().unwrap_or_default())?]
+            }
+            HouseBatchAction::ChangeStatus { status, reason } => {
+                vec![aggregate.change_status(status, reason, command.actor_id)?]
+            }
+            HouseBatchAction::Delete => vec![aggregate.delete()],
+            // 只发送修改的字段，不用读模型里的整套房源覆盖
+            action => vec![aggregate.patch(action.patch(&command.house_id))?],
+        };
+
+        self.house_repository
+            .save(
+                &aggregate,
+                expected_version,
+                &OutboxEvent::from_events(&events),
+            )
+            .await?;
+        self.event_bus.notify();
+
+        Ok(aggregate.version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::HouseBatchAction;
+    use crate::domain::house::value_objects::house::House;
+
+    #[test]
+    fn test_edit() {
+        let action: HouseBatchAction =
+            serde_json::from_value(json!({"action": "add_tags", "tags": ["满五", " 急售 "]}))
+                .unwrap();
+        assert!(action.is_edit());
+
+        let mut house = House {
+            tags: Some(vec!["满五".to_string()]),
+            ..Default::default()
+        };
+        action.edit(&mut house).unwrap();
+        assert_eq!(
+            house.tags,
+            Some(vec!["满五".to_string(), "急售".to_string()])
+        );
+        assert!(action.edit(&mut house).is_err());
+
+        let action: HouseBatchAction =
+            serde_json::from_value(json!({"action": "remove_tags", "tags": ["满五"]})).unwrap();
+        action.edit(&mut house).unwrap();
+        assert_eq!(house.tags, Some(vec!["急售".to_string()]));
+
+        // 事件只带修改的标签
+        let patch = action.patch("h1");
+        assert_eq!(patch.remove_tags, vec!["满五".to_string()]);
+        assert!(patch.add_tags.is_empty() && patch.maintainer_id.is_none());
+
+        let action: HouseBatchAction =
+            serde_json::from_value(json!({"action": "change_status", "status": "已售"})).unwrap();
+        assert_eq!(action.operation_type(), 6);
+        assert!(!action.is_edit());
+    }
+}
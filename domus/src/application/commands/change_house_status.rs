@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use event_bus::{AsyncEventBus, OutboxEvent};
+use serde::Deserialize;
+
+use crate::{
+    application::repositories::house_repository_aggregate::HouseRepositoryAggregate,
+    domain::house::value_objects::dictionary::HouseStatus,
+};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChangeHouseStatusCommand {
+    #[serde(skip)]
+    pub house_id: String,
+    // 目标状态
+    pub status: HouseStatus,
+    // 变更原因
+    pub reason: Option<String>,
+    // 期望的版本号，为空时以读取到的版本为准
+    pub version: Option<i64>,
+    #[serde(skip)]
+    pub actor_id: Option<String>,
+}
+
+pub struct ChangeHouseStatusCommandHandler {
+    pub house_repository: Arc<dyn HouseRepositoryAggregate>,
+    pub event_bus: Arc<AsyncEventBus>,
+}
+
+impl ChangeHouseStatusCommandHandler {
+    pub fn new(
+        house_repository: Arc<dyn HouseRepositoryAggregate>,
+        event_bus: Arc<AsyncEventBus>,
+    ) -> Self {
+        Self {
+            house_repository,
+            event_bus,
+        }
+    }
+
+    pub async fn handle(&self, command: ChangeHouseStatusCommand) -> anyhow::Result<()> {
+        let mut aggregate = self.house_repository.find_by_id(&command.house_id).await?;
+        let expected_version = command.version.unwrap_or(aggregate.version);
+
+        let event = aggregate.change_status(command.status, command.reason, command.actor_id)?;
+
+        self.house_repository
+            .save(
+                &aggregate,
+                expected_version,
+                &[OutboxEvent::from_event(&event)],
+            )
+            .await?;
+        self.event_bus.notify();
+
+        Ok(())
+    }
+}
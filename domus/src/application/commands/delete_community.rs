@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 
 use crate::application::repositories::community_repository_aggregate::CommunityRepositoryAggregate;
 
@@ -40,8 +40,10 @@ impl DeleteCommunityCommandHandler {
 
         let event = aggreagate.delete();
 
-        self.community_repository.save(&aggreagate).await?;
-        self.event_bus.publish(event).await;
+        self.community_repository
+            .save(&aggreagate, &[OutboxEvent::from_event(&event)])
+            .await?;
+        self.event_bus.notify();
 
         Ok(())
     }
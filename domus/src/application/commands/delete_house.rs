@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 
 use crate::application::repositories::house_repository_aggregate::HouseRepositoryAggregate;
 
@@ -32,11 +32,18 @@ impl DeleteHouseCommandHandler {
 
     pub async fn handle(&self, command: DeleteHouseCommand) -> anyhow::Result<()> {
         let mut aggreagate = self.house_repository.find_by_id(&command.id).await?;
+        let expected_version = aggreagate.version;
 
         let event = aggreagate.delete();
 
-        self.house_repository.save(&aggreagate).await?;
-        self.event_bus.publish(event).await;
+        self.house_repository
+            .save(
+                &aggreagate,
+                expected_version,
+                &[OutboxEvent::from_event(&event)],
+            )
+            .await?;
+        self.event_bus.notify();
 
         Ok(())
     }
@@ -1,5 +1,5 @@
 use crate::application::repositories::owner_repository_aggregate::OwnerRepositoryAggregate;
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 use serde::Deserialize;
 use std::sync::Arc;
 
@@ -36,8 +36,10 @@ impl DeleteOwnerCommandHandler {
         let mut aggregate = self.owner_repository.find_by_id(&command.id).await?;
 
         let event = aggregate.delete();
-        self.owner_repository.save(&aggregate).await?;
-        self.event_bus.publish(event).await;
+        self.owner_repository
+            .save(&aggregate, &[OutboxEvent::from_event(&event)])
+            .await?;
+        self.event_bus.notify();
 
         Ok(())
     }
@@ -4,6 +4,11 @@ use clap::{command, Parser, Subcommand};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+use super::{
+    rebuild_projections::Projection,
+    snapshots::{SnapshotAction, SnapshotAggregate},
+};
+
 #[derive(Parser)]
 #[command(name = "house-system")]
 #[command(about = "房源管理系统 CLI", long_about = None)]
@@ -15,6 +20,32 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum InitSystemCommand {
     InitSystem,
+    /// 清空读模型，按顺序回放 event_record 重建
+    RebuildProjections {
+        #[arg(long, value_enum, default_value = "all")]
+        projection: Projection,
+        /// 只统计，不写入
+        #[arg(long)]
+        dry_run: bool,
+        /// 读模型中有没有事件的数据时仍然重建，这些数据会丢失
+        #[arg(long)]
+        force: bool,
+    },
+    /// 清空房源全文索引，从读模型重新写入
+    RebuildSearchIndex,
+    /// 重建或压缩聚合快照
+    Snapshots {
+        #[arg(value_enum)]
+        action: SnapshotAction,
+        #[arg(long, value_enum, default_value = "all")]
+        aggregate: SnapshotAggregate,
+    },
+    /// 把 house_query 中用途、装修等字典字段的别名改写为规范值
+    NormalizeHouseDictionary {
+        /// 只统计，不写入
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 pub struct InitSystemCommandHandler {}
@@ -65,6 +96,42 @@ impl InitSystemCommandHandler {
                 "source": "^/api/domus/query/*",
                 "action": "POST",
                 "description": "使用高级房源查询"
+            },
+            {
+                "name": "事件管理",
+                "source": "^/api/domus/event/dead_letter/*",
+                "action": "POST",
+                "description": "重新投递死信事件"
+            },
+            {
+                "name": "事件查询",
+                "source": "^/api/domus/event/dead_letter/*",
+                "action": "GET",
+                "description": "查询死信事件"
+            },
+            {
+                "name": "事件浏览",
+                "source": "^/api/domus/event/store/*",
+                "action": "GET",
+                "description": "按条件浏览事件存储"
+            },
+            {
+                "name": "数据同步",
+                "source": "^/api/domus/sync/changes",
+                "action": "GET",
+                "description": "移动端增量同步房源、小区"
+            },
+            {
+                "name": "同步业主信息",
+                "source": "^/api/domus/sync/owner",
+                "action": "GET",
+                "description": "增量同步时包含业主及其联系方式"
+            },
+            {
+                "name": "导出业主信息",
+                "source": "^/api/domus/export/owner",
+                "action": "GET",
+                "description": "导出房源时包含业主电话、身份证号"
             }
         ]);
 
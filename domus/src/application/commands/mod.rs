@@ -1,10 +1,17 @@
+pub mod batch_house;
+pub mod change_house_status;
 pub mod delete_community;
 pub mod delete_house;
 pub mod delete_owner;
 pub mod init_system;
+pub mod normalize_house_dictionary;
+pub mod rebuild_projections;
+pub mod rebuild_search_index;
+pub mod restore_house;
 pub mod save_community;
 pub mod save_community_handler;
 pub mod save_house;
 pub mod save_house_handler;
 pub mod save_owner;
 pub mod save_owner_handler;
+pub mod snapshots;
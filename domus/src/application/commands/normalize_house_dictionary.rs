@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, DbConn, EntityTrait, PaginatorTrait, QueryOrder,
+};
+
+use crate::{domain::house::value_objects::house::House, infrastructure::entitiy::house_query};
+
+pub struct NormalizeHouseDictionaryCommand {
+    pub dry_run: bool,
+}
+
+// 把 house_query 里字典字段的历史写法改成规范值，无法识别的值只打印出来，交给人工处理
+pub struct NormalizeHouseDictionaryCommandHandler {
+    pool: Arc<DbConn>,
+}
+
+impl NormalizeHouseDictionaryCommandHandler {
+    pub fn new(pool: Arc<DbConn>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn handle(&self, command: NormalizeHouseDictionaryCommand) -> anyhow::Result<()> {
+        let mut paginator = house_query::Entity::find()
+            .order_by_asc(house_query::Column::Id)
+            .paginate(self.pool.as_ref(), 500);
+
+        let (mut changed, mut unknown) = (0, 0);
+        while let Some(models) = paginator.fetch_and_next().await? {
+            for model in models {
+                let mut house = House {
+                    purpose: Some(model.purpose.clone()),
+                    transaction_type: Some(model.transaction_type.clone()),
+                    house_status: Some(model.house_status.clone()),
+                    house_decoration: model.house_decoration.clone(),
+                    house_orientation: model.house_orientation.clone(),
+                    property_rights: model.property_rights.clone(),
+                    view_method: model.view_method.clone(),
+                    building_structure: model.building_structure.clone(),
+                    ..Default::default()
+                };
+                house.normalize();
+
+                if let Err(e) = house.validate_dictionary() {
+                    unknown += 1;
+                    println!("{}: {}", model.id, e);
+                }
+
+                let mut active: house_query::ActiveModel = model.clone().into();
+                if let Some(purpose) = house.purpose.filter(|v| *v != model.purpose) {
+                    active.purpose = Set(purpose);
+                }
+                if let Some(v) = house
+                    .transaction_type
+                    .filter(|v| *v != model.transaction_type)
+                {
+                    active.transaction_type = Set(v);
+                }
+                if let Some(v) = house.house_status.filter(|v| *v != model.house_status) {
+                    active.house_status = Set(v);
+                }
+                if house.house_decoration != model.house_decoration {
+                    active.house_decoration = Set(house.house_decoration);
+                }
+                if house.house_orientation != model.house_orientation {
+                    active.house_orientation = Set(house.house_orientation);
+                }
+                if house.property_rights != model.property_rights {
+                    active.property_rights = Set(house.property_rights);
+                }
+                if house.view_method != model.view_method {
+                    active.view_method = Set(house.view_method);
+                }
+                if house.building_structure != model.building_structure {
+                    active.building_structure = Set(house.building_structure);
+                }
+
+                if !active.is_changed() {
+                    continue;
+                }
+
+                changed += 1;
+                if !command.dry_run {
+                    active.update(self.pool.as_ref()).await?;
+                }
+            }
+        }
+
+        let prefix = if command.dry_run { "[dry-run] " } else { "" };
+        println!("{prefix}house_query: 规范 {changed} 行，{unknown} 行有无法识别的值");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,249 @@
+use std::sync::Arc;
+
+use clap::ValueEnum;
+use event_bus::{AsyncEventBus, EventListener, EventReplayer, TableBackup};
+use sea_orm::{DbConn, EntityName, EntityTrait, PaginatorTrait, QuerySelect};
+
+use crate::{
+    application::{
+        listeners::{
+            community::CommunityEventListener, house::HouseEventListener,
+            house_price::HousePriceListener, owner::OwnerEventListener,
+        },
+        queries::{
+            community::CommunityQueryService, house::HouseQueryService,
+            house_price::HousePriceQueryService, owner::OwnerQueryService,
+        },
+    },
+    infrastructure::entitiy::{community_query, house_price_history, house_query, owner_query},
+};
+
+// 可以重建的读模型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Projection {
+    All,
+    Community,
+    Owner,
+    House,
+    // 房源价格走势
+    HousePrice,
+}
+
+impl Projection {
+    // 展开 all，房源依赖小区和业主，放在最后
+    fn expand(self) -> Vec<Projection> {
+        match self {
+            Projection::All => vec![
+                Projection::Community,
+                Projection::Owner,
+                Projection::House,
+                Projection::HousePrice,
+            ],
+            projection => vec![projection],
+        }
+    }
+
+    fn table_name(self) -> &'static str {
+        match self {
+            Projection::All => "*",
+            Projection::Community => community_query::Entity.table_name(),
+            Projection::Owner => owner_query::Entity.table_name(),
+            Projection::House => house_query::Entity.table_name(),
+            Projection::HousePrice => house_price_history::Entity.table_name(),
+        }
+    }
+}
+
+pub struct RebuildProjectionsCommand {
+    pub projection: Projection,
+    pub dry_run: bool,
+    // 有事件的聚合少于读模型的行数时仍然重建
+    pub force: bool,
+}
+
+pub struct RebuildProjectionsCommandHandler {
+    pool: Arc<DbConn>,
+}
+
+impl RebuildProjectionsCommandHandler {
+    pub fn new(pool: Arc<DbConn>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn handle(&self, command: RebuildProjectionsCommand) -> anyhow::Result<()> {
+        let projections = command.projection.expand();
+
+        // 先检查全部读模型，避免重建到一半才发现事件不全
+        for projection in &projections {
+            self.check(*projection, command.dry_run, command.force)
+                .await?;
+        }
+        if command.dry_run {
+            return Ok(());
+        }
+
+        for projection in projections {
+            self.rebuild(projection).await?;
+        }
+
+        Ok(())
+    }
+
+    fn event_bus(&self, projection: Projection) -> Arc<AsyncEventBus> {
+        // 只订阅这个读模型的监听器，避免回放时触发其他副作用
+        let event_bus = Arc::new(AsyncEventBus::new(None));
+        self.subscribe(projection, event_bus.clone());
+        event_bus
+    }
+
+    // 读模型里的聚合比有事件的聚合多，说明有数据早于事件流，清空后回放会丢失
+    async fn check(
+        &self,
+        projection: Projection,
+        dry_run: bool,
+        force: bool,
+    ) -> anyhow::Result<()> {
+        let table_name = projection.table_name();
+        let event_bus = self.event_bus(projection);
+
+        let replayer = EventReplayer::new(self.pool.clone());
+        let total = replayer.count(&event_bus).await?;
+        let aggregates = replayer.count_aggregates(&event_bus).await?;
+        let rows = self.count_rows(projection).await?;
+        let read_aggregates = self.count_aggregates(projection).await?;
+
+        if dry_run {
+            println!(
+                "[dry-run] {table_name}: 将清空 {rows} 行（{read_aggregates} 个聚合），回放 {aggregates} 个聚合的 {total} 个事件"
+            );
+        }
+
+        if aggregates < read_aggregates {
+            let message = format!(
+                "{table_name}: 读模型中有 {read_aggregates} 个聚合，只有 {aggregates} 个聚合有事件，重建会丢失其余数据"
+            );
+            if !force {
+                return Err(anyhow::anyhow!("{message}，确认后加 --force"));
+            }
+            println!("{message}，已指定 --force");
+        }
+
+        Ok(())
+    }
+
+    async fn rebuild(&self, projection: Projection) -> anyhow::Result<()> {
+        let table_name = projection.table_name();
+        let event_bus = self.event_bus(projection);
+
+        let replayer = EventReplayer::new(self.pool.clone());
+        let total = replayer.count(&event_bus).await?;
+        let rows = self.count_rows(projection).await?;
+
+        // 先备份，回放失败时换回原表
+        let backup = TableBackup::create(self.pool.as_ref(), table_name).await?;
+        self.truncate(projection).await?;
+        println!("{table_name}: 已清空 {rows} 行，开始回放 {total} 个事件");
+
+        let result = replayer
+            .replay(&event_bus, |replayed| {
+                println!("{table_name}: 已回放 {replayed}/{total}");
+            })
+            .await;
+
+        match result {
+            Ok(replayed) => {
+                backup.drop(self.pool.as_ref()).await?;
+                println!("{table_name}: 重建完成，共回放 {replayed} 个事件");
+                Ok(())
+            }
+            Err(err) => {
+                backup.restore(self.pool.as_ref()).await?;
+                println!("{table_name}: 回放失败，已恢复原数据");
+                Err(err)
+            }
+        }
+    }
+
+    fn subscribe(&self, projection: Projection, event_bus: Arc<AsyncEventBus>) {
+        match projection {
+            Projection::All => {}
+            Projection::Community => {
+                Arc::new(CommunityEventListener::new(Arc::new(
+                    CommunityQueryService::new(self.pool.clone()),
+                )))
+                .subscribe(event_bus);
+            }
+            Projection::Owner => {
+                Arc::new(OwnerEventListener::new(Arc::new(OwnerQueryService::new(
+                    self.pool.clone(),
+                ))))
+                .subscribe(event_bus);
+            }
+            Projection::House => {
+                Arc::new(HouseEventListener::new(Arc::new(HouseQueryService::new(
+                    self.pool.clone(),
+                ))))
+                .subscribe(event_bus);
+            }
+            Projection::HousePrice => {
+                Arc::new(HousePriceListener::new(Arc::new(
+                    HousePriceQueryService::new(self.pool.clone()),
+                )))
+                .subscribe(event_bus);
+            }
+        }
+    }
+
+    async fn count_rows(&self, projection: Projection) -> anyhow::Result<u64> {
+        let pool = self.pool.as_ref();
+        let rows = match projection {
+            Projection::All => 0,
+            Projection::Community => community_query::Entity::find().count(pool).await?,
+            Projection::Owner => owner_query::Entity::find().count(pool).await?,
+            Projection::House => house_query::Entity::find().count(pool).await?,
+            Projection::HousePrice => house_price_history::Entity::find().count(pool).await?,
+        };
+
+        Ok(rows)
+    }
+
+    // 读模型中的聚合数，价格记录按房源去重
+    async fn count_aggregates(&self, projection: Projection) -> anyhow::Result<u64> {
+        let rows = match projection {
+            Projection::HousePrice => {
+                house_price_history::Entity::find()
+                    .select_only()
+                    .column(house_price_history::Column::HouseId)
+                    .distinct()
+                    .count(self.pool.as_ref())
+                    .await?
+            }
+            projection => self.count_rows(projection).await?,
+        };
+
+        Ok(rows)
+    }
+
+    async fn truncate(&self, projection: Projection) -> anyhow::Result<()> {
+        let pool = self.pool.as_ref();
+        match projection {
+            Projection::All => {}
+            Projection::Community => {
+                community_query::Entity::delete_many().exec(pool).await?;
+            }
+            Projection::Owner => {
+                owner_query::Entity::delete_many().exec(pool).await?;
+            }
+            Projection::House => {
+                house_query::Entity::delete_many().exec(pool).await?;
+            }
+            Projection::HousePrice => {
+                house_price_history::Entity::delete_many()
+                    .exec(pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use sea_orm::{
+    ColumnTrait, DbConn, EntityTrait, JoinType, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, RelationTrait,
+};
+
+use crate::infrastructure::{
+    dto::house_data_dto::HouseDataDto,
+    entitiy::{community_query, house_query},
+    search::house_index::{HouseSearchDocument, HouseSearchIndex},
+};
+
+// 从 house_query、community_query 重建房源全文索引，已删除的房源不入索引
+pub struct RebuildSearchIndexCommandHandler {
+    pool: Arc<DbConn>,
+    search_index: Arc<HouseSearchIndex>,
+}
+
+impl RebuildSearchIndexCommandHandler {
+    pub fn new(pool: Arc<DbConn>, search_index: Arc<HouseSearchIndex>) -> Self {
+        Self { pool, search_index }
+    }
+
+    pub async fn handle(&self) -> anyhow::Result<()> {
+        let mut paginator = house_query::Entity::find()
+            .join(
+                JoinType::LeftJoin,
+                house_query::Relation::CommunityQuery.def(),
+            )
+            .filter(house_query::Column::DeletedAt.is_null())
+            .order_by_asc(house_query::Column::Id)
+            .select_also(community_query::Entity)
+            .paginate(self.pool.as_ref(), 500);
+
+        let total = paginator.num_items().await?;
+        let mut documents = Vec::with_capacity(total as usize);
+        while let Some(rows) = paginator.fetch_and_next().await? {
+            for (house, community) in rows {
+                let dto = HouseDataDto::new(house, community.clone(), None);
+                documents.push(HouseSearchDocument::new(&dto.house, community.as_ref()));
+            }
+            println!("house_search_index: 已读取 {}/{total}", documents.len());
+        }
+
+        let indexed = self.search_index.rebuild(documents)?;
+        println!("house_search_index: 重建完成，共 {indexed} 套房源");
+
+        Ok(())
+    }
+}
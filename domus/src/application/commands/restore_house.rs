@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use event_bus::{AsyncEventBus, OutboxEvent};
+
+use crate::application::repositories::house_repository_aggregate::HouseRepositoryAggregate;
+
+pub struct RestoreHouseCommand {
+    pub id: String,
+}
+
+impl RestoreHouseCommand {
+    pub fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+pub struct RestoreHouseCommandHandler {
+    pub house_repository: Arc<dyn HouseRepositoryAggregate>,
+    pub event_bus: Arc<AsyncEventBus>,
+}
+
+impl RestoreHouseCommandHandler {
+    pub fn new(
+        house_repository: Arc<dyn HouseRepositoryAggregate>,
+        event_bus: Arc<AsyncEventBus>,
+    ) -> Self {
+        Self {
+            house_repository,
+            event_bus,
+        }
+    }
+
+    pub async fn handle(&self, command: RestoreHouseCommand) -> anyhow::Result<i64> {
+        let mut aggregate = self.house_repository.find_deleted(&command.id).await?;
+        let expected_version = aggregate.version;
+
+        // 删除期间同一地址可能已经录入了新房源
+        if self
+            .house_repository
+            .exists_address(
+                &aggregate.community_id,
+                &aggregate.house_address,
+                Some(aggregate.house_id.clone()),
+            )
+            .await?
+        {
+            return Err(anyhow::anyhow!(
+                "地址「{}」已有其他房源，请先处理该房源再恢复",
+                aggregate.house_address
+            ));
+        }
+
+        let event = aggregate.restore()?;
+
+        self.house_repository
+            .save(
+                &aggregate,
+                expected_version,
+                &[OutboxEvent::from_event(&event)],
+            )
+            .await?;
+        self.event_bus.notify();
+
+        Ok(aggregate.version)
+    }
+}
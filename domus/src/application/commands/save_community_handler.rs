@@ -1,6 +1,6 @@
 pub use std::sync::Arc;
 
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 
 use crate::{
     application::{
@@ -40,8 +40,10 @@ impl SaveCommunityCommandHandler {
             Some(mut aggreagate) => {
                 let event = aggreagate.update(&community)?;
 
-                self.community_repository.save(&aggreagate).await?;
-                self.event_bus.publish(event).await;
+                self.community_repository
+                    .save(&aggreagate, &[OutboxEvent::from_event(&event)])
+                    .await?;
+                self.event_bus.notify();
 
                 Ok(aggreagate.community_id)
             }
@@ -49,8 +51,10 @@ impl SaveCommunityCommandHandler {
             None => {
                 // 创建小区
                 let (aggregate, event) = CommunityAggregate::create(&command.into_inner())?;
-                self.community_repository.create(aggregate.clone()).await?;
-                self.event_bus.publish(event).await;
+                self.community_repository
+                    .create(aggregate.clone(), &[OutboxEvent::from_event(&event)])
+                    .await?;
+                self.event_bus.notify();
 
                 Ok(aggregate.community_id)
             }
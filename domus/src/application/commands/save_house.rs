@@ -3,14 +3,25 @@ use serde::Deserialize;
 use crate::domain::house::value_objects::house::House;
 
 #[derive(Debug, Clone, Deserialize)]
-pub struct SaveHouseCommand(House);
+pub struct SaveHouseCommand {
+    house: House,
+    // 期望的版本号，为空时以读取到的版本为准
+    expected_version: Option<i64>,
+}
 
 impl SaveHouseCommand {
-    pub fn new(house: House) -> Self {
-        Self(house)
+    pub fn new(house: House, expected_version: Option<i64>) -> Self {
+        Self {
+            house,
+            expected_version,
+        }
     }
 
     pub fn into_inner(&self) -> House {
-        self.0.clone()
+        self.house.clone()
+    }
+
+    pub fn expected_version(&self) -> Option<i64> {
+        self.expected_version
     }
 }
@@ -7,7 +7,7 @@ use crate::{
     },
     domain::house::aggregates::house::HouseAggregate,
 };
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 
 pub struct SaveHouseCommandHandler {
     pub house_repository: Arc<dyn HouseRepositoryAggregate>,
@@ -45,12 +45,17 @@ impl SaveHouseCommandHandler {
             Some(id) => {
                 let mut aggregate = self.house_repository.find_by_id(&id).await?;
                 let house_id = aggregate.house_id.clone();
+                let expected_version = command.expected_version().unwrap_or(aggregate.version);
                 let events = aggregate.update(&house)?;
 
-                self.house_repository.save(&aggregate).await?;
-                for event in events {
-                    self.event_bus.publish(event).await;
-                }
+                self.house_repository
+                    .save(
+                        &aggregate,
+                        expected_version,
+                        &OutboxEvent::from_events(&events),
+                    )
+                    .await?;
+                self.event_bus.notify();
 
                 Ok(house_id)
             }
@@ -58,8 +63,10 @@ impl SaveHouseCommandHandler {
             None => {
                 let (aggregate, event) = HouseAggregate::create(house)?;
                 let house_id = aggregate.house_id.clone();
-                self.house_repository.create(aggregate).await?;
-                self.event_bus.publish(event).await;
+                self.house_repository
+                    .create(aggregate, &[OutboxEvent::from_event(&event)])
+                    .await?;
+                self.event_bus.notify();
 
                 Ok(house_id)
             }
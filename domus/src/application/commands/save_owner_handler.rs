@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 
 use crate::{
     application::{
@@ -69,8 +69,10 @@ impl SaveOwnerCommandHandler {
 
                 let event = aggregate.update(&owner)?;
 
-                self.owner_repository.save(&aggregate).await?;
-                self.event_bus.publish(event).await;
+                self.owner_repository
+                    .save(&aggregate, &[OutboxEvent::from_event(&event)])
+                    .await?;
+                self.event_bus.notify();
 
                 Ok(aggregate.owner_id)
             }
@@ -84,9 +86,11 @@ impl SaveOwnerCommandHandler {
 
                 let (aggregate, event) = OwnerAggregate::create(owner)?;
 
-                self.owner_repository.create(aggregate.clone()).await?;
+                self.owner_repository
+                    .create(aggregate.clone(), &[OutboxEvent::from_event(&event)])
+                    .await?;
 
-                self.event_bus.publish(event).await;
+                self.event_bus.notify();
 
                 Ok(aggregate.owner_id)
             }
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use clap::ValueEnum;
+use event_bus::{Aggregate, SnapshotStore};
+use sea_orm::DbConn;
+
+use crate::domain::house::aggregates::house::HouseAggregate;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SnapshotAction {
+    // 从完整事件流重新生成快照
+    Rebuild,
+    // 每个聚合只保留最新的快照
+    Compact,
+}
+
+// 可以生成快照的聚合，只有通过快照加载的聚合才放在这里
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SnapshotAggregate {
+    All,
+    House,
+}
+
+impl SnapshotAggregate {
+    fn expand(self) -> Vec<SnapshotAggregate> {
+        match self {
+            SnapshotAggregate::All => vec![SnapshotAggregate::House],
+            aggregate => vec![aggregate],
+        }
+    }
+}
+
+pub struct SnapshotsCommand {
+    pub action: SnapshotAction,
+    pub aggregate: SnapshotAggregate,
+}
+
+pub struct SnapshotsCommandHandler {
+    snapshots: SnapshotStore,
+}
+
+impl SnapshotsCommandHandler {
+    pub fn new(pool: Arc<DbConn>) -> Self {
+        Self {
+            snapshots: SnapshotStore::new(pool),
+        }
+    }
+
+    pub async fn handle(&self, command: SnapshotsCommand) -> anyhow::Result<()> {
+        for aggregate in command.aggregate.expand() {
+            match aggregate {
+                SnapshotAggregate::All => {}
+                SnapshotAggregate::House => self.execute::<HouseAggregate>(command.action).await?,
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn execute<A: Aggregate>(&self, action: SnapshotAction) -> anyhow::Result<()> {
+        let aggregate_type = <A::Event as event_bus::Event>::AGGREGATE_TYPE;
+
+        match action {
+            SnapshotAction::Rebuild => {
+                let rebuilt = self
+                    .snapshots
+                    .rebuild::<A, _>(|rebuilt, total| {
+                        if rebuilt % 100 == 0 || rebuilt == total {
+                            println!("{aggregate_type}: 已生成 {rebuilt}/{total}");
+                        }
+                    })
+                    .await?;
+                println!("{aggregate_type}: 快照重建完成，共 {rebuilt} 个聚合");
+            }
+            SnapshotAction::Compact => {
+                let deleted = self.snapshots.compact::<A>().await?;
+                println!("{aggregate_type}: 已删除 {deleted} 个旧快照");
+            }
+        }
+
+        Ok(())
+    }
+}
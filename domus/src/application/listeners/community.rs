@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use event_bus::EventListener;
+use event_bus::{EventEnvelope, EventListener};
 
 use crate::{
     application::queries::community::CommunityQueryService,
@@ -21,20 +21,19 @@ impl CommunityEventListener {
 
 #[async_trait::async_trait]
 impl EventListener<CommunityEvent> for CommunityEventListener {
-    async fn handle(&self, event: CommunityEvent) {
-        match event {
+    async fn handle(&self, event: EventEnvelope<CommunityEvent>) -> anyhow::Result<()> {
+        match event.payload {
             CommunityEvent::Created(event) => {
-                self.community_query_service.create(event).await.unwrap();
+                self.community_query_service.create(event).await?;
             }
             CommunityEvent::Updated(event) => {
-                self.community_query_service.update(event).await.unwrap();
+                self.community_query_service.update(event).await?;
             }
             CommunityEvent::Deleted(event) => {
-                self.community_query_service
-                    .delete(&event.id)
-                    .await
-                    .unwrap();
+                self.community_query_service.delete(&event.id).await?;
             }
         }
+
+        Ok(())
     }
 }
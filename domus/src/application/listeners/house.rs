@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use event_bus::EventListener;
+use event_bus::{EventEnvelope, EventListener};
 
 use crate::{
     application::queries::house::HouseQueryService, domain::house::events::house::HouseEvent,
@@ -20,26 +20,46 @@ impl HouseEventListener {
 
 #[async_trait::async_trait]
 impl EventListener<HouseEvent> for HouseEventListener {
-    async fn handle(&self, event: HouseEvent) {
-        match event {
+    async fn handle(&self, event: EventEnvelope<HouseEvent>) -> anyhow::Result<()> {
+        match event.payload {
             HouseEvent::Created(event) => {
-                self.house_query_service.create(event).await.unwrap();
+                self.house_query_service.create(event).await?;
             }
             HouseEvent::Updated(event) => {
-                self.house_query_service.update(event).await.unwrap();
+                self.house_query_service.update(event).await?;
             }
             HouseEvent::Published(event) => {
-                self.house_query_service
-                    .publish(&event.house_id)
-                    .await
-                    .unwrap();
+                self.house_query_service.publish(&event.house_id).await?;
             }
             HouseEvent::Unpublished(event) => {
-                self.house_query_service.unpublish(&event.id).await.unwrap();
+                self.house_query_service.unpublish(&event.id).await?;
             }
             HouseEvent::Deleted(event) => {
-                self.house_query_service.delete(&event.id).await.unwrap();
+                self.house_query_service.delete(&event.id).await?;
+            }
+            HouseEvent::Restored(event) => {
+                self.house_query_service.restore(&event.id).await?;
+            }
+            HouseEvent::Purged(event) => {
+                self.house_query_service.purge(&event.id).await?;
+            }
+            HouseEvent::Listed(event)
+            | HouseEvent::Reserved(event)
+            | HouseEvent::Sold(event)
+            | HouseEvent::Rented(event)
+            | HouseEvent::Suspended(event)
+            | HouseEvent::Expired(event) => {
+                self.house_query_service
+                    .change_status(&event.house_id, event.to)
+                    .await?;
+            }
+            // 价格随后面的 Updated 写入
+            HouseEvent::PriceChanged(_) => {}
+            HouseEvent::Patched(event) => {
+                self.house_query_service.patch(&event).await?;
             }
         }
+
+        Ok(())
     }
 }
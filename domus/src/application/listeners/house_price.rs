@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use event_bus::{EventEnvelope, EventListener};
+
+use crate::{
+    application::queries::house_price::HousePriceQueryService,
+    domain::house::events::house::HouseEvent,
+};
+
+// 记录房源的价格变化
+pub struct HousePriceListener {
+    house_price_query_service: Arc<HousePriceQueryService>,
+}
+
+impl HousePriceListener {
+    pub fn new(house_price_query_service: Arc<HousePriceQueryService>) -> Self {
+        HousePriceListener {
+            house_price_query_service,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventListener<HouseEvent> for HousePriceListener {
+    async fn handle(&self, event: EventEnvelope<HouseEvent>) -> anyhow::Result<()> {
+        match &event.payload {
+            HouseEvent::PriceChanged(payload) => {
+                self.house_price_query_service
+                    .record(&event.event_id, payload)
+                    .await?;
+            }
+            HouseEvent::Purged(payload) => {
+                self.house_price_query_service.purge(&payload.id).await?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use event_bus::{EventEnvelope, EventListener};
+use sea_orm::{DbConn, EntityTrait, JoinType, QuerySelect, RelationTrait};
+
+use crate::{
+    domain::{
+        community::events::community::CommunityEvent, house::events::house::HouseEvent,
+        house::value_objects::house::House,
+    },
+    infrastructure::{
+        dto::house_data_dto::HouseDataDto,
+        entitiy::{community_query, house_query},
+        search::house_index::{HouseSearchDocument, HouseSearchIndex},
+    },
+};
+
+// 维护房源全文索引，与 house_query 投影并行执行，所以按事件内容更新，不读 house_query
+pub struct HouseSearchListener {
+    pool: Arc<DbConn>,
+    search_index: Arc<HouseSearchIndex>,
+}
+
+impl HouseSearchListener {
+    pub fn new(pool: Arc<DbConn>, search_index: Arc<HouseSearchIndex>) -> Self {
+        HouseSearchListener { pool, search_index }
+    }
+
+    async fn index(&self, house: House) -> anyhow::Result<()> {
+        let id = house.id.clone().unwrap_or_default();
+        let mut document = HouseSearchDocument::new(&house, None);
+
+        // 与投影一致：更新事件里没带的小区、地址、用途、交易类型保持原值
+        if let Some(current) = self.search_index.find(&id)? {
+            if house.community_id.is_none() {
+                document.community_id = current.community_id;
+            }
+            if house.house_address.is_none() {
+                document.house_address = current.house_address;
+            }
+            if house.purpose.is_none() {
+                document.purpose = current.purpose;
+            }
+            if house.transaction_type.is_none() {
+                document.transaction_type = current.transaction_type;
+            }
+        }
+
+        let community = community_query::Entity::find_by_id(document.community_id.clone())
+            .one(self.pool.as_ref())
+            .await?;
+        document.set_community(community.as_ref());
+
+        self.search_index.upsert(&[document])
+    }
+
+    // 恢复的房源事件里没有内容，从 house_query 读取；删除期间读模型只有 deleted_at 会变，不影响索引内容
+    async fn reindex(&self, house_id: &str) -> anyhow::Result<()> {
+        let row = house_query::Entity::find_by_id(house_id)
+            .join(
+                JoinType::LeftJoin,
+                house_query::Relation::CommunityQuery.def(),
+            )
+            .select_also(community_query::Entity)
+            .one(self.pool.as_ref())
+            .await?;
+
+        if let Some((house, community)) = row {
+            let dto = HouseDataDto::new(house, community.clone(), None);
+            self.search_index
+                .upsert(&[HouseSearchDocument::new(&dto.house, community.as_ref())])?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl EventListener<HouseEvent> for HouseSearchListener {
+    async fn handle(&self, event: EventEnvelope<HouseEvent>) -> anyhow::Result<()> {
+        match event.payload {
+            HouseEvent::Created(house) | HouseEvent::Updated(house) => self.index(house).await?,
+            HouseEvent::Deleted(event) => self.search_index.delete(&event.id)?,
+            HouseEvent::Purged(event) => self.search_index.delete(&event.id)?,
+            HouseEvent::Restored(event) => self.reindex(&event.id).await?,
+            // 只有标签参与检索，维护人不在索引里
+            HouseEvent::Patched(event) => {
+                if let Some(mut document) = self.search_index.find(&event.house_id)? {
+                    document.patch_tags(&event);
+                    self.search_index.upsert(&[document])?;
+                }
+            }
+            // 状态不参与全文检索
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+// 小区名称、地址变化时，更新小区下所有房源的索引
+pub struct CommunitySearchListener {
+    search_index: Arc<HouseSearchIndex>,
+}
+
+impl CommunitySearchListener {
+    pub fn new(search_index: Arc<HouseSearchIndex>) -> Self {
+        CommunitySearchListener { search_index }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventListener<CommunityEvent> for CommunitySearchListener {
+    async fn handle(&self, event: EventEnvelope<CommunityEvent>) -> anyhow::Result<()> {
+        let CommunityEvent::Updated(community) = event.payload else {
+            return Ok(());
+        };
+        let Some(community_id) = community.id else {
+            return Ok(());
+        };
+
+        let documents = self
+            .search_index
+            .find_by_community(&community_id)?
+            .into_iter()
+            .map(|mut document| {
+                if let Some(name) = &community.name {
+                    document.community_name = name.clone();
+                }
+                if let Some(address) = &community.address {
+                    document.community_address = address.clone();
+                }
+                if let Some(district) = &community.district {
+                    document.district = district.clone();
+                }
+                document
+            })
+            .collect::<Vec<_>>();
+
+        if !documents.is_empty() {
+            self.search_index.upsert(&documents)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,3 +1,6 @@
 pub mod community;
 pub mod house;
+pub mod house_price;
+pub mod house_search;
 pub mod owner;
+pub mod user_system;
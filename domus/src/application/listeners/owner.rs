@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use event_bus::EventListener;
+use event_bus::{EventEnvelope, EventListener};
 
 use crate::{
     application::queries::owner::OwnerQueryService, domain::owner::events::owner::OwnerEvent,
@@ -20,20 +20,19 @@ impl OwnerEventListener {
 
 #[async_trait::async_trait]
 impl EventListener<OwnerEvent> for OwnerEventListener {
-    async fn handle(&self, event: OwnerEvent) {
-        match event {
+    async fn handle(&self, event: EventEnvelope<OwnerEvent>) -> anyhow::Result<()> {
+        match event.payload {
             OwnerEvent::Created(event) => {
-                self.owner_query_service.create(event).await.unwrap();
+                self.owner_query_service.create(event).await?;
             }
             OwnerEvent::Updated(event) => {
-                self.owner_query_service.update(event).await.unwrap();
+                self.owner_query_service.update(event).await?;
             }
             OwnerEvent::Deleted(event) => {
-                self.owner_query_service
-                    .delete(&event.owner_id)
-                    .await
-                    .unwrap();
+                self.owner_query_service.delete(&event.owner_id).await?;
             }
         }
+
+        Ok(())
     }
 }
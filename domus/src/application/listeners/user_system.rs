@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use casbin::{CoreApi, Enforcer};
+use event_bus::{EventEnvelope, EventListener};
+use tokio::sync::Mutex;
+
+use crate::{
+    application::services::favorite::FavoriteService,
+    infrastructure::user_system::events::{RoleEvent, UserEvent},
+};
+
+// 用户被删除后清理其收藏
+pub struct UserEventListener {
+    favorite_service: Arc<FavoriteService>,
+}
+
+impl UserEventListener {
+    pub fn new(favorite_service: Arc<FavoriteService>) -> Self {
+        UserEventListener { favorite_service }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventListener<UserEvent> for UserEventListener {
+    async fn handle(&self, event: EventEnvelope<UserEvent>) -> anyhow::Result<()> {
+        if let Some(event) = event.payload.user_deleted {
+            self.favorite_service
+                .delete_user_favorites(&event.id)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+// 角色或权限变化后重新加载 casbin 策略
+pub struct RoleEventListener {
+    enforcer: Arc<Mutex<Enforcer>>,
+}
+
+impl RoleEventListener {
+    pub fn new(enforcer: Arc<Mutex<Enforcer>>) -> Self {
+        RoleEventListener { enforcer }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventListener<RoleEvent> for RoleEventListener {
+    async fn handle(&self, _event: EventEnvelope<RoleEvent>) -> anyhow::Result<()> {
+        self.enforcer.lock().await.load_policy().await?;
+        Ok(())
+    }
+}
@@ -1,10 +1,26 @@
 use std::sync::Arc;
 
 use crate::{
-    domain::house::value_objects::house::{ApartmentType, House},
+    application::queries::{
+        house_filter::{
+            recently_reduced, HouseRangeFilter, HouseSearchResult, HouseSort, NearFilter,
+        },
+        house_map::{CommunityHouseStats, HouseMap, HouseMapRequest, MapLevel},
+    },
+    domain::house::{
+        events::house_patched::HousePatchedEvent,
+        value_objects::{
+            dictionary::{
+                Dictionary, HouseDecoration, HouseOrientation, HouseStatus, Purpose,
+                TransactionType,
+            },
+            house::{ApartmentType, House},
+        },
+    },
+    infrastructure::search::house_index::HouseSearchIndex,
     infrastructure::{
         dto::house_data_dto::{CommunityWithHouseCount, HouseDataDto},
-        entitiy::{community_query, house_query, owner_query},
+        entitiy::{community_query, house_aggregate, house_query, owner_query},
     },
 };
 use chrono::Utc;
@@ -12,7 +28,8 @@ use sea_orm::{
     prelude::DateTimeUtc,
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
-    Condition, DbConn, EntityTrait, JoinType, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+    Condition, DbConn, EntityTrait, JoinType, Order, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, QueryTrait,
 };
 use sea_orm::{ColumnTrait, RelationTrait};
 use serde::Deserialize;
@@ -21,15 +38,28 @@ use shared_dto::table_data::TableDataResponse;
 
 pub struct HouseQueryService {
     pool: Arc<DbConn>,
+    search_index: Option<Arc<HouseSearchIndex>>,
 }
 
 impl HouseQueryService {
     pub fn new(pool: Arc<DbConn>) -> Self {
-        HouseQueryService { pool }
+        HouseQueryService {
+            pool,
+            search_index: None,
+        }
+    }
+
+    // 启用关键词搜索
+    pub fn search_index(mut self, search_index: Arc<HouseSearchIndex>) -> Self {
+        self.search_index = Some(search_index);
+        self
     }
 
     // 创建房源
-    pub async fn create(&self, event: House) -> anyhow::Result<()> {
+    pub async fn create(&self, mut event: House) -> anyhow::Result<()> {
+        // 历史事件里可能还是别名，回放时一并规范
+        event.normalize();
+        let unit_price = event.unit_price();
         let model = house_query::ActiveModel {
             id: Set(event.id.unwrap().clone()),
             community_id: event.community_id.map_or(NotSet, Set),
@@ -51,6 +81,7 @@ impl HouseQueryService {
             use_area: Set(event.use_area),
             floor_height: Set(event.floor_height),
             house_decoration: Set(event.house_decoration),
+            unit_price: Set(unit_price),
             sale_price: Set(event.sale_price),
             rent_price: Set(event.rent_price),
             rent_low_price: Set(event.rent_low_price),
@@ -89,6 +120,7 @@ impl HouseQueryService {
             external_sync: Set(event.external_sync),
             remark: Set(event.remark),
             images: Set(Some(serde_json::to_value(event.images).unwrap())),
+            maintainer_id: Set(event.maintainer_id.or(event.created_by.clone())),
             created_by: Set(event.created_by),
             ..Default::default()
         };
@@ -98,11 +130,14 @@ impl HouseQueryService {
     }
 
     // 更新房源
-    pub async fn update(&self, event: House) -> anyhow::Result<()> {
+    pub async fn update(&self, mut event: House) -> anyhow::Result<()> {
+        event.normalize();
+        let unit_price = event.unit_price();
         let model = house_query::ActiveModel {
             id: Set(event.id.unwrap().clone()),
             community_id: event.community_id.map_or(NotSet, Set),
             owner_id: Set(event.owner_id),
+            maintainer_id: event.maintainer_id.map_or(NotSet, |id| Set(Some(id))),
             title: Set(event.title.clone()),
             purpose: event.purpose.map_or(NotSet, Set),
             house_address: event.house_address.map_or(NotSet, Set),
@@ -120,6 +155,7 @@ impl HouseQueryService {
             use_area: Set(event.use_area),
             floor_height: Set(event.floor_height),
             house_decoration: Set(event.house_decoration),
+            unit_price: Set(unit_price),
             sale_price: Set(event.sale_price),
             rent_price: Set(event.rent_price),
             rent_low_price: Set(event.rent_low_price),
@@ -178,16 +214,224 @@ impl HouseQueryService {
         Ok(())
     }
 
+    // 批量修改的部分字段，其他字段保持不变
+    pub async fn patch(&self, event: &HousePatchedEvent) -> anyhow::Result<()> {
+        let Some(row) = house_query::Entity::find_by_id(&event.house_id)
+            .one(self.pool.as_ref())
+            .await?
+        else {
+            return Ok(());
+        };
+
+        let mut house = House {
+            maintainer_id: row.maintainer_id,
+            tags: row.tags.and_then(|tags| serde_json::from_value(tags).ok()),
+            ..Default::default()
+        };
+        event.apply(&mut house);
+
+        let model = house_query::ActiveModel {
+            id: Set(event.house_id.clone()),
+            maintainer_id: Set(house.maintainer_id),
+            tags: Set(Some(serde_json::to_value(house.tags)?)),
+            ..Default::default()
+        };
+        model.update(self.pool.as_ref()).await?;
+        Ok(())
+    }
+
+    // 彻底删除房源，同步时读模型里没有的房源视为已删除
+    pub async fn purge(&self, house_id: &str) -> anyhow::Result<()> {
+        house_query::Entity::delete_by_id(house_id)
+            .exec(self.pool.as_ref())
+            .await?;
+        Ok(())
+    }
+
+    // 从回收站恢复房源
+    pub async fn restore(&self, house_id: &str) -> anyhow::Result<()> {
+        let model = house_query::ActiveModel {
+            id: Set(house_id.to_string()),
+            deleted_at: Set(None),
+            ..Default::default()
+        };
+
+        model.update(self.pool.as_ref()).await?;
+        Ok(())
+    }
+
+    // 回收站：已删除的房源，按删除时间倒序。已删除的房源不在全文索引里，不支持关键词搜索
+    pub async fn find_deleted(
+        &self,
+        mut params: HouseRequest,
+    ) -> anyhow::Result<TableDataResponse<HouseDataDto>> {
+        params.not_exclude_deleted = Some(true);
+        let condition = Self::condition(&params)?.add(house_query::Column::DeletedAt.is_not_null());
+
+        let page = params.page.max(1);
+        let page_size = params.page_size.clamp(1, 10000);
+        let paginator = house_query::Entity::find()
+            .join(
+                JoinType::LeftJoin,
+                house_query::Relation::CommunityQuery.def(),
+            )
+            .join(JoinType::LeftJoin, house_query::Relation::OwnerQuery.def())
+            .filter(condition)
+            .order_by_desc(house_query::Column::DeletedAt)
+            .select_also(community_query::Entity)
+            .select_also(owner_query::Entity)
+            .paginate(self.pool.as_ref(), page_size);
+
+        let total = paginator.num_items().await?;
+        let data = paginator
+            .fetch_page(page - 1)
+            .await?
+            .into_iter()
+            .map(|(house, community, owner)| HouseDataDto::new(house, community, owner))
+            .collect::<Vec<HouseDataDto>>();
+
+        Ok(TableDataResponse::new(data, total))
+    }
+
+    // 变更房源状态
+    pub async fn change_status(&self, house_id: &str, status: HouseStatus) -> anyhow::Result<()> {
+        let model = house_query::ActiveModel {
+            id: Set(house_id.to_string()),
+            house_status: Set(status.as_str().to_string()),
+            ..Default::default()
+        };
+
+        model.update(self.pool.as_ref()).await?;
+        Ok(())
+    }
+
     // 查询房源列表
     pub async fn find_all(
         &self,
         params: HouseRequest,
+    ) -> anyhow::Result<TableDataResponse<HouseDataDto>> {
+        self.find_page(params, false).await
+    }
+
+    // 查询房源列表，关键词命中的房源不截断，超过上限时返回错误，用于导出
+    pub async fn find_all_complete(
+        &self,
+        params: HouseRequest,
+    ) -> anyhow::Result<TableDataResponse<HouseDataDto>> {
+        self.find_page(params, true).await
+    }
+
+    async fn find_page(
+        &self,
+        params: HouseRequest,
+        complete: bool,
     ) -> anyhow::Result<TableDataResponse<HouseDataDto>> {
         let mut condition = Condition::all();
+
+        // 关键词搜索，没有命中直接返回
+        let search = if complete {
+            HouseSearchResult::search_complete(self.search_index.as_deref(), params.q.as_deref())?
+        } else {
+            HouseSearchResult::search(self.search_index.as_deref(), params.q.as_deref())?
+        };
+        if let Some(search) = &search {
+            if search.is_empty() {
+                return Ok(TableDataResponse::new(Vec::new(), 0));
+            }
+            condition = condition.add(search.condition());
+        }
+
+        condition = condition.add(Self::condition(&params)?);
+
+        // 分页逻辑
+        let page = params.page.max(1);
+        let page_size = params.page_size.clamp(1, 10000);
+        let query = house_query::Entity::find()
+            .join(
+                JoinType::LeftJoin,
+                house_query::Relation::CommunityQuery.def(),
+            )
+            .join(JoinType::LeftJoin, house_query::Relation::OwnerQuery.def())
+            .filter(condition.clone());
+
+        // 周边搜索没有指定排序时由近到远，关键词搜索仍按相关度
+        let sort = params
+            .sort
+            .clone()
+            .or_else(|| (params.near.is_some() && search.is_none()).then(HouseSort::nearest));
+        let query = match &sort {
+            Some(sort) => sort.apply(query, params.near.as_ref())?,
+            None => query,
+        };
+
+        let paginator = query
+            .apply_if(search.as_ref(), |query, search| {
+                query.order_by(search.order(), Order::Asc)
+            })
+            .order_by_desc(house_query::Column::UpdatedAt)
+            // 更新时间相同时按 id 排，分页（如导出）不会重复或遗漏
+            .order_by_asc(house_query::Column::Id)
+            .select_also(community_query::Entity)
+            .select_also(owner_query::Entity)
+            .paginate(self.pool.as_ref(), page_size);
+
+        let total = paginator.num_items().await?;
+        let data = paginator.fetch_page(page - 1).await?;
+
+        let data = data
+            .into_iter()
+            .map(|(house, community, owner)| {
+                let hit = search.as_ref().and_then(|search| search.hit(&house.id));
+                HouseDataDto::new(house, community, owner)
+                    .with_search_hit(hit)
+                    .with_distance(params.near.as_ref())
+            })
+            .collect::<Vec<HouseDataDto>>();
+
+        Ok(TableDataResponse::new(data, total))
+    }
+
+    // 按列表的筛选条件查出房源 id，最多 limit 条，用于批量操作
+    pub async fn find_ids(&self, params: HouseRequest, limit: u64) -> anyhow::Result<Vec<String>> {
+        let mut condition = Condition::all();
+
+        // 批量操作需要全部命中的房源
+        let search =
+            HouseSearchResult::search_complete(self.search_index.as_deref(), params.q.as_deref())?;
+        if let Some(search) = &search {
+            if search.is_empty() {
+                return Ok(Vec::new());
+            }
+            condition = condition.add(search.condition());
+        }
+        condition = condition.add(Self::condition(&params)?);
+
+        let ids = house_query::Entity::find()
+            .select_only()
+            .column(house_query::Column::Id)
+            .join(
+                JoinType::LeftJoin,
+                house_query::Relation::CommunityQuery.def(),
+            )
+            .filter(condition)
+            .order_by_desc(house_query::Column::UpdatedAt)
+            .order_by_asc(house_query::Column::Id)
+            .limit(limit)
+            .into_tuple::<String>()
+            .all(self.pool.as_ref())
+            .await?;
+
+        Ok(ids)
+    }
+
+    // 房源列表的筛选条件，列表和地图聚合共用
+    fn condition(params: &HouseRequest) -> anyhow::Result<Condition> {
+        let mut condition = Condition::all();
+
         if let Some(AmapBounds {
             north_east,
             south_west,
-        }) = params.amap_bounds
+        }) = &params.amap_bounds
         {
             condition = condition
                 .add(community_query::Column::Lat.gte(south_west.lat))
@@ -196,26 +440,38 @@ impl HouseQueryService {
                 .add(community_query::Column::Lng.lte(north_east.lng));
         }
 
-        if let Some(transaction_type) = params.transaction_type {
-            condition = condition.add(house_query::Column::TransactionType.eq(transaction_type));
+        // 周边搜索
+        if let Some(near) = &params.near {
+            condition = condition.add(near.condition()?);
+        }
+
+        if let Some(transaction_type) = &params.transaction_type {
+            condition =
+                condition.add(house_query::Column::TransactionType.eq(transaction_type.as_str()));
+        }
+
+        if let Some(purpose) = &params.purpose {
+            condition = condition.add(house_query::Column::Purpose.eq(purpose.as_str()));
         }
 
-        if let Some(purpose) = params.purpose {
-            condition = condition.add(house_query::Column::Purpose.eq(purpose));
+        if let Some(maintainer_id) = &params.maintainer_id {
+            condition = condition.add(house_query::Column::MaintainerId.eq(maintainer_id.as_str()));
         }
 
         // 朝向
-        if let Some(house_orientation) = params.house_orientation {
-            condition = condition.add(house_query::Column::HouseOrientation.eq(house_orientation));
+        if let Some(house_orientation) = &params.house_orientation {
+            condition =
+                condition.add(house_query::Column::HouseOrientation.eq(house_orientation.as_str()));
         }
 
         // 装修
-        if let Some(house_decoration) = params.house_decoration {
-            condition = condition.add(house_query::Column::HouseDecoration.eq(house_decoration));
+        if let Some(house_decoration) = &params.house_decoration {
+            condition =
+                condition.add(house_query::Column::HouseDecoration.eq(house_decoration.as_str()));
         }
 
         // apartment_type
-        if let Some(apartment_type) = params.apartment_type {
+        if let Some(apartment_type) = &params.apartment_type {
             if let Some(r) = apartment_type.room {
                 condition = condition.add(house_query::Column::Room.eq(r));
             }
@@ -236,141 +492,71 @@ impl HouseQueryService {
             }
         }
 
-        // 售价
-        if let Some(price) = params.price {
-            // 如果是 1000+
-            if price.ends_with('+') {
-                let price = price
-                    .trim_end_matches('+')
-                    .parse::<f32>()
-                    .unwrap_or_default();
-                condition = condition.add(house_query::Column::SalePrice.lte(price));
-            }
-
-            let price = price
-                .split('-')
-                .map(|p| p.parse::<f32>().unwrap_or_default())
-                .collect::<Vec<f32>>();
-
-            // 范围查询 1000-2000
-            if price.len() == 2 {
-                condition =
-                    condition.add(house_query::Column::SalePrice.between(price[0], price[1]));
-            }
+        // 售价、租金、面积、单价、楼层、建筑年代
+        condition = condition.add(params.range.condition()?);
 
-            // 大于等于 1000
-            if price.len() == 1 {
-                condition = condition.add(house_query::Column::SalePrice.gte(price[0]));
-            }
+        if let Some(updated_at) = params.updated_at {
+            condition = condition.add(house_query::Column::UpdatedAt.gt(updated_at));
         }
 
-        // 租金
-        if let Some(rent) = params.rent {
-            // 如果是 1000+
-            if rent.ends_with('+') {
-                let rent = rent
-                    .trim_end_matches('+')
-                    .parse::<f32>()
-                    .unwrap_or_default();
-                condition = condition.add(house_query::Column::RentPrice.lte(rent));
-            }
-
-            let rent = rent
-                .split('-')
-                .map(|p| p.parse::<f32>().unwrap_or_default())
-                .collect::<Vec<f32>>();
-
-            // 范围查询 1000-2000
-            if rent.len() == 2 {
-                condition = condition.add(house_query::Column::RentPrice.between(rent[0], rent[1]));
-            }
-
-            // 大于等于 1000
-            if rent.len() == 1 {
-                condition = condition.add(house_query::Column::SalePrice.gte(rent[0]));
-            }
+        // 最近降价
+        if let Some(days) = params.reduced_within_days {
+            condition = condition.add(recently_reduced(days));
         }
 
-        // 面积
-        if let Some(area) = params.area {
-            // 如果是 1000+
-            if area.ends_with('+') {
-                let area = area
-                    .trim_end_matches('+')
-                    .parse::<f32>()
-                    .unwrap_or_default();
-                condition = condition.add(house_query::Column::BuildingArea.lte(area));
-            }
-
-            let area = area
-                .split('-')
-                .map(|p| p.parse::<f32>().unwrap_or_default())
-                .collect::<Vec<f32>>();
+        // 不排除已删除
+        if !params.not_exclude_deleted.unwrap_or(false) {
+            condition = condition.add(house_query::Column::DeletedAt.is_null());
+        }
 
-            // 范围查询 1000-2000
-            if area.len() == 2 {
-                condition =
-                    condition.add(house_query::Column::BuildingArea.between(area[0], area[1]));
-            }
+        Ok(condition)
+    }
 
-            // 大于等于 1000
-            if area.len() == 1 {
-                condition = condition.add(house_query::Column::BuildingArea.gte(area[0]));
-            }
-        }
+    // 地图聚合，按缩放级别分别按区县、网格、小区统计房源
+    pub async fn map_aggregate(&self, params: HouseMapRequest) -> anyhow::Result<HouseMap> {
+        let level = MapLevel::from_zoom(params.zoom);
+        let params = params.filter;
+        let mut condition = Condition::all();
 
-        // 楼层
-        if let Some(floor) = params.floor {
-            match floor.as_str() {
-                "low" => {
-                    condition = condition.add(house_query::Column::Level.lt(3));
-                }
-                "middle" => {
-                    condition = condition.add(house_query::Column::Level.between(3, 6));
-                }
-                "high" => {
-                    condition = condition.add(house_query::Column::Level.gt(6));
-                }
-                _ => {
-                    // 如果不是 low, middle, high 则不做处理
-                    condition = condition.add(house_query::Column::Level.eq(floor));
-                }
+        // 关键词搜索，没有命中直接返回
+        let search = HouseSearchResult::search(self.search_index.as_deref(), params.q.as_deref())?;
+        if let Some(search) = &search {
+            if search.is_empty() {
+                return Ok(HouseMap::aggregate(level, Vec::new()));
             }
+            condition = condition.add(search.condition());
         }
 
-        if let Some(updated_at) = params.updated_at {
-            condition = condition.add(house_query::Column::UpdatedAt.gt(updated_at));
-        }
+        condition = condition
+            .add(Self::condition(&params)?)
+            .add(community_query::Column::Id.is_not_null());
 
-        // 不排除已删除
-        if !params.not_exclude_deleted.unwrap_or(false) {
-            condition = condition.add(house_query::Column::DeletedAt.is_null());
-        }
-
-        // 分页逻辑
-        let page = params.page.max(1);
-        let page_size = params.page_size.min(10000).max(1);
-        let paginator = house_query::Entity::find()
+        let rows = house_query::Entity::find()
             .join(
                 JoinType::LeftJoin,
                 house_query::Relation::CommunityQuery.def(),
             )
-            .join(JoinType::LeftJoin, house_query::Relation::OwnerQuery.def())
-            .filter(condition.clone())
-            .order_by_desc(house_query::Column::UpdatedAt)
-            .select_also(community_query::Entity)
-            .select_also(owner_query::Entity)
-            .paginate(self.pool.as_ref(), page_size as u64);
-
-        let total = paginator.num_items().await?;
-        let data = paginator.fetch_page(page - 1).await?;
-
-        let data = data
-            .into_iter()
-            .map(|(house, community, owner)| HouseDataDto::new(house, community, owner))
-            .collect::<Vec<HouseDataDto>>();
+            .filter(condition)
+            .select_only()
+            .column(community_query::Column::Id)
+            .column(community_query::Column::Name)
+            .column(community_query::Column::District)
+            .column(community_query::Column::Adcode)
+            .column(community_query::Column::Lat)
+            .column(community_query::Column::Lng)
+            .expr_as(house_query::Column::Id.count(), "house_count")
+            .expr_as(house_query::Column::SalePrice.sum(), "sale_price_sum")
+            .expr_as(house_query::Column::SalePrice.count(), "sale_price_count")
+            .expr_as(house_query::Column::UnitPrice.sum(), "unit_price_sum")
+            .expr_as(house_query::Column::UnitPrice.count(), "unit_price_count")
+            .expr_as(house_query::Column::RentPrice.sum(), "rent_price_sum")
+            .expr_as(house_query::Column::RentPrice.count(), "rent_price_count")
+            .group_by(community_query::Column::Id)
+            .into_model::<CommunityHouseStats>()
+            .all(self.pool.as_ref())
+            .await?;
 
-        Ok(TableDataResponse::new(data, total as u64))
+        Ok(HouseMap::aggregate(level, rows))
     }
 
     // 根据id查询
@@ -387,10 +573,20 @@ impl HouseQueryService {
             .one(self.pool.as_ref())
             .await;
 
-        match data {
-            Ok(Some((house, community, owner))) => Some(HouseDataDto::new(house, community, owner)),
-            _ => None,
-        }
+        let mut data = match data {
+            Ok(Some((house, community, owner))) => HouseDataDto::new(house, community, owner),
+            _ => return None,
+        };
+
+        // 带上聚合的版本号，保存时用于检测并发修改
+        data.version = house_aggregate::Entity::find_by_id(id)
+            .one(self.pool.as_ref())
+            .await
+            .ok()
+            .flatten()
+            .map(|model| model.version);
+
+        Some(data)
     }
 
     // 查找最新的房源
@@ -446,149 +642,17 @@ impl HouseQueryService {
     ) -> anyhow::Result<Vec<CommunityWithHouseCount>> {
         let mut condition = Condition::all();
 
-        condition = condition.add(house_query::Column::DeletedAt.is_null());
-
-        if let Some(transaction_type) = params.transaction_type {
-            condition = condition.add(house_query::Column::TransactionType.eq(transaction_type));
-        }
-
-        if let Some(purpose) = params.purpose {
-            condition = condition.add(house_query::Column::Purpose.eq(purpose));
-        }
-
-        // 朝向
-        if let Some(house_orientation) = params.house_orientation {
-            condition = condition.add(house_query::Column::HouseOrientation.eq(house_orientation));
-        }
-
-        // 装修
-        if let Some(house_decoration) = params.house_decoration {
-            condition = condition.add(house_query::Column::HouseDecoration.eq(house_decoration));
-        }
-
-        // apartment_type
-        if let Some(apartment_type) = params.apartment_type {
-            if let Some(r) = apartment_type.room {
-                condition = condition.add(house_query::Column::Room.eq(r));
-            }
-            if let Some(h) = apartment_type.hall {
-                condition = condition.add(house_query::Column::Hall.eq(h));
-            }
-            if let Some(b) = apartment_type.bathroom {
-                condition = condition.add(house_query::Column::Bathroom.eq(b));
-            }
-            if let Some(k) = apartment_type.kitchen {
-                condition = condition.add(house_query::Column::Kitchen.eq(k));
-            }
-            if let Some(t) = apartment_type.terrace {
-                condition = condition.add(house_query::Column::Terrace.eq(t));
-            }
-            if let Some(b) = apartment_type.balcony {
-                condition = condition.add(house_query::Column::Balcony.eq(b));
-            }
-        }
-
-        // 售价
-        if let Some(price) = params.price {
-            // 如果是 1000+
-            if price.ends_with('+') {
-                let price = price
-                    .trim_end_matches('+')
-                    .parse::<f32>()
-                    .unwrap_or_default();
-                condition = condition.add(house_query::Column::SalePrice.lte(price));
-            }
-
-            let price = price
-                .split('-')
-                .map(|p| p.parse::<f32>().unwrap_or_default())
-                .collect::<Vec<f32>>();
-
-            // 范围查询 1000-2000
-            if price.len() == 2 {
-                condition =
-                    condition.add(house_query::Column::SalePrice.between(price[0], price[1]));
-            }
-
-            // 大于等于 1000
-            if price.len() == 1 {
-                condition = condition.add(house_query::Column::SalePrice.gte(price[0]));
-            }
-        }
-
-        // 租金
-        if let Some(rent) = params.rent {
-            // 如果是 1000+
-            if rent.ends_with('+') {
-                let rent = rent
-                    .trim_end_matches('+')
-                    .parse::<f32>()
-                    .unwrap_or_default();
-                condition = condition.add(house_query::Column::RentPrice.lte(rent));
-            }
-
-            let rent = rent
-                .split('-')
-                .map(|p| p.parse::<f32>().unwrap_or_default())
-                .collect::<Vec<f32>>();
-
-            // 范围查询 1000-2000
-            if rent.len() == 2 {
-                condition = condition.add(house_query::Column::RentPrice.between(rent[0], rent[1]));
-            }
-
-            // 大于等于 1000
-            if rent.len() == 1 {
-                condition = condition.add(house_query::Column::SalePrice.gte(rent[0]));
+        // 关键词搜索，没有命中直接返回
+        let search = HouseSearchResult::search(self.search_index.as_deref(), params.q.as_deref())?;
+        if let Some(search) = &search {
+            if search.is_empty() {
+                return Ok(Vec::new());
             }
+            condition = condition.add(search.condition());
         }
 
-        // 面积
-        if let Some(area) = params.area {
-            // 如果是 1000+
-            if area.ends_with('+') {
-                let area = area
-                    .trim_end_matches('+')
-                    .parse::<f32>()
-                    .unwrap_or_default();
-                condition = condition.add(house_query::Column::BuildingArea.lte(area));
-            }
-
-            let area = area
-                .split('-')
-                .map(|p| p.parse::<f32>().unwrap_or_default())
-                .collect::<Vec<f32>>();
-
-            // 范围查询 1000-2000
-            if area.len() == 2 {
-                condition =
-                    condition.add(house_query::Column::BuildingArea.between(area[0], area[1]));
-            }
-
-            // 大于等于 1000
-            if area.len() == 1 {
-                condition = condition.add(house_query::Column::BuildingArea.gte(area[0]));
-            }
-        }
-
-        // 楼层
-        if let Some(floor) = params.floor {
-            match floor.as_str() {
-                "low" => {
-                    condition = condition.add(house_query::Column::Level.lt(3));
-                }
-                "middle" => {
-                    condition = condition.add(house_query::Column::Level.between(3, 6));
-                }
-                "high" => {
-                    condition = condition.add(house_query::Column::Level.gt(6));
-                }
-                _ => {
-                    // 如果不是 low, middle, high 则不做处理
-                    condition = condition.add(house_query::Column::Level.eq(floor));
-                }
-            }
-        }
+        // 与房源列表的筛选条件一致
+        condition = condition.add(Self::condition(&params)?);
 
         // 需要join 小区， 小区id,房源个数
         let data = house_query::Entity::find()
@@ -618,20 +682,29 @@ impl HouseQueryService {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 
 pub struct HouseRequest {
+    #[serde(default)]
     pub page: u64,
+    #[serde(default)]
     pub page_size: u64,
+    // 关键词，搜索房源标题、地址、小区名称等
+    pub q: Option<String>,
     pub amap_bounds: Option<AmapBounds>,
+    // 周边搜索，如 {"lat": 31.82, "lng": 117.22, "radius_m": 2000}
+    pub near: Option<NearFilter>,
     pub updated_at: Option<DateTimeUtc>,
-    pub transaction_type: Option<String>,
-    pub purpose: Option<String>,
-    pub house_orientation: Option<String>,
-    pub house_decoration: Option<String>,
+    pub transaction_type: Option<TransactionType>,
+    pub purpose: Option<Purpose>,
+    pub house_orientation: Option<HouseOrientation>,
+    pub house_decoration: Option<HouseDecoration>,
     pub apartment_type: Option<ApartmentType>,
-    pub price: Option<String>,
-    pub rent: Option<String>,
-    pub area: Option<String>,
-    // "low", "middle", "high"
-    pub floor: Option<String>,
+    #[serde(flatten)]
+    pub range: HouseRangeFilter,
+    // 最近多少天内降过价
+    pub reduced_within_days: Option<u32>,
+    // 维护人
+    pub maintainer_id: Option<String>,
+    // 排序，如 "unit_price:asc,area:desc"，默认按更新时间倒序，周边搜索时默认由近到远
+    pub sort: Option<HouseSort>,
     // 不排除已删除的
     pub not_exclude_deleted: Option<bool>,
 }
@@ -0,0 +1,240 @@
+use std::{collections::HashMap, sync::Arc};
+
+use sea_orm::{ColumnTrait, DbConn, EntityTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait};
+use serde::{Deserialize, Serialize};
+use shared_dto::table_data::TableDataResponse;
+
+use crate::{
+    domain::house::value_objects::house_address::{HouseAddress, DUPLICATE_THRESHOLD},
+    infrastructure::entitiy::{community_query, house_aggregate},
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateHouse {
+    pub house_id: String,
+    pub house_address: String,
+    // 统一后的地址
+    pub normalized: String,
+    // 与被比较地址的相似度
+    pub similarity: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    pub community_id: String,
+    pub community_name: Option<String>,
+    pub houses: Vec<DuplicateHouse>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DuplicateReportRequest {
+    // 只检查一个小区
+    pub community_id: Option<String>,
+    // 相似度阈值，默认 0.85
+    pub threshold: Option<f64>,
+    #[serde(default)]
+    pub page: u64,
+    #[serde(default)]
+    pub page_size: u64,
+}
+
+// 按地址相似度识别同一小区内的疑似重复房源，读取 house_aggregate 与 exists_address 保持一致
+pub struct HouseDuplicateQueryService {
+    pool: Arc<DbConn>,
+}
+
+impl HouseDuplicateQueryService {
+    pub fn new(pool: Arc<DbConn>) -> Self {
+        Self { pool }
+    }
+
+    // 小区内与该地址相似的房源，按相似度从高到低
+    pub async fn find_similar(
+        &self,
+        community_id: &str,
+        house_address: &str,
+        self_id: Option<&str>,
+    ) -> anyhow::Result<Vec<DuplicateHouse>> {
+        let address = HouseAddress::parse(house_address);
+        let rows = self.addresses(Some(community_id)).await?;
+
+        let mut houses: Vec<DuplicateHouse> = rows
+            .into_iter()
+            .filter(|(house_id, ..)| Some(house_id.as_str()) != self_id)
+            .filter_map(|(house_id, _, house_address)| {
+                let other = HouseAddress::parse(&house_address);
+                let similarity = address.similarity(&other);
+                (similarity >= DUPLICATE_THRESHOLD).then(|| DuplicateHouse {
+                    house_id,
+                    house_address,
+                    normalized: other.normalized(),
+                    similarity: Some(similarity),
+                })
+            })
+            .collect();
+        houses.sort_by(|a, b| {
+            b.similarity
+                .unwrap_or(0.0)
+                .total_cmp(&a.similarity.unwrap_or(0.0))
+        });
+
+        Ok(houses)
+    }
+
+    // 疑似重复房源报告，用于数据清理
+    pub async fn report(
+        &self,
+        request: DuplicateReportRequest,
+    ) -> anyhow::Result<TableDataResponse<DuplicateGroup>> {
+        let threshold = request
+            .threshold
+            .unwrap_or(DUPLICATE_THRESHOLD)
+            .clamp(0.5, 1.0);
+        let rows = self.addresses(request.community_id.as_deref()).await?;
+
+        let mut communities: Vec<(String, Vec<(String, String)>)> = Vec::new();
+        for (house_id, community_id, house_address) in rows {
+            match communities.last_mut() {
+                Some((id, houses)) if *id == community_id => houses.push((house_id, house_address)),
+                _ => communities.push((community_id, vec![(house_id, house_address)])),
+            }
+        }
+
+        let mut groups = Vec::new();
+        for (community_id, houses) in communities {
+            let addresses: Vec<HouseAddress> = houses
+                .iter()
+                .map(|(_, address)| HouseAddress::parse(address))
+                .collect();
+
+            for members in group_similar(&addresses, threshold) {
+                groups.push(DuplicateGroup {
+                    community_id: community_id.clone(),
+                    community_name: None,
+                    houses: members
+                        .into_iter()
+                        .map(|index| DuplicateHouse {
+                            house_id: houses[index].0.clone(),
+                            house_address: houses[index].1.clone(),
+                            normalized: addresses[index].normalized(),
+                            similarity: None,
+                        })
+                        .collect(),
+                });
+            }
+        }
+
+        let total = groups.len() as u64;
+        let page = request.page.max(1);
+        let page_size = request.page_size.clamp(1, 1000);
+        let mut groups: Vec<DuplicateGroup> = groups
+            .into_iter()
+            .skip(((page - 1) * page_size) as usize)
+            .take(page_size as usize)
+            .collect();
+
+        // 回填小区名称
+        let names: HashMap<String, String> = community_query::Entity::find()
+            .select_only()
+            .columns([community_query::Column::Id, community_query::Column::Name])
+            .filter(
+                community_query::Column::Id
+                    .is_in(groups.iter().map(|group| group.community_id.clone())),
+            )
+            .into_tuple::<(String, String)>()
+            .all(self.pool.as_ref())
+            .await?
+            .into_iter()
+            .collect();
+        for group in &mut groups {
+            group.community_name = names.get(&group.community_id).cloned();
+        }
+
+        Ok(TableDataResponse::new(groups, total))
+    }
+
+    // 未删除房源的 (id, 小区 id, 地址)，按小区排序
+    async fn addresses(
+        &self,
+        community_id: Option<&str>,
+    ) -> anyhow::Result<Vec<(String, String, String)>> {
+        Ok(house_aggregate::Entity::find()
+            .select_only()
+            .columns([
+                house_aggregate::Column::HouseId,
+                house_aggregate::Column::CommunityId,
+                house_aggregate::Column::HouseAddress,
+            ])
+            .filter(house_aggregate::Column::DeletedAt.is_null())
+            .apply_if(community_id, |query, community_id| {
+                query.filter(house_aggregate::Column::CommunityId.eq(community_id))
+            })
+            .order_by_asc(house_aggregate::Column::CommunityId)
+            .order_by_asc(house_aggregate::Column::CreatedAt)
+            .into_tuple::<(String, String, String)>()
+            .all(self.pool.as_ref())
+            .await?)
+    }
+}
+
+// 相似的地址两两相连，返回包含多套房源的连通分组
+fn group_similar(addresses: &[HouseAddress], threshold: f64) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..addresses.len()).collect();
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[i] = root;
+        root
+    }
+
+    for i in 0..addresses.len() {
+        for j in i + 1..addresses.len() {
+            if addresses[i].similarity(&addresses[j]) >= threshold {
+                let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                parent[b] = a;
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut index: HashMap<usize, usize> = HashMap::new();
+    for i in 0..addresses.len() {
+        let root = find(&mut parent, i);
+        let group = *index.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[group].push(i);
+    }
+
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::group_similar;
+    use crate::domain::house::value_objects::house_address::{HouseAddress, DUPLICATE_THRESHOLD};
+
+    #[test]
+    fn test_group_similar() {
+        let addresses: Vec<HouseAddress> = [
+            "11栋104",
+            "12栋104",
+            "11-104",
+            "11幢1单元104室",
+            "12-104",
+            "3栋201",
+        ]
+        .iter()
+        .map(|address| HouseAddress::parse(address))
+        .collect();
+
+        assert_eq!(
+            group_similar(&addresses, DUPLICATE_THRESHOLD),
+            vec![vec![0, 2, 3], vec![1, 4]]
+        );
+    }
+}
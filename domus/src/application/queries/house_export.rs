@@ -0,0 +1,367 @@
+use std::{collections::HashMap, sync::Arc};
+
+use actix_web::web::Bytes;
+use casbin::{CoreApi, Enforcer};
+use futures::{stream, Stream, StreamExt};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, DbConn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Mutex;
+use user_system::shared::claims::Claims;
+
+use crate::{
+    application::queries::house::{HouseQueryService, HouseRequest},
+    infrastructure::{
+        dto::house_data_dto::HouseDataDto,
+        entitiy::house_export_log,
+        spreadsheet::{write_csv, write_xlsx, SpreadsheetFormat},
+    },
+};
+
+// 每次从读模型取的房源数
+const PAGE_SIZE: u64 = 1000;
+// 单次导出的最大房源数
+const MAX_ROWS: u64 = 50000;
+
+// 导出业主电话、身份证号需要的权限，不对应实际路由，只在角色权限里配置
+pub const EXPORT_OWNER_PERMISSION: &str = "/api/domus/export/owner";
+
+// 可导出的列，key 为 HouseDataDto 展开后的字段路径，表头与导入时的列名一致
+pub struct ExportColumn {
+    pub key: &'static str,
+    pub header: &'static str,
+    // 业主联系方式，需要导出权限
+    pub owner: bool,
+}
+
+const fn column(key: &'static str, header: &'static str) -> ExportColumn {
+    ExportColumn {
+        key,
+        header,
+        owner: false,
+    }
+}
+
+const COLUMNS: &[ExportColumn] = &[
+    column("id", "房源ID"),
+    column("title", "标题"),
+    column("purpose", "用途"),
+    column("transaction_type", "交易类型"),
+    column("house_status", "状态"),
+    column("community.name", "小区"),
+    column("community.district", "行政区"),
+    column("community.address", "小区地址"),
+    column("house_address", "地址"),
+    column("apartment_type.room", "室"),
+    column("apartment_type.hall", "厅"),
+    column("apartment_type.bathroom", "卫"),
+    column("apartment_type.kitchen", "厨"),
+    column("apartment_type.terrace", "阳台"),
+    column("floor_range.door_number_from", "楼层"),
+    column("floor_range.door_number_to", "总楼层"),
+    column("stairs.stairs", "梯"),
+    column("stairs.rooms", "户"),
+    column("building_area", "建筑面积"),
+    column("use_area", "使用面积"),
+    column("sale_price", "售价"),
+    column("unit_price", "单价"),
+    column("rent_price", "租价"),
+    column("house_decoration", "装修"),
+    column("house_orientation", "朝向"),
+    column("house_type", "房屋类型"),
+    column("building_year", "建筑年代"),
+    column("property_rights", "产权性质"),
+    column("tags", "标签"),
+    column("source", "来源"),
+    column("delegate_number", "委托编号"),
+    column("remark", "备注"),
+    column("maintainer_id", "维护人"),
+    column("owner.name", "业主"),
+    ExportColumn {
+        key: "owner.phone",
+        header: "业主电话",
+        owner: true,
+    },
+    ExportColumn {
+        key: "owner.id_card",
+        header: "身份证号",
+        owner: true,
+    },
+    column("updated_at", "更新时间"),
+];
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HouseExportRequest {
+    // csv 或 xlsx，默认 csv
+    #[serde(default)]
+    pub format: SpreadsheetFormat,
+    // 导出的列，可以写字段路径或表头，不传时导出有权限的全部列
+    pub columns: Option<Vec<String>>,
+    // 与房源列表相同的筛选条件，分页参数不生效
+    #[serde(flatten)]
+    pub filter: HouseRequest,
+}
+
+// 导出操作的来源，写入导出记录
+pub struct HouseExportOperator {
+    pub operator_id: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+pub enum HouseExportBody {
+    // CSV 按页生成，边查边返回
+    Csv(Box<dyn Stream<Item = anyhow::Result<Bytes>> + Unpin>),
+    // Excel 需要整体写出
+    Xlsx(Vec<u8>),
+}
+
+pub struct HouseExport {
+    pub format: SpreadsheetFormat,
+    pub filename: String,
+    pub body: HouseExportBody,
+}
+
+// 按房源列表的筛选条件导出 CSV/Excel
+pub struct HouseExportService {
+    pool: Arc<DbConn>,
+    house_query_service: Arc<HouseQueryService>,
+    enforcer: Arc<Mutex<Enforcer>>,
+}
+
+impl HouseExportService {
+    pub fn new(
+        pool: Arc<DbConn>,
+        house_query_service: Arc<HouseQueryService>,
+        enforcer: Arc<Mutex<Enforcer>>,
+    ) -> Self {
+        Self {
+            pool,
+            house_query_service,
+            enforcer,
+        }
+    }
+
+    // 是否可以导出业主电话、身份证号
+    pub async fn include_owner(&self, user: &Claims) -> bool {
+        let enforcer = self.enforcer.lock().await;
+        user.rules.iter().any(|role| {
+            enforcer
+                .enforce((role.as_str(), EXPORT_OWNER_PERMISSION, "GET"))
+                .unwrap_or(false)
+        })
+    }
+
+    pub async fn export(
+        &self,
+        mut request: HouseExportRequest,
+        include_owner: bool,
+        operator: HouseExportOperator,
+    ) -> anyhow::Result<HouseExport> {
+        let columns = select_columns(request.columns.as_deref(), include_owner)?;
+
+        request.filter.page = 1;
+        request.filter.page_size = PAGE_SIZE;
+        let first = self
+            .house_query_service
+            .find_all_complete(request.filter.clone())
+            .await?;
+        if first.total > MAX_ROWS {
+            return Err(anyhow::anyhow!(
+                "单次最多导出 {} 套房源，请缩小筛选范围",
+                MAX_ROWS
+            ));
+        }
+
+        // 导出前记录，包含筛选条件和列，便于追查数据去向
+        house_export_log::ActiveModel {
+            operator_id: Set(operator.operator_id),
+            format: Set(request.format.as_str().to_string()),
+            columns: Set(Value::from(
+                columns.iter().map(|column| column.key).collect::<Vec<_>>(),
+            )),
+            filter: Set(Some(serde_json::to_value(&request.filter)?)),
+            include_owner: Set(columns.iter().any(|column| column.owner)),
+            row_count: Set(first.total),
+            ip_address: Set(operator.ip_address),
+            user_agent: Set(operator.user_agent),
+            ..Default::default()
+        }
+        .insert(self.pool.as_ref())
+        .await?;
+
+        let header: Vec<Value> = columns
+            .iter()
+            .map(|column| Value::from(column.header))
+            .collect();
+        let filename = format!(
+            "houses-{}.{}",
+            chrono::Local::now().format("%Y%m%d%H%M%S"),
+            request.format.as_str()
+        );
+        let pages = first.total.div_ceil(PAGE_SIZE).max(1);
+
+        let body = match request.format {
+            SpreadsheetFormat::Csv => {
+                let mut chunk = vec![header];
+                chunk.extend(first.list.iter().map(|dto| export_row(dto, &columns)));
+                let first_chunk = Bytes::from(write_csv(&chunk, true)?);
+
+                let house_query_service = self.house_query_service.clone();
+                let filter = request.filter;
+                let rest = stream::iter(2..=pages).then(move |page| {
+                    let house_query_service = house_query_service.clone();
+                    let filter = HouseRequest {
+                        page,
+                        ..filter.clone()
+                    };
+                    let columns = columns.clone();
+                    async move {
+                        let data = house_query_service.find_all_complete(filter).await?;
+                        let rows: Vec<Vec<Value>> = data
+                            .list
+                            .iter()
+                            .map(|dto| export_row(dto, &columns))
+                            .collect();
+                        Ok(Bytes::from(write_csv(&rows, false)?))
+                    }
+                });
+
+                HouseExportBody::Csv(Box::new(Box::pin(
+                    stream::once(async move { Ok(first_chunk) }).chain(rest),
+                )))
+            }
+            SpreadsheetFormat::Xlsx => {
+                let mut rows = vec![header];
+                rows.extend(first.list.iter().map(|dto| export_row(dto, &columns)));
+                for page in 2..=pages {
+                    let filter = HouseRequest {
+                        page,
+                        ..request.filter.clone()
+                    };
+                    let data = self.house_query_service.find_all_complete(filter).await?;
+                    rows.extend(data.list.iter().map(|dto| export_row(dto, &columns)));
+                }
+                HouseExportBody::Xlsx(write_xlsx(&rows)?)
+            }
+        };
+
+        Ok(HouseExport {
+            format: request.format,
+            filename,
+            body,
+        })
+    }
+}
+
+// 按请求选出导出的列，没有权限时不能导出业主联系方式
+fn select_columns(
+    keys: Option<&[String]>,
+    include_owner: bool,
+) -> anyhow::Result<Vec<&'static ExportColumn>> {
+    let Some(keys) = keys.filter(|keys| !keys.is_empty()) else {
+        return Ok(COLUMNS
+            .iter()
+            .filter(|column| include_owner || !column.owner)
+            .collect());
+    };
+
+    keys.iter()
+        .map(|key| {
+            let column = COLUMNS
+                .iter()
+                .find(|column| column.key == key || column.header == key)
+                .ok_or_else(|| anyhow::anyhow!("不支持导出的列: {}", key))?;
+            if column.owner && !include_owner {
+                return Err(anyhow::anyhow!("没有导出{}的权限", column.header));
+            }
+            Ok(column)
+        })
+        .collect()
+}
+
+// 把房源展开为 "apartment_type.room" 形式的字段，按列取值
+fn export_row(dto: &HouseDataDto, columns: &[&ExportColumn]) -> Vec<Value> {
+    let mut fields = HashMap::new();
+    if let Ok(value) = serde_json::to_value(dto) {
+        flatten("", value, &mut fields);
+    }
+
+    columns
+        .iter()
+        .map(|column| fields.remove(column.key).unwrap_or(Value::Null))
+        .collect()
+}
+
+fn flatten(prefix: &str, value: Value, fields: &mut HashMap<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&key, value, fields);
+            }
+        }
+        value => {
+            fields.insert(prefix.to_string(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{export_row, select_columns};
+    use crate::infrastructure::dto::house_data_dto::HouseDataDto;
+
+    #[test]
+    fn test_select_columns() {
+        let all = select_columns(None, false).unwrap();
+        assert!(all.iter().all(|column| !column.owner));
+        assert!(select_columns(None, true)
+            .unwrap()
+            .iter()
+            .any(|column| column.key == "owner.phone"));
+
+        let keys = ["小区".to_string(), "owner.phone".to_string()];
+        assert!(select_columns(Some(&keys), false).is_err());
+        let columns = select_columns(Some(&keys), true).unwrap();
+        assert_eq!(columns[0].key, "community.name");
+        assert!(select_columns(Some(&["x".to_string()]), true).is_err());
+    }
+
+    #[test]
+    fn test_export_row() {
+        let dto: HouseDataDto = serde_json::from_value(json!({
+            "id": "h1",
+            "house_address": "11栋104室",
+            "apartment_type": {"room": 3, "hall": 2},
+            "tags": ["满五"],
+            "owner": {
+                "id": "o1",
+                "name": "房主",
+                "phone": "13966605120",
+                "created_at": "2025-07-26T05:56:10Z",
+                "updated_at": "2025-07-26T05:56:10Z"
+            }
+        }))
+        .unwrap();
+
+        let keys = ["地址", "apartment_type.room", "卫", "标签", "业主电话"].map(String::from);
+        let columns = select_columns(Some(&keys), true).unwrap();
+        assert_eq!(
+            export_row(&dto, &columns),
+            vec![
+                json!("11栋104室"),
+                json!(3),
+                Value::Null,
+                json!(["满五"]),
+                json!("13966605120"),
+            ]
+        );
+    }
+}
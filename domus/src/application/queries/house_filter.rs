@@ -0,0 +1,709 @@
+use std::{fmt, marker::PhantomData, str::FromStr};
+
+use sea_orm::{
+    sea_query::{Alias, Expr, Query, SimpleExpr},
+    ColumnTrait, Condition, Order, QueryOrder, Value,
+};
+use serde::{
+    de::{self, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+
+use crate::infrastructure::{
+    entitiy::{community_query, house_price_history, house_query},
+    search::house_index::{HouseSearchIndex, SearchHit},
+};
+
+// 关键词搜索最多取多少条，再与其他条件求交集，列表按相关度只取前面这些
+const SEARCH_LIMIT: usize = 1000;
+// 导出、批量操作需要完整结果，关键词命中超过这个数时返回错误而不是截断
+const COMPLETE_SEARCH_LIMIT: usize = 50000;
+
+// 地球平均半径，米
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+// 周边搜索的最大半径，米
+const MAX_RADIUS_M: f64 = 50_000.0;
+
+// 范围条件，min、max 都包含边界，只填一个表示不限上限或下限
+// 除了 {"min": 1000, "max": 2000}，也兼容旧的 "1000-2000"、"1000+"、"1000"（大于等于）写法
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RangeFilter<T> {
+    pub min: Option<T>,
+    pub max: Option<T>,
+}
+
+impl<T> RangeFilter<T> {
+    pub fn new(min: Option<T>, max: Option<T>) -> Self {
+        Self { min, max }
+    }
+}
+
+impl<T: FromStr> FromStr for RangeFilter<T> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        let parse = |v: &str| {
+            let v = v.trim();
+            if v.is_empty() {
+                return Ok(None);
+            }
+            v.parse::<T>()
+                .map(Some)
+                .map_err(|_| format!("无法解析范围「{}」", s))
+        };
+
+        if let Some(min) = value.strip_suffix('+') {
+            return Ok(Self::new(parse(min)?, None));
+        }
+
+        match value.split_once('-') {
+            Some((min, max)) => Ok(Self::new(parse(min)?, parse(max)?)),
+            None => Ok(Self::new(parse(value)?, None)),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for RangeFilter<T>
+where
+    T: FromStr + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RangeVisitor<T>(PhantomData<T>);
+
+        #[derive(Deserialize)]
+        struct Bounds<T> {
+            min: Option<T>,
+            max: Option<T>,
+        }
+
+        impl<'de, T> Visitor<'de> for RangeVisitor<T>
+        where
+            T: FromStr + Deserialize<'de>,
+        {
+            type Value = RangeFilter<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("{\"min\", \"max\"} 或 \"1000-2000\"、\"1000+\" 形式的范围")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                let bounds = Bounds::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                Ok(RangeFilter::new(bounds.min, bounds.max))
+            }
+        }
+
+        deserializer.deserialize_any(RangeVisitor(PhantomData))
+    }
+}
+
+// 楼层兼容 "low"、"middle"、"high"，单个数字表示具体楼层
+fn deserialize_floor<'de, D>(deserializer: D) -> Result<Option<RangeFilter<i32>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    let floor = match value {
+        None | Some(serde_json::Value::Null) => return Ok(None),
+        Some(serde_json::Value::String(s)) => match s.trim() {
+            "low" => RangeFilter::new(None, Some(2)),
+            "middle" => RangeFilter::new(Some(3), Some(6)),
+            "high" => RangeFilter::new(Some(7), None),
+            s => match s.parse::<i32>() {
+                Ok(floor) => RangeFilter::new(Some(floor), Some(floor)),
+                Err(_) => s.parse().map_err(de::Error::custom)?,
+            },
+        },
+        Some(serde_json::Value::Number(n)) => {
+            let floor = n
+                .as_i64()
+                .and_then(|n| i32::try_from(n).ok())
+                .ok_or_else(|| de::Error::custom(format!("无法解析楼层「{}」", n)))?;
+            RangeFilter::new(Some(floor), Some(floor))
+        }
+        Some(v) => RangeFilter::deserialize(v).map_err(de::Error::custom)?,
+    };
+
+    Ok(Some(floor))
+}
+
+// 房源列表的范围筛选，内部和公开查询共用
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct HouseRangeFilter {
+    // 售价
+    pub price: Option<RangeFilter<f64>>,
+    // 租金
+    pub rent: Option<RangeFilter<f64>>,
+    // 建筑面积
+    pub area: Option<RangeFilter<f64>>,
+    // 单价，投影维护的 unit_price
+    pub unit_price: Option<RangeFilter<f64>>,
+    // 所在楼层
+    #[serde(default, deserialize_with = "deserialize_floor")]
+    pub floor: Option<RangeFilter<i32>>,
+    // 建筑年代
+    pub building_year: Option<RangeFilter<i32>>,
+}
+
+impl HouseRangeFilter {
+    pub fn condition(&self) -> anyhow::Result<Condition> {
+        let column = |column: house_query::Column| Expr::col((house_query::Entity, column));
+
+        // building_year 是字符串，可能带“年”，按数字比较
+        let building_year =
+            column(house_query::Column::BuildingYear).cast_as(Alias::new("UNSIGNED"));
+
+        HouseConditionBuilder::new()
+            .range(
+                "售价",
+                column(house_query::Column::SalePrice).into(),
+                self.price,
+            )?
+            .range(
+                "租金",
+                column(house_query::Column::RentPrice).into(),
+                self.rent,
+            )?
+            .range(
+                "面积",
+                column(house_query::Column::BuildingArea).into(),
+                self.area,
+            )?
+            .range(
+                "单价",
+                column(house_query::Column::UnitPrice).into(),
+                self.unit_price,
+            )?
+            .range(
+                "楼层",
+                column(house_query::Column::CurrentFloor).into(),
+                self.floor,
+            )?
+            .range("建筑年代", building_year, self.building_year)
+            .map(HouseConditionBuilder::build)
+    }
+}
+
+pub struct HouseConditionBuilder {
+    condition: Condition,
+}
+
+impl Default for HouseConditionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HouseConditionBuilder {
+    pub fn new() -> Self {
+        Self {
+            condition: Condition::all(),
+        }
+    }
+
+    // 添加范围条件，min 大于 max 时返回错误
+    pub fn range<T>(
+        mut self,
+        label: &str,
+        expr: SimpleExpr,
+        filter: Option<RangeFilter<T>>,
+    ) -> anyhow::Result<Self>
+    where
+        T: Into<Value> + PartialOrd + fmt::Display + Copy,
+    {
+        let Some(RangeFilter { min, max }) = filter else {
+            return Ok(self);
+        };
+
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                return Err(anyhow::anyhow!(
+                    "{}范围无效：最小值 {} 大于最大值 {}",
+                    label,
+                    min,
+                    max
+                ));
+            }
+        }
+
+        if let Some(min) = min {
+            self.condition = self.condition.add(Expr::expr(expr.clone()).gte(min));
+        }
+        if let Some(max) = max {
+            self.condition = self.condition.add(Expr::expr(expr).lte(max));
+        }
+
+        Ok(self)
+    }
+
+    pub fn build(self) -> Condition {
+        self.condition
+    }
+}
+
+// 周边搜索，按小区坐标计算到中心点的距离，如 {"lat": 31.82, "lng": 117.22, "radius_m": 2000}
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct NearFilter {
+    pub lat: f64,
+    pub lng: f64,
+    // 半径，米
+    pub radius_m: f64,
+}
+
+impl NearFilter {
+    pub fn condition(&self) -> anyhow::Result<Condition> {
+        if !(-90.0..=90.0).contains(&self.lat) || !(-180.0..=180.0).contains(&self.lng) {
+            return Err(anyhow::anyhow!(
+                "周边搜索的坐标无效：{}, {}",
+                self.lat,
+                self.lng
+            ));
+        }
+        if !(self.radius_m > 0.0 && self.radius_m <= MAX_RADIUS_M) {
+            return Err(anyhow::anyhow!(
+                "周边搜索的半径需在 0 到 {} 米之间",
+                MAX_RADIUS_M
+            ));
+        }
+
+        // 先用外接矩形筛一遍，能走 (lat, lng) 索引，再按球面距离精确过滤
+        let lat_delta = (self.radius_m / EARTH_RADIUS_M).to_degrees();
+        let lng_delta = lat_delta / self.lat.to_radians().cos().max(0.01);
+
+        Ok(Condition::all()
+            .add(community_query::Column::Lat.between(self.lat - lat_delta, self.lat + lat_delta))
+            .add(community_query::Column::Lng.between(self.lng - lng_delta, self.lng + lng_delta))
+            .add(Expr::expr(self.distance()).lte(self.radius_m)))
+    }
+
+    // 小区到中心点的距离（haversine），米
+    pub fn distance(&self) -> SimpleExpr {
+        Expr::cust_with_values(
+            "? * 2 * ASIN(SQRT(POW(SIN(RADIANS(`community_query`.`lat` - ?) / 2), 2) \
+             + COS(RADIANS(?)) * COS(RADIANS(`community_query`.`lat`)) \
+             * POW(SIN(RADIANS(`community_query`.`lng` - ?) / 2), 2)))",
+            [EARTH_RADIUS_M, self.lat, self.lat, self.lng],
+        )
+    }
+
+    // 与 SQL 相同的公式，用于回填 distance_m
+    pub fn distance_to(&self, lat: f64, lng: f64) -> f64 {
+        let d_lat = (lat - self.lat).to_radians();
+        let d_lng = (lng - self.lng).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2)
+            + self.lat.to_radians().cos() * lat.to_radians().cos() * (d_lng / 2.0).sin().powi(2);
+
+        EARTH_RADIUS_M * 2.0 * a.sqrt().asin()
+    }
+}
+
+// 最近 days 天内降过价（售价或租金）的房源
+pub fn recently_reduced(days: u32) -> SimpleExpr {
+    let since = chrono::Utc::now() - chrono::Duration::days(days.into());
+
+    house_query::Column::Id.in_subquery(
+        Query::select()
+            .column(house_price_history::Column::HouseId)
+            .from(house_price_history::Entity)
+            .and_where(
+                Expr::col(house_price_history::Column::NewPrice)
+                    .lt(Expr::col(house_price_history::Column::OldPrice)),
+            )
+            .and_where(house_price_history::Column::ChangedAt.gte(since))
+            .to_owned(),
+    )
+}
+
+// 可排序的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HouseSortField {
+    // 总价
+    Price,
+    // 租金
+    Rent,
+    // 单价
+    UnitPrice,
+    // 建筑面积
+    Area,
+    // 挂牌时间
+    ListedAt,
+    // 更新时间
+    UpdatedAt,
+    // 到周边搜索中心点的距离
+    Distance,
+}
+
+impl HouseSortField {
+    const ALL: [(&'static str, HouseSortField); 7] = [
+        ("price", HouseSortField::Price),
+        ("rent", HouseSortField::Rent),
+        ("unit_price", HouseSortField::UnitPrice),
+        ("area", HouseSortField::Area),
+        ("listed_at", HouseSortField::ListedAt),
+        ("updated_at", HouseSortField::UpdatedAt),
+        ("distance", HouseSortField::Distance),
+    ];
+
+    fn name(self) -> &'static str {
+        Self::ALL
+            .iter()
+            .find(|(_, field)| *field == self)
+            .map(|(name, _)| *name)
+            .unwrap_or_default()
+    }
+
+    fn expr(self, near: Option<&NearFilter>) -> anyhow::Result<SimpleExpr> {
+        let column = match self {
+            HouseSortField::Price => house_query::Column::SalePrice,
+            HouseSortField::Rent => house_query::Column::RentPrice,
+            HouseSortField::UnitPrice => house_query::Column::UnitPrice,
+            HouseSortField::Area => house_query::Column::BuildingArea,
+            HouseSortField::ListedAt => house_query::Column::CreatedAt,
+            HouseSortField::UpdatedAt => house_query::Column::UpdatedAt,
+            HouseSortField::Distance => {
+                return near
+                    .map(NearFilter::distance)
+                    .ok_or_else(|| anyhow::anyhow!("按距离排序需要指定 near"));
+            }
+        };
+
+        Ok(Expr::col((house_query::Entity, column)).into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HouseSortKey {
+    pub field: HouseSortField,
+    pub order: SortOrder,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl FromStr for HouseSortKey {
+    type Err = String;
+
+    // "price"、"price:asc"、"unit_price:desc"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, order) = s.trim().split_once(':').unwrap_or((s.trim(), "asc"));
+        let field = HouseSortField::ALL
+            .iter()
+            .find(|(field, _)| *field == name.trim())
+            .map(|(_, field)| *field)
+            .ok_or_else(|| {
+                let allowed: Vec<&str> = HouseSortField::ALL.iter().map(|(n, _)| *n).collect();
+                format!("不支持按「{}」排序，可选：{}", name, allowed.join("、"))
+            })?;
+        let order = match order.trim().to_lowercase().as_str() {
+            "asc" => SortOrder::Asc,
+            "desc" => SortOrder::Desc,
+            order => return Err(format!("排序方向「{}」无效，可选 asc、desc", order)),
+        };
+
+        Ok(Self { field, order })
+    }
+}
+
+impl fmt::Display for HouseSortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let order = match self.order {
+            SortOrder::Asc => "asc",
+            SortOrder::Desc => "desc",
+        };
+        write!(f, "{}:{}", self.field.name(), order)
+    }
+}
+
+// 多字段排序，按顺序依次比较，写成 "price:asc,area:desc" 或 ["price:asc", "area:desc"]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HouseSort(pub Vec<HouseSortKey>);
+
+impl HouseSort {
+    // 由近到远
+    pub fn nearest() -> Self {
+        HouseSort(vec![HouseSortKey {
+            field: HouseSortField::Distance,
+            order: SortOrder::Asc,
+        }])
+    }
+
+    // 空值总是排在最后，按距离排序时需要周边搜索的中心点
+    pub fn apply<Q: QueryOrder>(
+        &self,
+        mut query: Q,
+        near: Option<&NearFilter>,
+    ) -> anyhow::Result<Q> {
+        for key in &self.0 {
+            let expr = key.field.expr(near)?;
+            let order = match key.order {
+                SortOrder::Asc => Order::Asc,
+                SortOrder::Desc => Order::Desc,
+            };
+            query = query
+                .order_by(Expr::expr(expr.clone()).is_null(), Order::Asc)
+                .order_by(expr, order);
+        }
+        Ok(query)
+    }
+}
+
+impl<'de> Deserialize<'de> for HouseSort {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            One(String),
+            Many(Vec<String>),
+        }
+
+        let keys = match Raw::deserialize(deserializer)? {
+            Raw::One(s) => s.split(',').map(str::to_string).collect(),
+            Raw::Many(keys) => keys,
+        };
+
+        keys.iter()
+            .filter(|key| !key.trim().is_empty())
+            .map(|key| key.parse())
+            .collect::<Result<Vec<_>, _>>()
+            .map(HouseSort)
+            .map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for HouseSort {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let keys: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        serializer.serialize_str(&keys.join(","))
+    }
+}
+
+// 关键词搜索的结果，用于过滤、按相关度排序和回填高亮
+pub struct HouseSearchResult {
+    hits: Vec<SearchHit>,
+}
+
+impl HouseSearchResult {
+    // 没有关键词时返回 None，只取相关度最高的 SEARCH_LIMIT 条
+    pub fn search(
+        search_index: Option<&HouseSearchIndex>,
+        q: Option<&str>,
+    ) -> anyhow::Result<Option<Self>> {
+        Ok(Self::search_with_limit(search_index, q, SEARCH_LIMIT)?.map(|(search, _)| search))
+    }
+
+    // 需要全部命中的房源时使用，命中超过 COMPLETE_SEARCH_LIMIT 条返回错误
+    pub fn search_complete(
+        search_index: Option<&HouseSearchIndex>,
+        q: Option<&str>,
+    ) -> anyhow::Result<Option<Self>> {
+        match Self::search_with_limit(search_index, q, COMPLETE_SEARCH_LIMIT)? {
+            Some((_, true)) => Err(anyhow::anyhow!(
+                "关键词命中的房源超过 {} 套，请缩小搜索范围",
+                COMPLETE_SEARCH_LIMIT
+            )),
+            search => Ok(search.map(|(search, _)| search)),
+        }
+    }
+
+    // 多取一条判断是否被截断
+    fn search_with_limit(
+        search_index: Option<&HouseSearchIndex>,
+        q: Option<&str>,
+        limit: usize,
+    ) -> anyhow::Result<Option<(Self, bool)>> {
+        let Some(q) = q.map(str::trim).filter(|q| !q.is_empty()) else {
+            return Ok(None);
+        };
+        let search_index = search_index.ok_or_else(|| anyhow::anyhow!("搜索索引未启用"))?;
+
+        let mut hits = search_index.search(q, limit + 1)?;
+        let truncated = hits.len() > limit;
+        hits.truncate(limit);
+        Ok(Some((Self { hits }, truncated)))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hits.is_empty()
+    }
+
+    pub fn condition(&self) -> SimpleExpr {
+        house_query::Column::Id.is_in(self.hits.iter().map(|hit| hit.id.clone()))
+    }
+
+    // 按命中顺序排序，即相关度从高到低
+    pub fn order(&self) -> SimpleExpr {
+        let placeholders = vec!["?"; self.hits.len()].join(", ");
+        Expr::cust_with_values(
+            format!("FIELD(`house_query`.`id`, {})", placeholders),
+            self.hits.iter().map(|hit| hit.id.clone()),
+        )
+    }
+
+    pub fn hit(&self, id: &str) -> Option<&SearchHit> {
+        self.hits.iter().find(|hit| hit.id == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use sea_orm::{DbBackend, EntityTrait, QueryFilter, QueryTrait};
+
+    use super::{
+        recently_reduced, HouseRangeFilter, HouseSearchResult, HouseSort, NearFilter, RangeFilter,
+    };
+    use crate::infrastructure::{
+        entitiy::house_query,
+        search::house_index::{HouseSearchDocument, HouseSearchIndex},
+    };
+
+    #[test]
+    fn test_parse_range() {
+        let filter: HouseRangeFilter = serde_json::from_value(json!({
+            "price": "100-200",
+            "rent": "1000+",
+            "area": {"max": 90},
+            "unit_price": 2,
+            "floor": "high",
+        }))
+        .unwrap();
+
+        assert_eq!(
+            filter.price,
+            Some(RangeFilter::new(Some(100.0), Some(200.0)))
+        );
+        assert_eq!(filter.rent, Some(RangeFilter::new(Some(1000.0), None)));
+        assert_eq!(filter.area, Some(RangeFilter::new(None, Some(90.0))));
+        assert_eq!(filter.unit_price, Some(RangeFilter::new(Some(2.0), None)));
+        assert_eq!(filter.floor, Some(RangeFilter::new(Some(7), None)));
+
+        let sql = house_query::Entity::find()
+            .filter(filter.condition().unwrap())
+            .build(DbBackend::MySql)
+            .to_string();
+        assert!(sql.contains("`house_query`.`rent_price` >= 1000"));
+        assert!(sql.contains("`house_query`.`current_floor` >= 7"));
+
+        assert!(serde_json::from_value::<HouseRangeFilter>(json!({"price": "abc"})).is_err());
+
+        let filter: HouseRangeFilter =
+            serde_json::from_value(json!({"area": {"min": 90, "max": 60}})).unwrap();
+        assert!(filter.condition().is_err());
+
+        let sql = house_query::Entity::find()
+            .filter(recently_reduced(30))
+            .build(DbBackend::MySql)
+            .to_string();
+        assert!(sql.contains("`house_query`.`id` IN (SELECT `house_id` FROM `house_price_history` WHERE `new_price` < `old_price`"));
+    }
+
+    #[test]
+    fn test_sort() {
+        let sort: HouseSort = serde_json::from_value(json!("unit_price:desc, area")).unwrap();
+        assert_eq!(sort.0.len(), 2);
+        assert_eq!(
+            serde_json::to_value(&sort).unwrap(),
+            json!("unit_price:desc,area:asc")
+        );
+
+        let sql = sort
+            .apply(house_query::Entity::find(), None)
+            .unwrap()
+            .build(DbBackend::MySql)
+            .to_string();
+        assert!(sql.contains(
+            "ORDER BY `house_query`.`unit_price` IS NULL ASC, `house_query`.`unit_price` DESC"
+        ));
+
+        assert!(serde_json::from_value::<HouseSort>(json!(["price:up"])).is_err());
+        assert!(serde_json::from_value::<HouseSort>(json!("owner_phone")).is_err());
+
+        // 没有中心点不能按距离排序
+        let sort: HouseSort = serde_json::from_value(json!("distance")).unwrap();
+        assert!(sort.apply(house_query::Entity::find(), None).is_err());
+    }
+
+    #[test]
+    fn test_near() {
+        let near: NearFilter =
+            serde_json::from_value(json!({"lat": 31.0, "lng": 117.0, "radius_m": 2000})).unwrap();
+
+        // 纬度相差 1 度约 111 公里
+        let distance = near.distance_to(32.0, 117.0);
+        assert!((distance - 111_195.0).abs() < 10.0);
+        assert_eq!(near.distance_to(31.0, 117.0), 0.0);
+
+        let sql = HouseSort::nearest()
+            .apply(
+                house_query::Entity::find().filter(near.condition().unwrap()),
+                Some(&near),
+            )
+            .unwrap()
+            .build(DbBackend::MySql)
+            .to_string();
+        assert!(sql.contains("`community_query`.`lat` BETWEEN"));
+        assert!(sql.contains("<= 2000 ORDER BY (6371008.8 * 2 * ASIN("));
+
+        let near = NearFilter {
+            radius_m: 0.0,
+            ..near
+        };
+        assert!(near.condition().is_err());
+    }
+
+    #[test]
+    fn test_search_truncated() {
+        let index = HouseSearchIndex::in_memory().unwrap();
+        let documents: Vec<HouseSearchDocument> = (1..=3)
+            .map(|id| HouseSearchDocument {
+                id: id.to_string(),
+                community_name: "皖江华府".to_string(),
+                ..Default::default()
+            })
+            .collect();
+        index.upsert(&documents).unwrap();
+
+        let (search, truncated) =
+            HouseSearchResult::search_with_limit(Some(&index), Some("华府"), 2)
+                .unwrap()
+                .unwrap();
+        assert_eq!(search.hits.len(), 2);
+        assert!(truncated);
+
+        let (search, truncated) =
+            HouseSearchResult::search_with_limit(Some(&index), Some("华府"), 3)
+                .unwrap()
+                .unwrap();
+        assert_eq!(search.hits.len(), 3);
+        assert!(!truncated);
+
+        assert!(
+            HouseSearchResult::search_complete(Some(&index), Some("华府"))
+                .unwrap()
+                .is_some()
+        );
+        assert!(HouseSearchResult::search(Some(&index), Some(" "))
+            .unwrap()
+            .is_none());
+    }
+}
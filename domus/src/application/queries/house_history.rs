@@ -0,0 +1,554 @@
+use std::sync::Arc;
+
+use event_bus::EventEnvelope;
+use sea_orm::{prelude::DateTimeUtc, DbConn};
+use serde::Serialize;
+use serde_json::Value;
+use shared_utils::diff_values::diff_values;
+
+use crate::{
+    application::{
+        queries::house::HouseQueryService, services::house_operation_log::HouseOperationLogService,
+    },
+    domain::house::{
+        events::{house::HouseEvent, house_price_changed::PriceField},
+        value_objects::{dictionary::Dictionary, house::House},
+    },
+    infrastructure::{dto::house_data_dto::HouseDataDto, entitiy::house_operation_log},
+};
+
+// 房源的一个版本，changes 为与上一版本的字段差异 { 字段: { before, after } }
+#[derive(Debug, Clone, Serialize)]
+pub struct HouseVersion {
+    pub version: i64,
+    // 来源为 operation_log 时是操作记录的 id
+    pub event_id: String,
+    // event / operation_log，早于事件流的变更来自操作记录
+    pub source: &'static str,
+    // Created / Updated / Published / Unpublished / Deleted / Restored / Purged / PriceChanged
+    // Listed / Reserved / Sold / Rented / Suspended / Expired / StatusChanged / Patched
+    pub change_type: String,
+    pub operator_id: Option<String>,
+    pub occurred_at: DateTimeUtc,
+    pub changes: Value,
+}
+
+// 按事件流回放房源的历史版本，与读模型的投影规则保持一致。
+// 早于事件流的房源没有 Created 事件，之前的部分由操作记录补齐
+pub struct HouseHistoryQueryService {
+    pool: Arc<DbConn>,
+    house_query_service: HouseQueryService,
+    house_operation_log_service: HouseOperationLogService,
+}
+
+impl HouseHistoryQueryService {
+    pub fn new(pool: Arc<DbConn>) -> Self {
+        Self {
+            house_query_service: HouseQueryService::new(pool.clone()),
+            house_operation_log_service: HouseOperationLogService::new(pool.clone()),
+            pool,
+        }
+    }
+
+    // 房源的所有版本，按发生顺序
+    pub async fn history(&self, house_id: &str) -> anyhow::Result<Vec<HouseVersion>> {
+        let events = self.load(house_id).await?;
+        if covers_lifetime(&events) {
+            return Ok(event_versions(&events, None));
+        }
+
+        // 事件之前的变更来自操作记录，事件的差异从倒推出的事件前状态开始计算
+        let logs = self.house_operation_log_service.list(house_id).await?;
+        let first_event_at = events.first().map(|event| event.occurred_at);
+        let mut versions: Vec<HouseVersion> = logs
+            .iter()
+            .filter(|log| first_event_at.is_none_or(|at| log_time(log) < at))
+            .map(log_version)
+            .collect();
+
+        if let Some(at) = first_event_at {
+            let before = match self.current(house_id).await {
+                Some(house) => rewind(
+                    house,
+                    &events,
+                    &logs,
+                    at - chrono::Duration::milliseconds(1),
+                ),
+                None => None,
+            };
+            versions.extend(event_versions(&events, before));
+        }
+
+        Ok(versions)
+    }
+
+    // 房源在某一时刻的状态和版本号，当时还未创建或已删除时返回 None
+    pub async fn as_of(
+        &self,
+        house_id: &str,
+        as_of: DateTimeUtc,
+    ) -> anyhow::Result<Option<(House, i64)>> {
+        let events = self.load(house_id).await?;
+        let version = events
+            .iter()
+            .take_while(|event| event.occurred_at <= as_of)
+            .count() as i64;
+
+        let state = if covers_lifetime(&events) {
+            events
+                .iter()
+                .take_while(|event| event.occurred_at <= as_of)
+                .fold(None, |state, event| {
+                    Some(apply(state, &event.payload, event.occurred_at))
+                })
+        } else {
+            // 事件流不完整，从当前状态倒推
+            let logs = self.house_operation_log_service.list(house_id).await?;
+            match self.current(house_id).await {
+                Some(house) => rewind(house, &events, &logs, as_of),
+                None => None,
+            }
+        };
+
+        Ok(state
+            .filter(|house| house.deleted_at.is_none())
+            .map(|house| (house, version)))
+    }
+
+    // 房源详情在某一时刻的样子，小区和业主为当前数据
+    pub async fn find_as_of(
+        &self,
+        house_id: &str,
+        as_of: DateTimeUtc,
+    ) -> anyhow::Result<HouseDataDto> {
+        let (house, version) = self
+            .as_of(house_id, as_of)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("该时间点房源不存在"))?;
+
+        let mut data = self
+            .house_query_service
+            .find_by_id(house_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("房源不存在"))?;
+        data.house = house;
+        data.version = Some(version);
+
+        Ok(data)
+    }
+
+    async fn load(&self, house_id: &str) -> anyhow::Result<Vec<EventEnvelope<HouseEvent>>> {
+        event_bus::load_stream::<HouseEvent, _>(self.pool.as_ref(), house_id, 0).await
+    }
+
+    // 读模型中的当前状态，包括已删除的
+    async fn current(&self, house_id: &str) -> Option<House> {
+        self.house_query_service
+            .find_by_id(house_id)
+            .await
+            .map(|data| data.house)
+    }
+}
+
+// 事件流从 Created 开始才覆盖房源的整个生命周期
+fn covers_lifetime(events: &[EventEnvelope<HouseEvent>]) -> bool {
+    matches!(
+        events.first().map(|event| &event.payload),
+        Some(HouseEvent::Created(_))
+    )
+}
+
+// 逐个事件计算版本，initial 为第一个事件之前的状态
+fn event_versions(
+    events: &[EventEnvelope<HouseEvent>],
+    initial: Option<House>,
+) -> Vec<HouseVersion> {
+    let mut versions = Vec::with_capacity(events.len());
+    let mut state = initial;
+
+    for (index, event) in events.iter().enumerate() {
+        let before = state.as_ref().map(diff_view).unwrap_or(Value::Null);
+        let after = apply(state.take(), &event.payload, event.occurred_at);
+        let changes = diff(before, diff_view(&after));
+        state = Some(after);
+
+        versions.push(HouseVersion {
+            version: index as i64 + 1,
+            event_id: event.event_id.clone(),
+            source: "event",
+            change_type: change_type(&event.payload).to_string(),
+            operator_id: event.actor_id.clone(),
+            occurred_at: event.occurred_at,
+            changes,
+        });
+    }
+
+    versions
+}
+
+// 操作记录转为版本，早于事件流，版本号为 0；只有修改记录带字段差异
+fn log_version(log: &house_operation_log::Model) -> HouseVersion {
+    HouseVersion {
+        version: 0,
+        event_id: log.id.to_string(),
+        source: "operation_log",
+        change_type: log_change_type(log.operation_type).to_string(),
+        operator_id: Some(log.operator_id.clone()),
+        occurred_at: log_time(log),
+        changes: log.operation_content.clone().unwrap_or(Value::Null),
+    }
+}
+
+fn log_time(log: &house_operation_log::Model) -> DateTimeUtc {
+    log.created_at.and_utc()
+}
+
+// 操作类型：1=新增，2=修改，3=删除，4=上架，5=下架，6=状态变更，7=恢复
+fn log_change_type(operation_type: u8) -> &'static str {
+    match operation_type {
+        1 => "Created",
+        2 => "Updated",
+        3 => "Deleted",
+        4 => "Published",
+        5 => "Unpublished",
+        6 => "StatusChanged",
+        7 => "Restored",
+        _ => "Unknown",
+    }
+}
+
+// 从当前状态倒推到 as_of：按时间倒序撤销之后的变更。修改的字段取操作记录差异里的 before，
+// 其余变更按事件撤销，操作记录和事件重复的撤销结果相同。as_of 时还未创建返回 None
+fn rewind(
+    house: House,
+    events: &[EventEnvelope<HouseEvent>],
+    logs: &[house_operation_log::Model],
+    as_of: DateTimeUtc,
+) -> Option<House> {
+    enum Change<'a> {
+        Event(&'a HouseEvent),
+        Log(&'a house_operation_log::Model),
+    }
+
+    let mut changes: Vec<(DateTimeUtc, Change)> = events
+        .iter()
+        .filter(|event| event.occurred_at > as_of)
+        .map(|event| (event.occurred_at, Change::Event(&event.payload)))
+        .chain(
+            logs.iter()
+                .filter(|log| log_time(log) > as_of)
+                .map(|log| (log_time(log), Change::Log(log))),
+        )
+        .collect();
+    changes.sort_by_key(|(at, _)| std::cmp::Reverse(*at));
+
+    let mut house = house;
+    for (at, change) in changes {
+        match change {
+            Change::Event(HouseEvent::Created(_)) => return None,
+            Change::Log(log) if log.operation_type == 1 => return None,
+            Change::Log(log) if log.operation_type == 2 => {
+                if let Some(content) = &log.operation_content {
+                    house = undo_diff(house, content);
+                }
+            }
+            Change::Event(HouseEvent::Deleted(_)) => house.deleted_at = None,
+            Change::Log(log) if log.operation_type == 3 => house.deleted_at = None,
+            Change::Event(HouseEvent::Restored(_)) => house.deleted_at = Some(at),
+            Change::Log(log) if log.operation_type == 7 => house.deleted_at = Some(at),
+            Change::Event(HouseEvent::Published(_)) => house.external_sync = None,
+            Change::Log(log) if log.operation_type == 4 => house.external_sync = None,
+            Change::Event(HouseEvent::Unpublished(_)) => {
+                house.external_sync = Some("all".to_string())
+            }
+            Change::Log(log) if log.operation_type == 5 => {
+                house.external_sync = Some("all".to_string())
+            }
+            Change::Event(
+                HouseEvent::Listed(event)
+                | HouseEvent::Reserved(event)
+                | HouseEvent::Sold(event)
+                | HouseEvent::Rented(event)
+                | HouseEvent::Suspended(event)
+                | HouseEvent::Expired(event),
+            ) => {
+                if let Some(from) = event.from {
+                    house.house_status = Some(from.as_str().to_string());
+                }
+            }
+            Change::Event(HouseEvent::PriceChanged(event)) => match event.field {
+                PriceField::SalePrice => house.sale_price = event.old,
+                PriceField::RentPrice => house.rent_price = event.old,
+            },
+            // 修改的字段由操作记录撤销
+            Change::Event(_) | Change::Log(_) => {}
+        }
+    }
+
+    Some(house)
+}
+
+// 把差异 { 字段: { before, after } } 里的字段改回 before，类型对不上的字段跳过
+fn undo_diff(house: House, diff: &Value) -> House {
+    let Value::Object(fields) = diff else {
+        return house;
+    };
+
+    let mut house = house;
+    for (key, change) in fields {
+        let mut value = serde_json::to_value(&house).unwrap_or(Value::Null);
+        let Value::Object(map) = &mut value else {
+            return house;
+        };
+        let field = map.entry(key.clone()).or_insert(Value::Null);
+        undo_field(field, change);
+
+        if let Ok(undone) = serde_json::from_value(value) {
+            house = undone;
+        }
+    }
+
+    house
+}
+
+fn undo_field(field: &mut Value, change: &Value) {
+    match change {
+        Value::Object(change) if change.contains_key("before") && change.contains_key("after") => {
+            *field = change["before"].clone();
+        }
+        // 嵌套对象的差异
+        Value::Object(change) => {
+            if !field.is_object() {
+                *field = Value::Object(Default::default());
+            }
+            for (key, change) in change {
+                if let Value::Object(field) = field {
+                    undo_field(field.entry(key.clone()).or_insert(Value::Null), change);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// 把事件应用到房源上，规则同 HouseQueryService 的 create/update/publish/unpublish/delete/change_status
+fn apply(state: Option<House>, event: &HouseEvent, occurred_at: DateTimeUtc) -> House {
+    let mut house = match (state, event) {
+        (_, HouseEvent::Created(house)) => House {
+            maintainer_id: house.maintainer_id.clone().or(house.created_by.clone()),
+            ..house.clone()
+        },
+        (Some(before), HouseEvent::Updated(update)) => {
+            let mut house = update.clone();
+            // 读模型更新时这些字段为空则保留原值
+            house.id = before.id;
+            house.created_by = before.created_by;
+            house.maintainer_id = house.maintainer_id.or(before.maintainer_id);
+            house.community_id = house.community_id.or(before.community_id);
+            house.purpose = house.purpose.or(before.purpose);
+            house.house_address = house.house_address.or(before.house_address);
+            house.transaction_type = house.transaction_type.or(before.transaction_type);
+            house.house_status = house.house_status.or(before.house_status);
+            house.deleted_at = before.deleted_at;
+            house
+        }
+        (None, HouseEvent::Updated(update)) => update.clone(),
+        (state, HouseEvent::Published(_)) => House {
+            external_sync: Some("all".to_string()),
+            ..state.unwrap_or_default()
+        },
+        (state, HouseEvent::Unpublished(_)) => House {
+            external_sync: None,
+            ..state.unwrap_or_default()
+        },
+        (state, HouseEvent::Deleted(_)) => House {
+            deleted_at: Some(occurred_at),
+            ..state.unwrap_or_default()
+        },
+        (state, HouseEvent::Restored(_)) => House {
+            deleted_at: None,
+            ..state.unwrap_or_default()
+        },
+        // 彻底删除前已经在回收站里
+        (state, HouseEvent::Purged(_)) => state.unwrap_or_default(),
+        (
+            state,
+            HouseEvent::Listed(event)
+            | HouseEvent::Reserved(event)
+            | HouseEvent::Sold(event)
+            | HouseEvent::Rented(event)
+            | HouseEvent::Suspended(event)
+            | HouseEvent::Expired(event),
+        ) => House {
+            house_status: Some(event.to.as_str().to_string()),
+            ..state.unwrap_or_default()
+        },
+        (state, HouseEvent::PriceChanged(event)) => {
+            let mut house = state.unwrap_or_default();
+            match event.field {
+                PriceField::SalePrice => house.sale_price = event.new,
+                PriceField::RentPrice => house.rent_price = event.new,
+            }
+            house
+        }
+        (state, HouseEvent::Patched(event)) => {
+            let mut house = state.unwrap_or_default();
+            event.apply(&mut house);
+            house
+        }
+    };
+
+    house.updated_at = Some(occurred_at);
+    house
+}
+
+fn change_type(event: &HouseEvent) -> &'static str {
+    match event {
+        HouseEvent::Created(_) => "Created",
+        HouseEvent::Updated(_) => "Updated",
+        HouseEvent::Published(_) => "Published",
+        HouseEvent::Unpublished(_) => "Unpublished",
+        HouseEvent::Deleted(_) => "Deleted",
+        HouseEvent::Restored(_) => "Restored",
+        HouseEvent::Purged(_) => "Purged",
+        HouseEvent::Listed(_) => "Listed",
+        HouseEvent::Reserved(_) => "Reserved",
+        HouseEvent::Sold(_) => "Sold",
+        HouseEvent::Rented(_) => "Rented",
+        HouseEvent::Suspended(_) => "Suspended",
+        HouseEvent::Expired(_) => "Expired",
+        HouseEvent::PriceChanged(_) => "PriceChanged",
+        HouseEvent::Patched(_) => "Patched",
+    }
+}
+
+// 更新时间每个版本都会变，不参与比较
+fn diff_view(house: &House) -> Value {
+    let mut value = serde_json::to_value(house).unwrap_or(Value::Null);
+    if let Value::Object(map) = &mut value {
+        map.remove("updated_at");
+    }
+    value
+}
+
+// House 序列化时省略空字段，比较前补齐两边缺少的字段，新增和清空的字段才能出现在差异里
+fn diff(mut before: Value, mut after: Value) -> Value {
+    align(&mut before, &mut after);
+    diff_values(&before, &after)
+}
+
+fn align(before: &mut Value, after: &mut Value) {
+    if let (Value::Object(before), Value::Object(after)) = (before, after) {
+        for key in after.keys() {
+            before.entry(key.clone()).or_insert(Value::Null);
+        }
+        for (key, value) in before.iter_mut() {
+            let other = after.entry(key.clone()).or_insert(Value::Null);
+            align(value, other);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use event_bus::EventEnvelope;
+
+    use super::{apply, diff, diff_view, rewind};
+    use crate::{
+        domain::house::{
+            events::{house::HouseEvent, house_status_changed::HouseStatusChangedEvent},
+            value_objects::{dictionary::HouseStatus, house::House},
+        },
+        infrastructure::entitiy::house_operation_log,
+    };
+
+    #[test]
+    fn test_version_diff() {
+        let created: House = serde_json::from_value(json!({
+            "id": "h1",
+            "house_address": "1栋101",
+            "house_status": "在售",
+            "sale_price": 100.0,
+        }))
+        .unwrap();
+        let updated: House = serde_json::from_value(json!({
+            "id": "h1",
+            "sale_price": 95.0,
+            "remark": "降价",
+        }))
+        .unwrap();
+
+        let now = chrono::Utc::now();
+        let v1 = apply(None, &HouseEvent::Created(created), now);
+        let v2 = apply(Some(v1.clone()), &HouseEvent::Updated(updated), now);
+
+        // 更新时没有带上的地址和状态保留原值
+        assert_eq!(v2.house_address.as_deref(), Some("1栋101"));
+        assert_eq!(
+            diff(diff_view(&v1), diff_view(&v2)),
+            json!({
+                "sale_price": { "before": 100.0, "after": 95.0 },
+                "remark": { "before": null, "after": "降价" },
+            })
+        );
+    }
+
+    #[test]
+    fn test_rewind_legacy_house() {
+        let now = chrono::Utc::now();
+        let log =
+            |id: u64, operation_type: u8, minutes: i64, content: Option<serde_json::Value>| {
+                house_operation_log::Model {
+                    id,
+                    operation_type,
+                    house_id: "h1".to_string(),
+                    operation_content: content,
+                    operator_id: "u1".to_string(),
+                    ip_address: None,
+                    user_agent: None,
+                    created_at: (now - chrono::Duration::minutes(minutes)).naive_utc(),
+                }
+            };
+        // 早于事件流创建，之后改过一次价格，再通过事件改为已售
+        let logs = vec![
+            log(1, 1, 60, None),
+            log(
+                2,
+                2,
+                30,
+                Some(json!({ "sale_price": { "before": 100.0, "after": 95.0 } })),
+            ),
+        ];
+        let events = vec![EventEnvelope::new(HouseEvent::Sold(
+            HouseStatusChangedEvent {
+                house_id: "h1".to_string(),
+                from: Some(HouseStatus::OnSale),
+                to: HouseStatus::Sold,
+                reason: None,
+                actor_id: None,
+                changed_at: now,
+            },
+        ))];
+        let current: House = serde_json::from_value(json!({
+            "id": "h1",
+            "house_address": "1栋101",
+            "house_status": "已售",
+            "sale_price": 95.0,
+        }))
+        .unwrap();
+
+        let at = |minutes: i64| now - chrono::Duration::minutes(minutes);
+        let house = rewind(current.clone(), &events, &logs, at(10)).unwrap();
+        assert_eq!(house.house_status.as_deref(), Some("在售"));
+        assert_eq!(house.sale_price, Some(95.0));
+
+        let house = rewind(current.clone(), &events, &logs, at(45)).unwrap();
+        assert_eq!(house.sale_price, Some(100.0));
+        assert_eq!(house.house_address.as_deref(), Some("1栋101"));
+
+        // 创建之前不存在
+        assert!(rewind(current, &events, &logs, at(90)).is_none());
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+
+use sea_orm::FromQueryResult;
+use serde::{Deserialize, Serialize};
+
+use crate::application::queries::house::HouseRequest;
+
+const BASE32: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+// 地图聚合的层级，随缩放级别由粗到细
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "level", rename_all = "snake_case")]
+pub enum MapLevel {
+    // 按区县
+    District,
+    // 按 geohash 网格，precision 为 geohash 长度
+    Grid { precision: usize },
+    // 按小区
+    Community,
+}
+
+impl MapLevel {
+    // 高德地图缩放级别：11 及以下看全市，12~14 看片区，15 及以上看小区
+    pub fn from_zoom(zoom: f64) -> Self {
+        match zoom {
+            z if z < 12.0 => MapLevel::District,
+            z if z < 14.0 => MapLevel::Grid { precision: 5 },
+            z if z < 15.0 => MapLevel::Grid { precision: 6 },
+            _ => MapLevel::Community,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HouseMapRequest {
+    // 地图缩放级别
+    pub zoom: f64,
+    // 与房源列表相同的筛选条件
+    #[serde(flatten)]
+    pub filter: HouseRequest,
+}
+
+// 按小区汇总的房源统计，再在内存中合并为区县、网格
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct CommunityHouseStats {
+    pub id: String,
+    pub name: String,
+    pub district: Option<String>,
+    pub adcode: Option<String>,
+    pub lat: Option<f64>,
+    pub lng: Option<f64>,
+    pub house_count: i64,
+    pub sale_price_sum: Option<f64>,
+    pub sale_price_count: i64,
+    pub unit_price_sum: Option<f64>,
+    pub unit_price_count: i64,
+    pub rent_price_sum: Option<f64>,
+    pub rent_price_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HouseMapBucket {
+    // 区县为行政区划代码，网格为 geohash，小区为小区 id
+    pub key: String,
+    pub name: String,
+    pub house_count: i64,
+    // 平均售价
+    pub avg_price: Option<f64>,
+    // 平均单价
+    pub avg_unit_price: Option<f64>,
+    // 平均租金
+    pub avg_rent: Option<f64>,
+    // 按房源数加权的中心点，区县、小区都没有坐标时为空
+    pub lat: Option<f64>,
+    pub lng: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HouseMap {
+    #[serde(flatten)]
+    pub level: MapLevel,
+    pub buckets: Vec<HouseMapBucket>,
+}
+
+#[derive(Default)]
+struct BucketSum {
+    name: String,
+    house_count: i64,
+    located_count: i64,
+    lat: f64,
+    lng: f64,
+    sale_price: (f64, i64),
+    unit_price: (f64, i64),
+    rent_price: (f64, i64),
+}
+
+impl BucketSum {
+    fn add(&mut self, stats: &CommunityHouseStats) {
+        self.house_count += stats.house_count;
+        if let (Some(lat), Some(lng)) = (stats.lat, stats.lng) {
+            self.located_count += stats.house_count;
+            self.lat += lat * stats.house_count as f64;
+            self.lng += lng * stats.house_count as f64;
+        }
+        self.sale_price.0 += stats.sale_price_sum.unwrap_or_default();
+        self.sale_price.1 += stats.sale_price_count;
+        self.unit_price.0 += stats.unit_price_sum.unwrap_or_default();
+        self.unit_price.1 += stats.unit_price_count;
+        self.rent_price.0 += stats.rent_price_sum.unwrap_or_default();
+        self.rent_price.1 += stats.rent_price_count;
+    }
+
+    fn into_bucket(self, key: String) -> HouseMapBucket {
+        let avg = |(sum, count): (f64, i64)| (count > 0).then(|| sum / count as f64);
+        let center = |sum: f64| (self.located_count > 0).then(|| sum / self.located_count as f64);
+
+        HouseMapBucket {
+            key,
+            name: self.name,
+            house_count: self.house_count,
+            avg_price: avg(self.sale_price),
+            avg_unit_price: avg(self.unit_price),
+            avg_rent: avg(self.rent_price),
+            lat: center(self.lat),
+            lng: center(self.lng),
+        }
+    }
+}
+
+impl HouseMap {
+    pub fn aggregate(level: MapLevel, rows: Vec<CommunityHouseStats>) -> Self {
+        let mut sums: HashMap<String, BucketSum> = HashMap::new();
+
+        for stats in &rows {
+            let (key, name) = match level {
+                MapLevel::District => {
+                    let name = stats.district.clone().unwrap_or("未知区域".to_string());
+                    (stats.adcode.clone().unwrap_or(name.clone()), name)
+                }
+                MapLevel::Grid { precision } => {
+                    // 没有坐标的小区无法落到网格里
+                    let (Some(lat), Some(lng)) = (stats.lat, stats.lng) else {
+                        continue;
+                    };
+                    let key = geohash(lat, lng, precision);
+                    (key.clone(), key)
+                }
+                MapLevel::Community => (stats.id.clone(), stats.name.clone()),
+            };
+
+            let sum = sums.entry(key).or_default();
+            sum.name = name;
+            sum.add(stats);
+        }
+
+        let mut buckets: Vec<HouseMapBucket> = sums
+            .into_iter()
+            .map(|(key, sum)| sum.into_bucket(key))
+            .collect();
+        buckets.sort_by(|a, b| {
+            b.house_count
+                .cmp(&a.house_count)
+                .then_with(|| a.key.cmp(&b.key))
+        });
+
+        Self { level, buckets }
+    }
+}
+
+// 把坐标编码为 geohash，经纬度交替二分，每 5 位一个字符
+pub fn geohash(lat: f64, lng: f64, precision: usize) -> String {
+    let (mut lat_range, mut lng_range) = ((-90.0, 90.0), (-180.0, 180.0));
+    let mut hash = String::with_capacity(precision);
+    let (mut bits, mut value, mut even) = (0, 0usize, true);
+
+    while hash.len() < precision {
+        let (range, coord) = if even {
+            (&mut lng_range, lng)
+        } else {
+            (&mut lat_range, lat)
+        };
+        let mid = (range.0 + range.1) / 2.0;
+        value <<= 1;
+        if coord >= mid {
+            value |= 1;
+            range.0 = mid;
+        } else {
+            range.1 = mid;
+        }
+        even = !even;
+
+        bits += 1;
+        if bits == 5 {
+            hash.push(BASE32[value] as char);
+            bits = 0;
+            value = 0;
+        }
+    }
+
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{geohash, CommunityHouseStats, HouseMap, HouseMapRequest, MapLevel};
+
+    fn stats(
+        id: &str,
+        adcode: &str,
+        lat: f64,
+        house_count: i64,
+        price: f64,
+    ) -> CommunityHouseStats {
+        CommunityHouseStats {
+            id: id.to_string(),
+            name: id.to_string(),
+            district: Some("蜀山区".to_string()),
+            adcode: Some(adcode.to_string()),
+            lat: Some(lat),
+            lng: Some(117.0),
+            house_count,
+            sale_price_sum: Some(price * house_count as f64),
+            sale_price_count: house_count,
+            unit_price_sum: None,
+            unit_price_count: 0,
+            rent_price_sum: None,
+            rent_price_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_request() {
+        let request: HouseMapRequest = serde_json::from_value(json!({
+            "zoom": 13,
+            "price": "100-200",
+            "floor": "low",
+        }))
+        .unwrap();
+        assert_eq!(
+            MapLevel::from_zoom(request.zoom),
+            MapLevel::Grid { precision: 5 }
+        );
+        assert!(request.filter.range.price.is_some());
+        assert!(request.filter.range.floor.is_some());
+    }
+
+    #[test]
+    fn test_geohash() {
+        assert_eq!(geohash(57.64911, 10.40744, 11), "u4pruydqqvj");
+        assert!(geohash(31.82, 117.22, 6).starts_with(&geohash(31.82, 117.22, 5)));
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let rows = vec![
+            stats("a", "340104", 31.0, 1, 100.0),
+            stats("b", "340104", 32.0, 3, 200.0),
+            stats("c", "340111", 31.0, 2, 300.0),
+        ];
+
+        let map = HouseMap::aggregate(MapLevel::from_zoom(10.0), rows.clone());
+        assert_eq!(map.buckets.len(), 2);
+        let bucket = &map.buckets[0];
+        assert_eq!(bucket.key, "340104");
+        assert_eq!(bucket.house_count, 4);
+        assert_eq!(bucket.avg_price, Some(175.0));
+        assert_eq!(bucket.avg_unit_price, None);
+        assert_eq!(bucket.lat, Some(31.75));
+
+        let map = HouseMap::aggregate(MapLevel::from_zoom(16.0), rows);
+        assert_eq!(map.level, MapLevel::Community);
+        assert_eq!(map.buckets.len(), 3);
+    }
+
+    #[test]
+    fn test_aggregate_unlocated() {
+        let unlocated = CommunityHouseStats {
+            lat: None,
+            lng: None,
+            ..stats("d", "340104", 0.0, 2, 100.0)
+        };
+        let rows = vec![stats("a", "340104", 31.0, 1, 100.0), unlocated];
+
+        // 区县中心只按有坐标的小区计算
+        let map = HouseMap::aggregate(MapLevel::from_zoom(10.0), rows.clone());
+        assert_eq!(map.buckets[0].house_count, 3);
+        assert_eq!(map.buckets[0].lat, Some(31.0));
+
+        // 没有坐标的小区不在网格里，按小区聚合时不返回坐标
+        let map = HouseMap::aggregate(MapLevel::from_zoom(13.0), rows.clone());
+        assert_eq!(map.buckets.len(), 1);
+
+        let map = HouseMap::aggregate(MapLevel::from_zoom(16.0), rows);
+        let bucket = map.buckets.iter().find(|bucket| bucket.key == "d").unwrap();
+        assert_eq!(bucket.lat, None);
+        assert_eq!(bucket.lng, None);
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue::Set, ColumnTrait, DbConn, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+
+use crate::{
+    domain::house::events::house_price_changed::{HousePriceChangedEvent, PriceField},
+    infrastructure::entitiy::house_price_history,
+};
+
+// 房源价格走势，由 HousePriceChanged 事件维护
+pub struct HousePriceQueryService {
+    pool: Arc<DbConn>,
+}
+
+impl HousePriceQueryService {
+    pub fn new(pool: Arc<DbConn>) -> Self {
+        Self { pool }
+    }
+
+    // 记录一次价格变化，同一事件重复投递时忽略
+    pub async fn record(
+        &self,
+        event_id: &str,
+        event: &HousePriceChangedEvent,
+    ) -> anyhow::Result<()> {
+        let change_rate = match (event.old, event.new) {
+            (Some(old), Some(new)) if old != 0.0 => Some((new - old) / old),
+            _ => None,
+        };
+        let model = house_price_history::ActiveModel {
+            event_id: Set(event_id.to_string()),
+            house_id: Set(event.house_id.clone()),
+            field: Set(event.field.as_str().to_string()),
+            old_price: Set(event.old),
+            new_price: Set(event.new),
+            change_rate: Set(change_rate),
+            changed_at: Set(event.changed_at),
+            ..Default::default()
+        };
+
+        house_price_history::Entity::insert(model)
+            .on_conflict(
+                OnConflict::column(house_price_history::Column::EventId)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .do_nothing()
+            .exec(self.pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    // 房源彻底删除时一并删除价格记录
+    pub async fn purge(&self, house_id: &str) -> anyhow::Result<()> {
+        house_price_history::Entity::delete_many()
+            .filter(house_price_history::Column::HouseId.eq(house_id))
+            .exec(self.pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    // 房源的价格变化，按时间先后排列
+    pub async fn find_by_house(
+        &self,
+        house_id: &str,
+        field: Option<PriceField>,
+    ) -> anyhow::Result<Vec<house_price_history::Model>> {
+        let mut query = house_price_history::Entity::find()
+            .filter(house_price_history::Column::HouseId.eq(house_id));
+        if let Some(field) = field {
+            query = query.filter(house_price_history::Column::Field.eq(field.as_str()));
+        }
+
+        query
+            .order_by_asc(house_price_history::Column::ChangedAt)
+            .order_by_asc(house_price_history::Column::Id)
+            .all(self.pool.as_ref())
+            .await
+            .map_err(anyhow::Error::from)
+    }
+}
@@ -1,4 +1,11 @@
 pub mod community;
 pub mod house;
+pub mod house_duplicate;
+pub mod house_export;
+pub mod house_filter;
+pub mod house_history;
+pub mod house_map;
+pub mod house_price;
 pub mod owner;
 pub mod public_house;
+pub mod sync;
@@ -1,15 +1,22 @@
 use std::sync::Arc;
 
 use crate::{
-    domain::house::value_objects::house::ApartmentType,
+    application::queries::house_filter::{
+        recently_reduced, HouseRangeFilter, HouseSearchResult, HouseSort, NearFilter,
+    },
+    domain::house::value_objects::{
+        dictionary::{Dictionary, HouseDecoration, HouseOrientation, Purpose, TransactionType},
+        house::ApartmentType,
+    },
+    infrastructure::search::house_index::HouseSearchIndex,
     infrastructure::{
         dto::public_house_data_dto::PublicHouseDataDto,
         entitiy::{community_query, house_query, owner_query},
     },
 };
 use sea_orm::{
-    prelude::DateTimeUtc, Condition, DbConn, EntityTrait, JoinType, PaginatorTrait, QueryFilter,
-    QueryOrder, QuerySelect,
+    prelude::DateTimeUtc, Condition, DbConn, EntityTrait, JoinType, Order, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, QueryTrait,
 };
 use sea_orm::{ColumnTrait, RelationTrait};
 use serde::Deserialize;
@@ -18,11 +25,21 @@ use shared_dto::table_data::TableDataResponse;
 
 pub struct PublicHouseQueryService {
     pool: Arc<DbConn>,
+    search_index: Option<Arc<HouseSearchIndex>>,
 }
 
 impl PublicHouseQueryService {
     pub fn new(pool: Arc<DbConn>) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            search_index: None,
+        }
+    }
+
+    // 启用关键词搜索
+    pub fn search_index(mut self, search_index: Arc<HouseSearchIndex>) -> Self {
+        self.search_index = Some(search_index);
+        self
     }
 
     // 查询房源列表
@@ -31,6 +48,16 @@ impl PublicHouseQueryService {
         params: PubclicHouseRequest,
     ) -> anyhow::Result<TableDataResponse<PublicHouseDataDto>> {
         let mut condition = Condition::all();
+
+        // 关键词搜索，没有命中直接返回
+        let search = HouseSearchResult::search(self.search_index.as_deref(), params.q.as_deref())?;
+        if let Some(search) = &search {
+            if search.is_empty() {
+                return Ok(TableDataResponse::new(Vec::new(), 0));
+            }
+            condition = condition.add(search.condition());
+        }
+
         if let Some(AmapBounds {
             north_east,
             south_west,
@@ -43,22 +70,30 @@ impl PublicHouseQueryService {
                 .add(community_query::Column::Lng.lte(north_east.lng));
         }
 
+        // 周边搜索
+        if let Some(near) = &params.near {
+            condition = condition.add(near.condition()?);
+        }
+
         if let Some(transaction_type) = params.transaction_type {
-            condition = condition.add(house_query::Column::TransactionType.eq(transaction_type));
+            condition =
+                condition.add(house_query::Column::TransactionType.eq(transaction_type.as_str()));
         }
 
         if let Some(purpose) = params.purpose {
-            condition = condition.add(house_query::Column::Purpose.eq(purpose));
+            condition = condition.add(house_query::Column::Purpose.eq(purpose.as_str()));
         }
 
         // 朝向
         if let Some(house_orientation) = params.house_orientation {
-            condition = condition.add(house_query::Column::HouseOrientation.eq(house_orientation));
+            condition =
+                condition.add(house_query::Column::HouseOrientation.eq(house_orientation.as_str()));
         }
 
         // 装修
         if let Some(house_decoration) = params.house_decoration {
-            condition = condition.add(house_query::Column::HouseDecoration.eq(house_decoration));
+            condition =
+                condition.add(house_query::Column::HouseDecoration.eq(house_decoration.as_str()));
         }
 
         // apartment_type
@@ -83,112 +118,18 @@ impl PublicHouseQueryService {
             }
         }
 
-        // 售价
-        if let Some(price) = params.price {
-            // 如果是 1000+
-            if price.ends_with('+') {
-                let price = price
-                    .trim_end_matches('+')
-                    .parse::<f32>()
-                    .unwrap_or_default();
-                condition = condition.add(house_query::Column::SalePrice.lte(price));
-            }
-
-            let price = price
-                .split('-')
-                .map(|p| p.parse::<f32>().unwrap_or_default())
-                .collect::<Vec<f32>>();
-
-            // 范围查询 1000-2000
-            if price.len() == 2 {
-                condition =
-                    condition.add(house_query::Column::SalePrice.between(price[0], price[1]));
-            }
-
-            // 大于等于 1000
-            if price.len() == 1 {
-                condition = condition.add(house_query::Column::SalePrice.gte(price[0]));
-            }
-        }
-
-        // 租金
-        if let Some(rent) = params.rent {
-            // 如果是 1000+
-            if rent.ends_with('+') {
-                let rent = rent
-                    .trim_end_matches('+')
-                    .parse::<f32>()
-                    .unwrap_or_default();
-                condition = condition.add(house_query::Column::RentPrice.lte(rent));
-            }
-
-            let rent = rent
-                .split('-')
-                .map(|p| p.parse::<f32>().unwrap_or_default())
-                .collect::<Vec<f32>>();
-
-            // 范围查询 1000-2000
-            if rent.len() == 2 {
-                condition = condition.add(house_query::Column::RentPrice.between(rent[0], rent[1]));
-            }
-
-            // 大于等于 1000
-            if rent.len() == 1 {
-                condition = condition.add(house_query::Column::SalePrice.gte(rent[0]));
-            }
-        }
-
-        // 面积
-        if let Some(area) = params.area {
-            // 如果是 1000+
-            if area.ends_with('+') {
-                let area = area
-                    .trim_end_matches('+')
-                    .parse::<f32>()
-                    .unwrap_or_default();
-                condition = condition.add(house_query::Column::BuildingArea.lte(area));
-            }
-
-            let area = area
-                .split('-')
-                .map(|p| p.parse::<f32>().unwrap_or_default())
-                .collect::<Vec<f32>>();
-
-            // 范围查询 1000-2000
-            if area.len() == 2 {
-                condition =
-                    condition.add(house_query::Column::BuildingArea.between(area[0], area[1]));
-            }
-
-            // 大于等于 1000
-            if area.len() == 1 {
-                condition = condition.add(house_query::Column::BuildingArea.gte(area[0]));
-            }
-        }
-
-        // 楼层
-        if let Some(floor) = params.floor {
-            match floor.as_str() {
-                "low" => {
-                    condition = condition.add(house_query::Column::Level.lt(3));
-                }
-                "middle" => {
-                    condition = condition.add(house_query::Column::Level.between(3, 6));
-                }
-                "high" => {
-                    condition = condition.add(house_query::Column::Level.gt(6));
-                }
-                _ => {
-                    // 如果不是 low, middle, high 则不做处理
-                    condition = condition.add(house_query::Column::Level.eq(floor));
-                }
-            }
-        }
+        // 售价、租金、面积、单价、楼层、建筑年代
+        condition = condition.add(params.range.condition()?);
 
         if let Some(updated_at) = params.updated_at {
             condition = condition.add(house_query::Column::UpdatedAt.gt(updated_at));
         }
 
+        // 最近降价
+        if let Some(days) = params.reduced_within_days {
+            condition = condition.add(recently_reduced(days));
+        }
+
         // 不排除已删除
         if !params.not_exclude_deleted.unwrap_or(false) {
             condition = condition.add(house_query::Column::DeletedAt.is_null());
@@ -197,14 +138,31 @@ impl PublicHouseQueryService {
         // 分页逻辑
         let page = params.page.max(1);
         let page_size = params.page_size.min(10000).max(1);
-        let paginator = house_query::Entity::find()
+        let query = house_query::Entity::find()
             .join(
                 JoinType::LeftJoin,
                 house_query::Relation::CommunityQuery.def(),
             )
             .join(JoinType::LeftJoin, house_query::Relation::OwnerQuery.def())
-            .filter(condition.clone())
+            .filter(condition.clone());
+
+        // 周边搜索没有指定排序时由近到远，关键词搜索仍按相关度
+        let sort = params
+            .sort
+            .clone()
+            .or_else(|| (params.near.is_some() && search.is_none()).then(HouseSort::nearest));
+        let query = match &sort {
+            Some(sort) => sort.apply(query, params.near.as_ref())?,
+            None => query,
+        };
+
+        let paginator = query
+            .apply_if(search.as_ref(), |query, search| {
+                query.order_by(search.order(), Order::Asc)
+            })
             .order_by_desc(house_query::Column::UpdatedAt)
+            // 更新时间相同时按 id 排，分页（如导出）不会重复或遗漏
+            .order_by_asc(house_query::Column::Id)
             .select_also(community_query::Entity)
             .select_also(owner_query::Entity)
             .paginate(self.pool.as_ref(), page_size as u64);
@@ -214,7 +172,12 @@ impl PublicHouseQueryService {
 
         let data = data
             .into_iter()
-            .map(|(house, community, owner)| PublicHouseDataDto::new(house, community, owner))
+            .map(|(house, community, owner)| {
+                let hit = search.as_ref().and_then(|search| search.hit(&house.id));
+                PublicHouseDataDto::new(house, community, owner)
+                    .with_search_hit(hit)
+                    .with_distance(params.near.as_ref())
+            })
             .collect::<Vec<PublicHouseDataDto>>();
 
         Ok(TableDataResponse::new(data, total as u64))
@@ -248,18 +211,23 @@ impl PublicHouseQueryService {
 pub struct PubclicHouseRequest {
     pub page: u64,
     pub page_size: u64,
+    // 关键词，搜索房源标题、地址、小区名称等
+    pub q: Option<String>,
     pub amap_bounds: Option<AmapBounds>,
+    // 周边搜索，如 {"lat": 31.82, "lng": 117.22, "radius_m": 2000}
+    pub near: Option<NearFilter>,
     pub updated_at: Option<DateTimeUtc>,
-    pub transaction_type: Option<String>,
-    pub purpose: Option<String>,
-    pub house_orientation: Option<String>,
-    pub house_decoration: Option<String>,
+    pub transaction_type: Option<TransactionType>,
+    pub purpose: Option<Purpose>,
+    pub house_orientation: Option<HouseOrientation>,
+    pub house_decoration: Option<HouseDecoration>,
     pub apartment_type: Option<ApartmentType>,
-    pub price: Option<String>,
-    pub rent: Option<String>,
-    pub area: Option<String>,
-    // "low", "middle", "high"
-    pub floor: Option<String>,
+    #[serde(flatten)]
+    pub range: HouseRangeFilter,
+    // 最近多少天内降过价
+    pub reduced_within_days: Option<u32>,
+    // 排序，如 "unit_price:asc,area:desc"，默认按更新时间倒序，周边搜索时默认由近到远
+    pub sort: Option<HouseSort>,
     // 不排除已删除的
     pub not_exclude_deleted: Option<bool>,
 }
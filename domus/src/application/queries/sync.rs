@@ -0,0 +1,472 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Duration,
+};
+
+use casbin::{CoreApi, Enforcer};
+use event_bus::{
+    entitiy::event_record,
+    outbox::{STATUS_FAILED, STATUS_PENDING, STATUS_PROCESSING},
+};
+use sea_orm::{
+    prelude::DateTimeUtc, ColumnTrait, DbConn, EntityTrait, JoinType, QueryFilter, QueryOrder,
+    QuerySelect, QueryTrait, RelationTrait,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use user_system::shared::claims::Claims;
+
+use crate::infrastructure::{
+    dto::house_data_dto::HouseDataDto,
+    entitiy::{community_query, house_query, owner_query},
+};
+
+const DEFAULT_LIMIT: u64 = 500;
+const MAX_LIMIT: u64 = 2000;
+// seq 在插入时分配、提交时才可见，只同步写入一段时间后的事件，避免越过晚提交的事务
+const SETTLE: Duration = Duration::from_secs(5);
+
+// 同步的实体类型，与事件的 aggregate_type 一致
+const HOUSE: &str = "house";
+const COMMUNITY: &str = "community";
+const OWNER: &str = "owner";
+
+// 同步业主信息需要的权限，不对应实际路由，只在角色权限里配置
+pub const SYNC_OWNER_PERMISSION: &str = "/api/domus/sync/owner";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SyncRequest {
+    // 上次返回的游标，首次同步不传
+    pub cursor: Option<String>,
+    // 本次最多处理的事件数
+    pub limit: Option<u64>,
+}
+
+// 客户端能看到的字段范围
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SyncScope {
+    // 业主信息（姓名、电话、证件）
+    pub owners: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncTombstone {
+    pub entity: &'static str,
+    pub id: String,
+    pub deleted_at: DateTimeUtc,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncChanges {
+    // 下次请求带上的游标
+    pub cursor: String,
+    // 全量快照的第一页，客户端先清空本地副本
+    pub reset: bool,
+    // 还有未返回的变更，客户端应立即继续拉取
+    pub has_more: bool,
+    pub houses: Vec<HouseDataDto>,
+    pub communities: Vec<community_query::Model>,
+    pub owners: Vec<owner_query::Model>,
+    // 已删除的实体，客户端从本地副本中移除
+    pub tombstones: Vec<SyncTombstone>,
+}
+
+// 游标对客户端不透明，内部是已同步到的事件序号；全量快照期间还带着已返回的最后一个房源 id
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SyncCursor {
+    seq: i64,
+    snapshot: Option<String>,
+}
+
+impl SyncCursor {
+    pub fn parse(cursor: &str) -> anyhow::Result<Self> {
+        let parse_seq = |seq: &str| i64::from_str_radix(seq, 16).ok().filter(|seq| *seq >= 0);
+        let cursor = match (cursor.strip_prefix('c'), cursor.strip_prefix('s')) {
+            (Some(seq), _) => parse_seq(seq).map(|seq| SyncCursor {
+                seq,
+                snapshot: None,
+            }),
+            (_, Some(snapshot)) => snapshot.split_once('.').and_then(|(seq, house_id)| {
+                parse_seq(seq).map(|seq| SyncCursor {
+                    seq,
+                    snapshot: Some(house_id.to_string()),
+                })
+            }),
+            _ => None,
+        };
+
+        cursor.ok_or_else(|| anyhow::anyhow!("同步游标无效，请重新全量同步"))
+    }
+
+    pub fn encode(&self) -> String {
+        match &self.snapshot {
+            Some(house_id) => format!("s{:x}.{}", self.seq, house_id),
+            None => format!("c{:x}", self.seq),
+        }
+    }
+}
+
+// 移动端增量同步：按事件序号找出变化的房源、小区、业主，返回读模型的当前状态。
+// 首次同步先分页返回读模型的全量快照，之后从快照时的事件序号开始增量同步
+pub struct SyncQueryService {
+    pool: Arc<DbConn>,
+    enforcer: Arc<Mutex<Enforcer>>,
+}
+
+impl SyncQueryService {
+    pub fn new(pool: Arc<DbConn>, enforcer: Arc<Mutex<Enforcer>>) -> Self {
+        Self { pool, enforcer }
+    }
+
+    // 按用户角色决定同步哪些字段
+    pub async fn scope(&self, user: &Claims) -> SyncScope {
+        let enforcer = self.enforcer.lock().await;
+        let owners = user.rules.iter().any(|role| {
+            enforcer
+                .enforce((role.as_str(), SYNC_OWNER_PERMISSION, "GET"))
+                .unwrap_or(false)
+        });
+
+        SyncScope { owners }
+    }
+
+    pub async fn changes(
+        &self,
+        request: SyncRequest,
+        scope: SyncScope,
+    ) -> anyhow::Result<SyncChanges> {
+        let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let mut aggregate_types = vec![HOUSE, COMMUNITY];
+        if scope.owners {
+            aggregate_types.push(OWNER);
+        }
+
+        let cursor = match request.cursor.as_deref() {
+            Some(cursor) if !cursor.is_empty() => SyncCursor::parse(cursor)?,
+            // 快照之前的变更已经包含在读模型里，之后的由增量同步补上
+            _ => SyncCursor {
+                seq: self.synced_seq(&aggregate_types).await?,
+                snapshot: Some(String::new()),
+            },
+        };
+
+        match cursor.snapshot {
+            Some(after) => self.snapshot(cursor.seq, after, limit, scope).await,
+            None => {
+                self.increment(cursor.seq, aggregate_types, limit, scope)
+                    .await
+            }
+        }
+    }
+
+    // 可以安全同步到的事件序号：已写入超过 SETTLE，并且之前的事件都已投递到读模型
+    async fn synced_seq(&self, aggregate_types: &[&str]) -> anyhow::Result<i64> {
+        let blocked_at = self.blocked_at(aggregate_types, 0).await?;
+        let settled_at = chrono::Utc::now() - chrono::Duration::from_std(SETTLE)?;
+
+        let seq = event_record::Entity::find()
+            .select_only()
+            .column_as(event_record::Column::Seq.max(), "seq")
+            .filter(event_record::Column::AggregateType.is_in(aggregate_types.to_vec()))
+            .filter(event_record::Column::CreatedAt.lte(settled_at))
+            .apply_if(blocked_at, |query, seq| {
+                query.filter(event_record::Column::Seq.lt(seq))
+            })
+            .into_tuple::<Option<i64>>()
+            .one(self.pool.as_ref())
+            .await?
+            .flatten()
+            .unwrap_or_default();
+
+        Ok(seq)
+    }
+
+    // 还没投递到读模型的第一个事件，不能越过，否则客户端会漏掉这次变更
+    async fn blocked_at(
+        &self,
+        aggregate_types: &[&str],
+        after: i64,
+    ) -> anyhow::Result<Option<i64>> {
+        let seq = event_record::Entity::find()
+            .select_only()
+            .column_as(event_record::Column::Seq.min(), "seq")
+            .filter(event_record::Column::AggregateType.is_in(aggregate_types.to_vec()))
+            .filter(event_record::Column::Status.is_in([
+                STATUS_PENDING,
+                STATUS_PROCESSING,
+                STATUS_FAILED,
+            ]))
+            .filter(event_record::Column::Seq.gt(after))
+            .into_tuple::<Option<i64>>()
+            .one(self.pool.as_ref())
+            .await?
+            .flatten();
+
+        Ok(seq)
+    }
+
+    // 全量快照：按 id 分页返回未删除的房源，第一页同时返回全部小区和业主
+    async fn snapshot(
+        &self,
+        seq: i64,
+        after: String,
+        limit: u64,
+        scope: SyncScope,
+    ) -> anyhow::Result<SyncChanges> {
+        let reset = after.is_empty();
+        let rows = house_query::Entity::find()
+            .join(
+                JoinType::LeftJoin,
+                house_query::Relation::CommunityQuery.def(),
+            )
+            .join(JoinType::LeftJoin, house_query::Relation::OwnerQuery.def())
+            .filter(house_query::Column::DeletedAt.is_null())
+            .filter(house_query::Column::Id.gt(after))
+            .order_by_asc(house_query::Column::Id)
+            .limit(limit)
+            .select_also(community_query::Entity)
+            .select_also(owner_query::Entity)
+            .all(self.pool.as_ref())
+            .await?;
+
+        let has_more = rows.len() as u64 == limit;
+        let last = rows.last().map(|(house, ..)| house.id.clone());
+        let mut changes = SyncChanges {
+            cursor: SyncCursor {
+                seq,
+                snapshot: last.filter(|_| has_more),
+            }
+            .encode(),
+            reset,
+            // 快照结束后立即开始增量同步，补上快照期间的变更
+            has_more: true,
+            houses: rows
+                .into_iter()
+                .map(|(house, community, owner)| house_dto(house, community, owner, scope))
+                .collect(),
+            communities: Vec::new(),
+            owners: Vec::new(),
+            tombstones: Vec::new(),
+        };
+
+        if reset {
+            changes.communities = community_query::Entity::find()
+                .all(self.pool.as_ref())
+                .await?;
+            if scope.owners {
+                changes.owners = owner_query::Entity::find().all(self.pool.as_ref()).await?;
+            }
+        }
+
+        Ok(changes)
+    }
+
+    async fn increment(
+        &self,
+        seq: i64,
+        aggregate_types: Vec<&str>,
+        limit: u64,
+        scope: SyncScope,
+    ) -> anyhow::Result<SyncChanges> {
+        let blocked_at = self.blocked_at(&aggregate_types, seq).await?;
+        let settled_at = chrono::Utc::now() - chrono::Duration::from_std(SETTLE)?;
+
+        let events = event_record::Entity::find()
+            .select_only()
+            .columns([
+                event_record::Column::Seq,
+                event_record::Column::AggregateType,
+                event_record::Column::AggregateId,
+                event_record::Column::CreatedAt,
+            ])
+            .filter(event_record::Column::AggregateType.is_in(aggregate_types))
+            .filter(event_record::Column::Seq.gt(seq))
+            .filter(event_record::Column::CreatedAt.lte(settled_at))
+            .apply_if(blocked_at, |query, seq| {
+                query.filter(event_record::Column::Seq.lt(seq))
+            })
+            .order_by_asc(event_record::Column::Seq)
+            .limit(limit)
+            .into_tuple::<(i64, String, String, DateTimeUtc)>()
+            .all(self.pool.as_ref())
+            .await?;
+
+        let has_more = events.len() as u64 == limit;
+        let next = SyncCursor {
+            seq: events.last().map(|(seq, ..)| *seq).unwrap_or(seq),
+            snapshot: None,
+        };
+
+        // 同一实体只取一次，记录最后一次变化的时间
+        let mut changed: HashMap<&str, BTreeMap<String, DateTimeUtc>> = HashMap::new();
+        for (_, aggregate_type, aggregate_id, created_at) in &events {
+            let entity = match aggregate_type.as_str() {
+                HOUSE => HOUSE,
+                COMMUNITY => COMMUNITY,
+                _ => OWNER,
+            };
+            changed
+                .entry(entity)
+                .or_default()
+                .insert(aggregate_id.clone(), *created_at);
+        }
+
+        let mut changes = SyncChanges {
+            cursor: next.encode(),
+            reset: false,
+            has_more,
+            houses: Vec::new(),
+            communities: Vec::new(),
+            owners: Vec::new(),
+            tombstones: Vec::new(),
+        };
+
+        if let Some(ids) = changed.remove(HOUSE) {
+            self.load_houses(ids, scope, &mut changes).await?;
+        }
+        if let Some(ids) = changed.remove(COMMUNITY) {
+            self.load_communities(ids, &mut changes).await?;
+        }
+        if let Some(ids) = changed.remove(OWNER) {
+            self.load_owners(ids, &mut changes).await?;
+        }
+
+        Ok(changes)
+    }
+
+    async fn load_houses(
+        &self,
+        mut ids: BTreeMap<String, DateTimeUtc>,
+        scope: SyncScope,
+        changes: &mut SyncChanges,
+    ) -> anyhow::Result<()> {
+        let rows = house_query::Entity::find()
+            .join(
+                JoinType::LeftJoin,
+                house_query::Relation::CommunityQuery.def(),
+            )
+            .join(JoinType::LeftJoin, house_query::Relation::OwnerQuery.def())
+            .filter(house_query::Column::Id.is_in(ids.keys().cloned()))
+            .select_also(community_query::Entity)
+            .select_also(owner_query::Entity)
+            .all(self.pool.as_ref())
+            .await?;
+
+        for (house, community, owner) in rows {
+            let changed_at = ids.remove(&house.id).unwrap_or(house.updated_at);
+            if let Some(deleted_at) = house.deleted_at {
+                changes.tombstones.push(SyncTombstone {
+                    entity: HOUSE,
+                    id: house.id,
+                    deleted_at: deleted_at.max(changed_at),
+                });
+                continue;
+            }
+
+            changes
+                .houses
+                .push(house_dto(house, community, owner, scope));
+        }
+
+        // 读模型里已经没有的也当作删除
+        changes
+            .tombstones
+            .extend(ids.into_iter().map(|(id, deleted_at)| SyncTombstone {
+                entity: HOUSE,
+                id,
+                deleted_at,
+            }));
+
+        Ok(())
+    }
+
+    async fn load_communities(
+        &self,
+        mut ids: BTreeMap<String, DateTimeUtc>,
+        changes: &mut SyncChanges,
+    ) -> anyhow::Result<()> {
+        let communities = community_query::Entity::find()
+            .filter(community_query::Column::Id.is_in(ids.keys().cloned()))
+            .all(self.pool.as_ref())
+            .await?;
+
+        for community in &communities {
+            ids.remove(&community.id);
+        }
+        changes.communities = communities;
+        changes
+            .tombstones
+            .extend(ids.into_iter().map(|(id, deleted_at)| SyncTombstone {
+                entity: COMMUNITY,
+                id,
+                deleted_at,
+            }));
+
+        Ok(())
+    }
+
+    async fn load_owners(
+        &self,
+        mut ids: BTreeMap<String, DateTimeUtc>,
+        changes: &mut SyncChanges,
+    ) -> anyhow::Result<()> {
+        let owners = owner_query::Entity::find()
+            .filter(owner_query::Column::Id.is_in(ids.keys().cloned()))
+            .all(self.pool.as_ref())
+            .await?;
+
+        for owner in &owners {
+            ids.remove(&owner.id);
+        }
+        changes.owners = owners;
+        changes
+            .tombstones
+            .extend(ids.into_iter().map(|(id, deleted_at)| SyncTombstone {
+                entity: OWNER,
+                id,
+                deleted_at,
+            }));
+
+        Ok(())
+    }
+}
+
+// 没有同步业主信息权限时去掉业主
+fn house_dto(
+    house: house_query::Model,
+    community: Option<community_query::Model>,
+    owner: Option<owner_query::Model>,
+    scope: SyncScope,
+) -> HouseDataDto {
+    let owner = owner.filter(|_| scope.owners);
+    let mut dto = HouseDataDto::new(house, community, owner);
+    if !scope.owners {
+        dto.house.owner_id = None;
+    }
+    dto
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SyncCursor;
+
+    #[test]
+    fn test_cursor() {
+        let cursor = SyncCursor {
+            seq: 1024,
+            snapshot: None,
+        };
+        assert_eq!(SyncCursor::parse(&cursor.encode()).unwrap(), cursor);
+
+        // 快照游标里的房源 id 可以包含任意字符
+        let cursor = SyncCursor {
+            seq: 1024,
+            snapshot: Some("h-1.2".to_string()),
+        };
+        assert_eq!(cursor.encode(), "s400.h-1.2");
+        assert_eq!(SyncCursor::parse(&cursor.encode()).unwrap(), cursor);
+        assert!(SyncCursor::parse("1024").is_err());
+        assert!(SyncCursor::parse("cxyz").is_err());
+    }
+}
@@ -1,11 +1,21 @@
+use event_bus::OutboxEvent;
+
 use crate::domain::community::aggregates::community::CommunityAggregate;
 
 #[async_trait::async_trait]
 pub trait CommunityRepositoryAggregate: Send + Sync {
-    // 创建小区
-    async fn create(&self, aggregate: CommunityAggregate) -> anyhow::Result<()>;
+    // 创建小区，事件和聚合在同一个事务里写入 outbox
+    async fn create(
+        &self,
+        aggregate: CommunityAggregate,
+        events: &[OutboxEvent],
+    ) -> anyhow::Result<()>;
     // 更新小区
-    async fn save(&self, aggregate: &CommunityAggregate) -> anyhow::Result<()>;
+    async fn save(
+        &self,
+        aggregate: &CommunityAggregate,
+        events: &[OutboxEvent],
+    ) -> anyhow::Result<()>;
     // 获取小区
     async fn find_by_id(&self, id: &str) -> anyhow::Result<Option<CommunityAggregate>>;
 }
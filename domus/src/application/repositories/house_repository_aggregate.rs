@@ -1,13 +1,27 @@
+use std::fmt;
+
+use event_bus::OutboxEvent;
+
 use crate::domain::house::aggregates::house::HouseAggregate;
 
 #[async_trait::async_trait]
 pub trait HouseRepositoryAggregate: Send + Sync {
-    // 创建小区
-    async fn create(&self, aggregate: HouseAggregate) -> anyhow::Result<()>;
-    // 更新小区
-    async fn save(&self, aggregate: &HouseAggregate) -> anyhow::Result<()>;
+    // 创建小区，事件和聚合在同一个事务里写入 outbox
+    async fn create(&self, aggregate: HouseAggregate, events: &[OutboxEvent])
+        -> anyhow::Result<()>;
+    // 更新小区，库里的版本号不等于 expected_version 时返回 HouseVersionConflict
+    async fn save(
+        &self,
+        aggregate: &HouseAggregate,
+        expected_version: i64,
+        events: &[OutboxEvent],
+    ) -> anyhow::Result<()>;
     // 获取小区
     async fn find_by_id(&self, id: &str) -> anyhow::Result<HouseAggregate>;
+    // 获取回收站中的房源，未删除时返回错误
+    async fn find_deleted(&self, id: &str) -> anyhow::Result<HouseAggregate>;
+    // 从事件流重建小区
+    async fn load_from_events(&self, id: &str) -> anyhow::Result<HouseAggregate>;
     // 地址是否存在
     async fn exists_address(
         &self,
@@ -16,3 +30,22 @@ pub trait HouseRepositoryAggregate: Send + Sync {
         self_id: Option<String>,
     ) -> anyhow::Result<bool>;
 }
+
+// 保存时版本号不一致，说明房源在此期间被其他人修改过
+#[derive(Debug)]
+pub struct HouseVersionConflict {
+    pub house_id: String,
+    pub expected_version: i64,
+}
+
+impl fmt::Display for HouseVersionConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "房源 {} 已被其他人修改（版本 {} 已过期），请刷新后重试",
+            self.house_id, self.expected_version
+        )
+    }
+}
+
+impl std::error::Error for HouseVersionConflict {}
@@ -1,11 +1,13 @@
+use event_bus::OutboxEvent;
+
 use crate::domain::owner::aggregates::owner::OwnerAggregate;
 
 #[async_trait::async_trait]
 pub trait OwnerRepositoryAggregate: Send + Sync {
-    // 创建owner
-    async fn create(&self, name: OwnerAggregate) -> anyhow::Result<()>;
+    // 创建owner，事件和聚合在同一个事务里写入 outbox
+    async fn create(&self, name: OwnerAggregate, events: &[OutboxEvent]) -> anyhow::Result<()>;
     // 更新owner
-    async fn save(&self, name: &OwnerAggregate) -> anyhow::Result<()>;
+    async fn save(&self, name: &OwnerAggregate, events: &[OutboxEvent]) -> anyhow::Result<()>;
     // 获取owner
     async fn find_by_id(&self, id: &str) -> anyhow::Result<OwnerAggregate>;
     // 根据手机号获取owner
@@ -89,6 +89,21 @@ impl FavoriteService {
         Ok(())
     }
 
+    // 删除用户的全部收藏及收藏夹（用户被删除时）
+    pub async fn delete_user_favorites(&self, user_id: &str) -> anyhow::Result<()> {
+        user_favorites::Entity::delete_many()
+            .filter(user_favorites::Column::UserId.eq(user_id))
+            .exec(self.pool.as_ref())
+            .await?;
+
+        favorite_categories::Entity::delete_many()
+            .filter(favorite_categories::Column::UserId.eq(user_id))
+            .exec(self.pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
     // 查询用户收藏夹
     pub async fn find_favorite_categories(
         &self,
@@ -0,0 +1,209 @@
+use std::{collections::HashSet, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::{
+        commands::batch_house::{BatchHouseCommand, BatchHouseCommandHandler, HouseBatchAction},
+        queries::house::{HouseQueryService, HouseRequest},
+        services::house_operation_log::{HouseOperationLogDto, HouseOperationLogService},
+    },
+    domain::house::value_objects::{dictionary::Dictionary, house::House},
+};
+
+// 单次批量操作的最大房源数
+const MAX_ITEMS: usize = 500;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HouseBatchRequest {
+    #[serde(flatten)]
+    pub action: HouseBatchAction,
+    // 指定房源，与 filter 二选一
+    pub ids: Option<Vec<String>>,
+    // 按房源列表的筛选条件选择房源
+    pub filter: Option<HouseRequest>,
+}
+
+// 批量操作的来源，写入操作日志
+pub struct HouseBatchOperator {
+    pub operator_id: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HouseBatchItem {
+    pub house_id: String,
+    pub ok: bool,
+    pub error: Option<String>,
+    // 操作后的版本号
+    pub version: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HouseBatchReport {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub items: Vec<HouseBatchItem>,
+}
+
+pub struct HouseBatchService {
+    batch_house_command_handler: BatchHouseCommandHandler,
+    house_query_service: Arc<HouseQueryService>,
+    house_operation_log_service: Arc<HouseOperationLogService>,
+}
+
+impl HouseBatchService {
+    pub fn new(
+        batch_house_command_handler: BatchHouseCommandHandler,
+        house_query_service: Arc<HouseQueryService>,
+        house_operation_log_service: Arc<HouseOperationLogService>,
+    ) -> Self {
+        Self {
+            batch_house_command_handler,
+            house_query_service,
+            house_operation_log_service,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        request: HouseBatchRequest,
+        operator: HouseBatchOperator,
+    ) -> anyhow::Result<HouseBatchReport> {
+        let ids = self.house_ids(request.ids, request.filter).await?;
+
+        // 逐套执行，单套失败不影响其他房源
+        let mut items = Vec::with_capacity(ids.len());
+        for house_id in ids {
+            let result = self
+                .execute_one(&house_id, &request.action, &operator)
+                .await;
+            items.push(match result {
+                Ok(version) => HouseBatchItem {
+                    house_id,
+                    ok: true,
+                    error: None,
+                    version: Some(version),
+                },
+                Err(e) => HouseBatchItem {
+                    house_id,
+                    ok: false,
+                    error: Some(e.to_string()),
+                    version: None,
+                },
+            });
+        }
+
+        let succeeded = items.iter().filter(|item| item.ok).count();
+        Ok(HouseBatchReport {
+            total: items.len(),
+            succeeded,
+            failed: items.len() - succeeded,
+            items,
+        })
+    }
+
+    async fn execute_one(
+        &self,
+        house_id: &str,
+        action: &HouseBatchAction,
+        operator: &HouseBatchOperator,
+    ) -> anyhow::Result<i64> {
+        let command = BatchHouseCommand {
+            house_id: house_id.to_string(),
+            action: action.clone(),
+            actor_id: Some(operator.operator_id.clone()),
+        };
+
+        let content = if action.is_edit() {
+            let mut house = self
+                .house_query_service
+                .find_by_id(house_id)
+                .await
+                .ok_or_else(|| anyhow::anyhow!("房源不存在"))?
+                .house;
+            // 按读模型预览修改，只用于检查有无变化和记录日志，聚合只收到修改的字段
+            action.edit(&mut house)?;
+
+            // 与保存房源一致，修改在执行前记录，日志里是与读模型的差异
+            self.record(action.operation_type(), house, operator).await;
+            None
+        } else {
+            Some(House {
+                id: Some(house_id.to_string()),
+                house_status: match action {
+                    HouseBatchAction::ChangeStatus { status, .. } => {
+                        Some(status.as_str().to_string())
+                    }
+                    _ => None,
+                },
+                ..Default::default()
+            })
+        };
+
+        let version = self.batch_house_command_handler.handle(command).await?;
+        if let Some(content) = content {
+            self.record(action.operation_type(), content, operator)
+                .await;
+        }
+
+        Ok(version)
+    }
+
+    // 指定的 id 去重，按筛选条件时查出匹配的房源
+    async fn house_ids(
+        &self,
+        ids: Option<Vec<String>>,
+        filter: Option<HouseRequest>,
+    ) -> anyhow::Result<Vec<String>> {
+        let ids = match (ids, filter) {
+            (Some(ids), None) => {
+                let mut seen = HashSet::new();
+                ids.into_iter()
+                    .filter(|id| !id.is_empty() && seen.insert(id.clone()))
+                    .collect::<Vec<_>>()
+            }
+            (None, Some(filter)) => {
+                let ids = self
+                    .house_query_service
+                    .find_ids(filter, MAX_ITEMS as u64 + 1)
+                    .await?;
+                if ids.len() > MAX_ITEMS {
+                    return Err(anyhow::anyhow!(
+                        "筛选出的房源超过 {} 套，请缩小筛选范围",
+                        MAX_ITEMS
+                    ));
+                }
+                ids
+            }
+            _ => return Err(anyhow::anyhow!("请指定 ids 或 filter 其中之一")),
+        };
+
+        if ids.is_empty() {
+            return Err(anyhow::anyhow!("没有需要处理的房源"));
+        }
+        if ids.len() > MAX_ITEMS {
+            return Err(anyhow::anyhow!("单次最多处理 {} 套房源", MAX_ITEMS));
+        }
+
+        Ok(ids)
+    }
+
+    async fn record(&self, operation_type: u8, house: House, operator: &HouseBatchOperator) {
+        if let Err(err) = self
+            .house_operation_log_service
+            .save_record(HouseOperationLogDto {
+                operation_type,
+                operation_content: house,
+                operator_id: operator.operator_id.clone(),
+                ip_address: operator.ip_address.clone(),
+                user_agent: operator.user_agent.clone(),
+            })
+            .await
+        {
+            tracing::error!("记录操作日志失败:{}", err);
+        }
+    }
+}
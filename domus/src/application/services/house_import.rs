@@ -0,0 +1,584 @@
+use std::{collections::HashMap, sync::Arc};
+
+use sea_orm::{ColumnTrait, DbConn, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    application::{
+        queries::house_duplicate::HouseDuplicateQueryService,
+        repositories::{
+            house_repository_aggregate::HouseRepositoryAggregate,
+            owner_repository_aggregate::OwnerRepositoryAggregate,
+        },
+        services::save_house::SaveHouseService,
+    },
+    domain::house::value_objects::{
+        house::{House, HouseData},
+        house_address::HouseAddress,
+    },
+    infrastructure::{entitiy::community_query, spreadsheet::Spreadsheet},
+};
+
+// 上传文件大小上限
+pub const IMPORT_MAX_BYTES: usize = 20 * 1024 * 1024;
+// 单次导入的最大行数
+const MAX_ROWS: usize = 5000;
+
+// 单元格的取值类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CellKind {
+    Text,
+    Number,
+    Integer,
+    // 逗号、顿号分隔的多个值
+    List,
+}
+
+// 表头到 HouseData 字段的映射，path 为 JSON 路径，community.、owner. 开头的写入小区、业主
+struct ImportColumn {
+    path: &'static str,
+    aliases: &'static [&'static str],
+    kind: CellKind,
+}
+
+const fn column(
+    path: &'static str,
+    aliases: &'static [&'static str],
+    kind: CellKind,
+) -> ImportColumn {
+    ImportColumn {
+        path,
+        aliases,
+        kind,
+    }
+}
+
+const COLUMNS: &[ImportColumn] = &[
+    // 房源
+    column("title", &["标题", "房源标题"], CellKind::Text),
+    column("purpose", &["用途"], CellKind::Text),
+    column("transaction_type", &["交易类型"], CellKind::Text),
+    column("house_status", &["状态", "房源状态"], CellKind::Text),
+    column(
+        "house_address",
+        &["地址", "房源地址", "门牌号"],
+        CellKind::Text,
+    ),
+    column("building_area", &["建筑面积", "面积"], CellKind::Number),
+    column("use_area", &["使用面积"], CellKind::Number),
+    column("sale_price", &["售价"], CellKind::Number),
+    column("rent_price", &["租价", "租金"], CellKind::Number),
+    column("down_payment", &["首付"], CellKind::Number),
+    column(
+        "floor_range.door_number_from",
+        &["楼层", "所在楼层"],
+        CellKind::Integer,
+    ),
+    column("floor_range.door_number_to", &["总楼层"], CellKind::Integer),
+    column("apartment_type.room", &["室"], CellKind::Integer),
+    column("apartment_type.hall", &["厅"], CellKind::Integer),
+    column("apartment_type.bathroom", &["卫"], CellKind::Integer),
+    column("house_decoration", &["装修"], CellKind::Text),
+    column("house_orientation", &["朝向"], CellKind::Text),
+    column("house_type", &["房屋类型"], CellKind::Text),
+    column("view_method", &["看房方式"], CellKind::Text),
+    column("property_rights", &["产权性质"], CellKind::Text),
+    column("building_structure", &["建筑结构"], CellKind::Text),
+    column("building_year", &["建筑年代"], CellKind::Text),
+    column("source", &["来源"], CellKind::Text),
+    column("delegate_number", &["委托编号"], CellKind::Text),
+    column("tags", &["标签", "推荐标签"], CellKind::List),
+    column("remark", &["备注"], CellKind::Text),
+    // 小区
+    column("community.id", &["小区ID", "小区编号"], CellKind::Text),
+    column("community.name", &["小区", "小区名称"], CellKind::Text),
+    column("community.address", &["小区地址"], CellKind::Text),
+    column("community.city", &["城市"], CellKind::Text),
+    column("community.district", &["行政区", "区域"], CellKind::Text),
+    column("community.adcode", &["行政区划代码"], CellKind::Text),
+    column("community.typecode", &["小区类型"], CellKind::Text),
+    column("community.lat", &["纬度"], CellKind::Number),
+    column("community.lng", &["经度"], CellKind::Number),
+    // 业主
+    column("owner.name", &["业主", "业主姓名"], CellKind::Text),
+    column(
+        "owner.phone",
+        &["业主电话", "电话", "手机号"],
+        CellKind::Text,
+    ),
+    column("owner.id_card", &["身份证", "身份证号"], CellKind::Text),
+    column("owner.description", &["业主情况"], CellKind::Text),
+];
+
+// 按表头找到对应的字段，表头可以是中文名或字段路径
+fn find_column(header: &str) -> Option<&'static ImportColumn> {
+    let header = header.trim();
+    COLUMNS
+        .iter()
+        .find(|column| column.path.eq_ignore_ascii_case(header) || column.aliases.contains(&header))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HouseImportQuery {
+    // 默认只校验不写入，确认无误后传 false 提交
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct HouseImportRow {
+    // 表格中的行号，表头为第 1 行
+    pub row: usize,
+    pub ok: bool,
+    pub house_id: Option<String>,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct HouseImportReport {
+    pub dry_run: bool,
+    pub total: usize,
+    // 校验通过（dry_run）或已导入的行数
+    pub succeeded: usize,
+    pub failed: usize,
+    // 没有识别的表头，这些列被忽略
+    pub ignored_columns: Vec<String>,
+    pub rows: Vec<HouseImportRow>,
+    // 已导入的房源，用于记录操作日志
+    #[serde(skip)]
+    pub houses: Vec<House>,
+}
+
+// 通过校验的行，提交时按顺序保存
+struct PendingRow {
+    index: usize,
+    data: HouseData,
+    // 文件里新建的小区名称，先保存的行创建后，后面的行关联到同一个小区
+    new_community: Option<String>,
+    // 文件里新建的业主电话
+    new_owner: Option<String>,
+}
+
+// 校验过程中在文件范围内去重
+#[derive(Default)]
+struct ImportContext {
+    // 小区名称 -> 已有小区 id，None 表示库里没有
+    communities: HashMap<String, Option<String>>,
+    // (小区, 统一后的地址) -> 行号
+    addresses: HashMap<(String, String), usize>,
+    // 业主电话 -> (姓名, 行号)
+    owners: HashMap<String, (Option<String>, usize)>,
+}
+
+// 从 CSV/Excel 批量导入房源，每行对应一个 HouseData
+pub struct HouseImportService {
+    pool: Arc<DbConn>,
+    save_house_service: Arc<SaveHouseService>,
+    house_repository: Arc<dyn HouseRepositoryAggregate>,
+    owner_repository: Arc<dyn OwnerRepositoryAggregate>,
+    house_duplicate_query_service: Arc<HouseDuplicateQueryService>,
+}
+
+impl HouseImportService {
+    pub fn new(
+        pool: Arc<DbConn>,
+        save_house_service: Arc<SaveHouseService>,
+        house_repository: Arc<dyn HouseRepositoryAggregate>,
+        owner_repository: Arc<dyn OwnerRepositoryAggregate>,
+        house_duplicate_query_service: Arc<HouseDuplicateQueryService>,
+    ) -> Self {
+        Self {
+            pool,
+            save_house_service,
+            house_repository,
+            owner_repository,
+            house_duplicate_query_service,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        bytes: &[u8],
+        dry_run: bool,
+        operator_id: &str,
+    ) -> anyhow::Result<HouseImportReport> {
+        let sheet = Spreadsheet::read(bytes)?;
+        if sheet.rows.len() > MAX_ROWS {
+            return Err(anyhow::anyhow!("单次最多导入 {} 行", MAX_ROWS));
+        }
+
+        let columns: Vec<Option<&ImportColumn>> =
+            sheet.headers.iter().map(|h| find_column(h)).collect();
+        if !columns.iter().flatten().any(|c| c.path == "house_address") {
+            return Err(anyhow::anyhow!("表头中缺少房源地址列"));
+        }
+
+        let mut report = HouseImportReport {
+            dry_run,
+            ignored_columns: sheet
+                .headers
+                .iter()
+                .zip(&columns)
+                .filter(|(header, column)| column.is_none() && !header.is_empty())
+                .map(|(header, _)| header.clone())
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut context = ImportContext::default();
+        let mut pending = Vec::new();
+        for (index, cells) in sheet.rows.iter().enumerate() {
+            // 跳过空行
+            if cells.iter().all(|cell| cell.is_empty()) {
+                continue;
+            }
+
+            let mut row = HouseImportRow {
+                row: index + 2,
+                ..Default::default()
+            };
+            match parse_row(&columns, cells) {
+                Ok(mut data) => {
+                    data.update_created_by(operator_id.to_string());
+                    if let Some(pending_row) = self.check(&mut context, &mut row, data).await? {
+                        pending.push(PendingRow {
+                            index: report.rows.len(),
+                            ..pending_row
+                        });
+                    }
+                }
+                Err(errors) => row.errors = errors,
+            }
+            row.ok = row.errors.is_empty();
+            report.rows.push(row);
+        }
+
+        if !dry_run {
+            self.commit(pending, &mut report).await;
+        }
+
+        report.total = report.rows.len();
+        report.succeeded = report.rows.iter().filter(|row| row.ok).count();
+        report.failed = report.total - report.succeeded;
+
+        Ok(report)
+    }
+
+    // 校验一行，通过时返回待保存的数据，错误和提示写入 row
+    async fn check(
+        &self,
+        context: &mut ImportContext,
+        row: &mut HouseImportRow,
+        mut data: HouseData,
+    ) -> anyhow::Result<Option<PendingRow>> {
+        let Some(house) = data.house.as_mut() else {
+            row.errors.push("参数不完整".to_string());
+            return Ok(None);
+        };
+
+        // 与新建房源的规则一致，预检查的结果和实际导入相同
+        if let Err(e) = house.prepare_create() {
+            row.errors.push(e.to_string());
+        }
+
+        // 小区：优先按 id，其次按名称关联已有小区，都没有时需要完整的小区信息才能新建
+        let mut new_community = None;
+        let community_key = match data.community.take() {
+            Some(community) => {
+                let existing = match (&community.id, &community.name) {
+                    (Some(id), _) => self.find_community(id, true).await?,
+                    (None, Some(name)) => match context.communities.get(name) {
+                        Some(id) => id.clone(),
+                        None => {
+                            let id = self.find_community(name, false).await?;
+                            context.communities.insert(name.clone(), id.clone());
+                            id
+                        }
+                    },
+                    (None, None) => None,
+                };
+
+                match existing {
+                    Some(id) => {
+                        house.community_id = Some(id.clone());
+                        Some(id)
+                    }
+                    None => match community.validate() {
+                        Ok(()) => {
+                            let key = community.id.clone().or(community.name.clone());
+                            if community.id.is_none() {
+                                new_community = community.name.clone();
+                            }
+                            row.warnings.push(format!(
+                                "小区「{}」不存在，将新建",
+                                community.name.clone().unwrap_or_default()
+                            ));
+                            data.community = Some(community);
+                            key
+                        }
+                        Err(_) => {
+                            row.errors.push(format!(
+                                "小区「{}」不存在，请先创建小区或补全小区地址、城市、坐标等信息",
+                                community.name.or(community.id).unwrap_or_default()
+                            ));
+                            None
+                        }
+                    },
+                }
+            }
+            None => {
+                row.errors.push("小区不能为空".to_string());
+                None
+            }
+        };
+
+        // 地址：库里已有的和文件里重复的都不能导入，写法不同的相似地址只做提示
+        if let (Some(community_key), Some(address)) = (community_key, house.house_address.clone()) {
+            if let Some(community_id) = &house.community_id {
+                if self
+                    .house_repository
+                    .exists_address(community_id, &address, None)
+                    .await?
+                {
+                    row.errors.push("地址已存在, 您可以更新它".to_string());
+                } else if let Some(similar) = self
+                    .house_duplicate_query_service
+                    .find_similar(community_id, &address, None)
+                    .await?
+                    .first()
+                {
+                    row.warnings
+                        .push(format!("可能与已有房源「{}」重复", similar.house_address));
+                }
+            }
+
+            let normalized = HouseAddress::parse(&address).normalized();
+            if let Some(first) = context
+                .addresses
+                .insert((community_key, normalized), row.row)
+            {
+                row.errors.push(format!("与第 {} 行地址重复", first));
+            }
+        }
+
+        // 业主：按电话去重，已有业主直接关联，不覆盖业主资料
+        let mut new_owner = None;
+        if let Some(owner) = data.owner.take() {
+            match owner.validate() {
+                Err(e) => row.errors.push(e.to_string()),
+                Ok(()) => {
+                    let phone = owner.phone.clone().unwrap_or_default();
+                    if let Some((name, first)) = context.owners.get(&phone) {
+                        if name != &owner.name {
+                            row.errors
+                                .push(format!("业主电话与第 {} 行相同但姓名不一致", first));
+                        } else {
+                            row.warnings.push(format!("与第 {} 行为同一业主", first));
+                            new_owner = Some(phone.clone());
+                        }
+                    } else if let Some(existing) =
+                        self.owner_repository.find_by_phone(&phone).await?
+                    {
+                        row.warnings
+                            .push(format!("业主电话已存在，将关联到业主「{}」", existing.name));
+                        house.owner_id = Some(existing.owner_id);
+                    } else {
+                        if let Some(id_card) = &owner.id_card {
+                            if self.owner_repository.exists_id_card(id_card, None).await? {
+                                row.errors.push("身份证号已存在".to_string());
+                            }
+                        }
+                        context
+                            .owners
+                            .insert(phone.clone(), (owner.name.clone(), row.row));
+                        new_owner = Some(phone);
+                        data.owner = Some(owner);
+                    }
+                }
+            }
+        }
+
+        if !row.errors.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(PendingRow {
+            index: 0,
+            data,
+            new_community,
+            new_owner,
+        }))
+    }
+
+    // 逐行保存，单行失败不影响其他行
+    async fn commit(&self, pending: Vec<PendingRow>, report: &mut HouseImportReport) {
+        let mut communities: HashMap<String, String> = HashMap::new();
+        let mut owners: HashMap<String, String> = HashMap::new();
+
+        for PendingRow {
+            index,
+            mut data,
+            new_community,
+            new_owner,
+        } in pending
+        {
+            let row = &mut report.rows[index];
+            let house = data.house.get_or_insert_with(House::default);
+
+            // 前面的行已经建好的小区、业主直接关联
+            if let Some(id) = new_community
+                .as_ref()
+                .and_then(|name| communities.get(name))
+            {
+                house.community_id = Some(id.clone());
+                data.community = None;
+            }
+            if let Some(id) = new_owner.as_ref().and_then(|phone| owners.get(phone)) {
+                house.owner_id = Some(id.clone());
+                data.owner = None;
+            }
+
+            match self.save_house_service.execute(data).await {
+                Ok(house) => {
+                    if let (Some(name), Some(id)) = (new_community, house.community_id.clone()) {
+                        communities.insert(name, id);
+                    }
+                    if let (Some(phone), Some(id)) = (new_owner, house.owner_id.clone()) {
+                        owners.insert(phone, id);
+                    }
+                    row.house_id = house.id.clone();
+                    report.houses.push(house);
+                }
+                Err(e) => {
+                    row.ok = false;
+                    row.errors.push(e.to_string());
+                }
+            }
+        }
+    }
+
+    // 按 id 或名称查找已有小区
+    async fn find_community(&self, key: &str, by_id: bool) -> anyhow::Result<Option<String>> {
+        let column = if by_id {
+            community_query::Column::Id
+        } else {
+            community_query::Column::Name
+        };
+
+        Ok(community_query::Entity::find()
+            .filter(column.eq(key))
+            .one(self.pool.as_ref())
+            .await?
+            .map(|community| community.id))
+    }
+}
+
+// 把一行单元格转为 HouseData，数字格式错误时返回所有错误
+fn parse_row(
+    columns: &[Option<&ImportColumn>],
+    cells: &[String],
+) -> Result<HouseData, Vec<String>> {
+    let mut json = Map::new();
+    let mut errors = Vec::new();
+
+    for (column, cell) in columns.iter().zip(cells) {
+        let (Some(column), false) = (column, cell.is_empty()) else {
+            continue;
+        };
+
+        let value = match column.kind {
+            CellKind::Text => Some(Value::String(cell.clone())),
+            CellKind::Number => cell
+                .replace(',', "")
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number),
+            CellKind::Integer => cell.parse::<i32>().ok().map(Value::from),
+            CellKind::List => Some(Value::from(
+                cell.split([',', '，', '、'])
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .collect::<Vec<_>>(),
+            )),
+        };
+
+        let Some(value) = value else {
+            errors.push(format!("「{}」格式不正确: {}", column.aliases[0], cell));
+            continue;
+        };
+
+        // 按路径写入嵌套对象
+        let mut target = &mut json;
+        let mut keys = column.path.split('.').peekable();
+        while let Some(key) = keys.next() {
+            if keys.peek().is_none() {
+                target.insert(key.to_string(), value);
+                break;
+            }
+            target = target
+                .entry(key)
+                .or_insert_with(|| Value::Object(Map::new()))
+                .as_object_mut()
+                .expect("导入列路径冲突");
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    serde_json::from_value(Value::Object(json)).map_err(|e| vec![e.to_string()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_column, parse_row};
+
+    #[test]
+    fn test_parse_row() {
+        let headers = [
+            "小区",
+            "地址",
+            "sale_price",
+            "标签",
+            "业主电话",
+            "楼层",
+            "不认识",
+        ];
+        let columns: Vec<_> = headers.iter().map(|h| find_column(h)).collect();
+        assert!(columns[6].is_none());
+
+        let cells: Vec<String> = [
+            "皖江华府",
+            "11栋104室",
+            "1,200,000",
+            "满五、唯一",
+            "13966605120",
+            "8",
+            "x",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let data = parse_row(&columns, &cells).unwrap();
+        let house = data.house.unwrap();
+        assert_eq!(house.house_address.as_deref(), Some("11栋104室"));
+        assert_eq!(house.sale_price, Some(1_200_000.0));
+        assert_eq!(
+            house.tags,
+            Some(vec!["满五".to_string(), "唯一".to_string()])
+        );
+        assert_eq!(house.floor_range.unwrap().door_number_from, Some(8));
+        assert_eq!(data.community.unwrap().name.as_deref(), Some("皖江华府"));
+        assert_eq!(data.owner.unwrap().phone.as_deref(), Some("13966605120"));
+
+        let mut cells = cells;
+        cells[2] = "面议".to_string();
+        cells[5] = "高层".to_string();
+        let errors = parse_row(&columns, &cells).unwrap_err();
+        assert_eq!(errors.len(), 2);
+    }
+}
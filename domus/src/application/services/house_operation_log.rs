@@ -68,7 +68,7 @@ impl HouseOperationLogService {
 }
 
 pub struct HouseOperationLogDto {
-    // '操作类型：1=新增，2=修改，3=删除，4=上架，5=下架'
+    // '操作类型：1=新增，2=修改，3=删除，4=上架，5=下架，6=状态变更，7=恢复'
     pub operation_type: u8,
     pub operation_content: House,
     pub operator_id: String,
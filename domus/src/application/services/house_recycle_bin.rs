@@ -0,0 +1,277 @@
+use std::{sync::Arc, time::Duration};
+
+use event_bus::{AsyncEventBus, OutboxEvent};
+use sea_orm::{
+    prelude::DateTimeUtc, ColumnTrait, DbConn, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+    TransactionTrait,
+};
+use serde::Serialize;
+use serde_json::json;
+use shared_dto::table_data::TableDataResponse;
+use tokio::task::JoinHandle;
+
+use crate::{
+    application::{
+        commands::restore_house::{RestoreHouseCommand, RestoreHouseCommandHandler},
+        queries::house::{HouseQueryService, HouseRequest},
+        repositories::house_repository_aggregate::HouseRepositoryAggregate,
+    },
+    domain::house::value_objects::house::FileInfo,
+    infrastructure::{
+        dto::house_data_dto::HouseDataDto,
+        entitiy::{house_aggregate, house_comments, house_query, user_favorites},
+    },
+};
+
+// 回收站默认保留天数
+pub const DEFAULT_RETENTION_DAYS: i64 = 30;
+// 检查过期房源的间隔
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// 每批彻底删除的房源数
+const PURGE_BATCH: u64 = 100;
+// 房源图片在 filestore 中的路径前缀，之后的部分为对象名
+const MEDIA_BUCKET_PATH: &str = "domus-houses-images/";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecycledHouse {
+    #[serde(flatten)]
+    pub house: HouseDataDto,
+    // 到期后彻底删除的时间
+    pub purge_at: Option<DateTimeUtc>,
+}
+
+// 房源回收站：列出、恢复已删除的房源，超过保留期后彻底删除
+pub struct HouseRecycleBinService {
+    pool: Arc<DbConn>,
+    house_repository: Arc<dyn HouseRepositoryAggregate>,
+    event_bus: Arc<AsyncEventBus>,
+    restore_house_command_handler: RestoreHouseCommandHandler,
+    house_query_service: Arc<HouseQueryService>,
+    retention: chrono::Duration,
+    // filestore 接口地址，如 http://127.0.0.1:9003/api/filestore
+    filestore_url: Option<String>,
+    // 调用 filestore 的内部令牌，与 filestore 的 FILESTORE_INTERNAL_TOKEN 一致
+    filestore_token: Option<String>,
+}
+
+impl HouseRecycleBinService {
+    pub fn new(
+        pool: Arc<DbConn>,
+        restore_house_command_handler: RestoreHouseCommandHandler,
+        house_query_service: Arc<HouseQueryService>,
+    ) -> Self {
+        Self {
+            pool,
+            house_repository: restore_house_command_handler.house_repository.clone(),
+            event_bus: restore_house_command_handler.event_bus.clone(),
+            restore_house_command_handler,
+            house_query_service,
+            retention: chrono::Duration::days(DEFAULT_RETENTION_DAYS),
+            filestore_url: None,
+            filestore_token: None,
+        }
+    }
+
+    // 删除后保留的天数
+    pub fn retention_days(mut self, days: i64) -> Self {
+        self.retention = chrono::Duration::days(days.max(1));
+        self
+    }
+
+    // 彻底删除时一并删除 filestore 中的图片，不设置时只删除数据
+    pub fn filestore_url(mut self, url: Option<String>) -> Self {
+        self.filestore_url = url.map(|url| url.trim_end_matches('/').to_string());
+        self
+    }
+
+    pub fn filestore_token(mut self, token: Option<String>) -> Self {
+        self.filestore_token = token;
+        self
+    }
+
+    pub async fn list(
+        &self,
+        params: HouseRequest,
+    ) -> anyhow::Result<TableDataResponse<RecycledHouse>> {
+        let data = self.house_query_service.find_deleted(params).await?;
+        let list = data
+            .list
+            .into_iter()
+            .map(|house| RecycledHouse {
+                purge_at: house.house.deleted_at.map(|at| at + self.retention),
+                house,
+            })
+            .collect();
+
+        Ok(TableDataResponse::new(list, data.total))
+    }
+
+    // 恢复房源，返回新的版本号
+    pub async fn restore(&self, house_id: String) -> anyhow::Result<i64> {
+        self.restore_house_command_handler
+            .handle(RestoreHouseCommand::new(house_id))
+            .await
+    }
+
+    // 定时彻底删除过期的房源
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match self.purge_expired().await {
+                    Ok(0) => {}
+                    Ok(count) => tracing::info!("回收站: 彻底删除 {} 套过期房源", count),
+                    Err(err) => tracing::error!("回收站清理失败: {}", err),
+                }
+                tokio::time::sleep(PURGE_INTERVAL).await;
+            }
+        })
+    }
+
+    // 彻底删除超过保留期的房源，返回删除的数量
+    pub async fn purge_expired(&self) -> anyhow::Result<usize> {
+        let expired_before = chrono::Utc::now() - self.retention;
+
+        let mut purged = 0;
+        loop {
+            let ids = house_aggregate::Entity::find()
+                .select_only()
+                .column(house_aggregate::Column::HouseId)
+                .filter(house_aggregate::Column::DeletedAt.lt(expired_before))
+                .order_by_asc(house_aggregate::Column::DeletedAt)
+                .limit(PURGE_BATCH)
+                .into_tuple::<String>()
+                .all(self.pool.as_ref())
+                .await?;
+
+            // 单套失败不影响其他房源，下次再试
+            let mut count = 0;
+            for house_id in &ids {
+                match self.purge(house_id, expired_before).await {
+                    Ok(true) => count += 1,
+                    Ok(false) => {}
+                    Err(err) => tracing::error!("彻底删除房源 {} 失败: {}", house_id, err),
+                }
+            }
+            purged += count;
+
+            if (ids.len() as u64) < PURGE_BATCH || count == 0 {
+                return Ok(purged);
+            }
+        }
+    }
+
+    // 彻底删除房源：删除评论、收藏和聚合，写入 Purged 事件，读模型、价格记录和索引由监听器删除。
+    // 事件流和快照保留，历史、回放和同步的删除记录不受影响；操作日志保留备查
+    async fn purge(&self, house_id: &str, expired_before: DateTimeUtc) -> anyhow::Result<bool> {
+        let images: Vec<FileInfo> = house_query::Entity::find_by_id(house_id)
+            .one(self.pool.as_ref())
+            .await?
+            .and_then(|house| house.images)
+            .and_then(|images| serde_json::from_value(images).ok())
+            .unwrap_or_default();
+
+        let mut aggregate = self.house_repository.find_deleted(house_id).await?;
+        let expected_version = aggregate.version;
+        let event = aggregate.purge()?;
+
+        let txn = self.pool.begin().await?;
+        // 先删聚合，期间被恢复或修改的房源不再删除
+        let result = house_aggregate::Entity::delete_many()
+            .filter(house_aggregate::Column::HouseId.eq(house_id))
+            .filter(house_aggregate::Column::DeletedAt.lt(expired_before))
+            .filter(house_aggregate::Column::Version.eq(expected_version))
+            .exec(&txn)
+            .await?;
+        if result.rows_affected == 0 {
+            return Ok(false);
+        }
+
+        house_comments::Entity::delete_many()
+            .filter(house_comments::Column::HouseId.eq(house_id))
+            .exec(&txn)
+            .await?;
+        user_favorites::Entity::delete_many()
+            .filter(user_favorites::Column::HouseId.eq(house_id))
+            .exec(&txn)
+            .await?;
+        OutboxEvent::append(&txn, &[OutboxEvent::from_event(&event)]).await?;
+        txn.commit().await?;
+        self.event_bus.notify();
+
+        // 数据已经删除，图片删除失败只记录日志
+        if let Err(err) = self.delete_images(&images).await {
+            tracing::error!("删除房源 {} 的图片失败: {}", house_id, err);
+        }
+
+        Ok(true)
+    }
+
+    async fn delete_images(&self, images: &[FileInfo]) -> anyhow::Result<()> {
+        let Some(url) = &self.filestore_url else {
+            return Ok(());
+        };
+        // 不在房源图片桶里的地址不是 filestore 上传的，不删除
+        let names: Vec<&str> = images.iter().filter_map(object_name).collect();
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        let res = reqwest::Client::new()
+            .post(format!("{}/delete_house_media", url))
+            .header(
+                "X-Internal-Token",
+                self.filestore_token.as_deref().unwrap_or_default(),
+            )
+            .json(&json!({ "names": names }))
+            .send()
+            .await?;
+        if !res.status().is_success() {
+            return Err(anyhow::anyhow!("filestore 返回 {}", res.status()));
+        }
+
+        let body: serde_json::Value = res.json().await?;
+        if body["code"] != 200 {
+            return Err(anyhow::anyhow!("{}", body["msg"]));
+        }
+
+        Ok(())
+    }
+}
+
+// 图片在 filestore 中的对象名，取地址中桶名之后的部分
+fn object_name(file: &FileInfo) -> Option<&str> {
+    file.url
+        .split_once(MEDIA_BUCKET_PATH)
+        .map(|(_, name)| name)
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::object_name;
+    use crate::domain::house::value_objects::house::FileInfo;
+
+    #[test]
+    fn test_object_name() {
+        let file = |name: &str, url: &str| FileInfo {
+            name: name.to_string(),
+            r#type: "image/jpeg".to_string(),
+            size: "1024".to_string(),
+            url: url.to_string(),
+        };
+
+        assert_eq!(
+            object_name(&file(
+                "a.jpg",
+                "http://127.0.0.1:9000/domus-houses-images/2025/b.jpg"
+            )),
+            Some("2025/b.jpg")
+        );
+        // 其他地址的图片不删除
+        assert_eq!(object_name(&file("a.jpg", "")), None);
+        assert_eq!(
+            object_name(&file("a.jpg", "http://cdn.example.com/a.jpg")),
+            None
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::{
+    application::{
+        commands::change_house_status::{
+            ChangeHouseStatusCommand, ChangeHouseStatusCommandHandler,
+        },
+        repositories::house_repository_aggregate::HouseRepositoryAggregate,
+    },
+    domain::house::value_objects::dictionary::{Dictionary, HouseStatus},
+};
+
+// 房源当前状态及可以变更到的状态
+#[derive(Debug, Clone, Serialize)]
+pub struct HouseStatusTransitions {
+    pub status: Option<HouseStatus>,
+    pub transitions: Vec<HouseStatus>,
+    pub version: i64,
+}
+
+pub struct HouseStatusService {
+    change_house_status_command_handler: ChangeHouseStatusCommandHandler,
+    house_repository: Arc<dyn HouseRepositoryAggregate>,
+}
+
+impl HouseStatusService {
+    pub fn new(
+        change_house_status_command_handler: ChangeHouseStatusCommandHandler,
+        house_repository: Arc<dyn HouseRepositoryAggregate>,
+    ) -> Self {
+        Self {
+            change_house_status_command_handler,
+            house_repository,
+        }
+    }
+
+    pub async fn change_status(&self, command: ChangeHouseStatusCommand) -> anyhow::Result<()> {
+        self.change_house_status_command_handler
+            .handle(command)
+            .await
+    }
+
+    pub async fn transitions(&self, house_id: &str) -> anyhow::Result<HouseStatusTransitions> {
+        let aggregate = self.house_repository.find_by_id(house_id).await?;
+        let transitions = match aggregate.status {
+            Some(status) => status.transitions().to_vec(),
+            // 状态无法识别时可以修正为任意状态
+            None => HouseStatus::ALL
+                .iter()
+                .copied()
+                .filter(|status| *status != HouseStatus::Draft)
+                .collect(),
+        };
+
+        Ok(HouseStatusTransitions {
+            status: aggregate.status,
+            transitions,
+            version: aggregate.version,
+        })
+    }
+}
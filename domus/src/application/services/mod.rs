@@ -2,8 +2,12 @@ pub mod delete_community;
 pub mod delete_house;
 pub mod delete_owner;
 pub mod favorite;
+pub mod house_batch;
 pub mod house_comment;
+pub mod house_import;
 pub mod house_operation_log;
+pub mod house_recycle_bin;
+pub mod house_status;
 pub mod save_community;
 pub mod save_house;
 pub mod save_owner;
@@ -48,7 +48,7 @@ impl SaveHouseService {
         // 创建房屋
         let house_id = self
             .save_house_command_handler
-            .handle(SaveHouseCommand::new(house.clone()))
+            .handle(SaveHouseCommand::new(house.clone(), house_data.version))
             .await?;
 
         house.id = Some(house_id.clone());
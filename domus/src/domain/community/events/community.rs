@@ -2,11 +2,26 @@ use crate::domain::community::{
     events::community_deleted::CommunityDeletedEvent, value_objects::commuity::Community,
 };
 
-use serde::Serialize;
+use event_bus::Event;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CommunityEvent {
     Created(Community),
     Updated(Community),
     Deleted(CommunityDeletedEvent),
 }
+
+impl Event for CommunityEvent {
+    const NAME: &'static str = "CommunityEvent";
+    const AGGREGATE_TYPE: &'static str = "community";
+
+    fn aggregate_id(&self) -> String {
+        match self {
+            CommunityEvent::Created(community) | CommunityEvent::Updated(community) => {
+                community.id.clone().unwrap_or_default()
+            }
+            CommunityEvent::Deleted(event) => event.id.clone(),
+        }
+    }
+}
@@ -1,13 +1,26 @@
+use event_bus::{Aggregate, EventEnvelope};
 use sea_orm::prelude::DateTimeUtc;
+use serde::{Deserialize, Serialize};
 
 use crate::domain::house::{
     events::{
-        house::HouseEvent, house_deleted::HouseDeletedEvent, house_published::HousePublishedEvent,
+        house::HouseEvent,
+        house_deleted::HouseDeletedEvent,
+        house_patched::HousePatchedEvent,
+        house_price_changed::{HousePriceChangedEvent, PriceField},
+        house_published::HousePublishedEvent,
+        house_purged::HousePurgedEvent,
+        house_restored::HouseRestoredEvent,
+        house_status_changed::HouseStatusChangedEvent,
         house_unpublished::HouseUnpublishedEvent,
     },
-    value_objects::house::House,
+    value_objects::{
+        dictionary::{Dictionary, HouseStatus},
+        house::{ExternalSyncAction, House},
+    },
 };
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HouseAggregate {
     pub house_id: String,
     // 小区id
@@ -20,6 +33,19 @@ pub struct HouseAggregate {
     pub unpublish_at: Option<DateTimeUtc>,
     // 是否删除
     pub deleted_at: Option<DateTimeUtc>,
+    // 房源状态，历史数据无法识别时为空
+    #[serde(default)]
+    pub status: Option<HouseStatus>,
+    // 售价、租金，用于识别价格变化
+    #[serde(default)]
+    pub sale_price: Option<f64>,
+    #[serde(default)]
+    pub rent_price: Option<f64>,
+    // 已彻底删除，不能再恢复
+    #[serde(default)]
+    pub purged: bool,
+    // 版本号，等于已应用的事件数，保存时用于乐观锁
+    pub version: i64,
 }
 
 impl HouseAggregate {
@@ -31,30 +57,125 @@ impl HouseAggregate {
             deleted_at: None,
             unpublish_at: None,
             publish_at: Some(chrono::Utc::now()),
+            status: None,
+            sale_price: None,
+            rent_price: None,
+            purged: false,
+            version: 0,
         }
     }
 
+    // 应用事件，每个事件使版本号加一
+    pub fn apply(&mut self, event: &HouseEvent, occurred_at: DateTimeUtc) {
+        match event {
+            HouseEvent::Created(house) => {
+                self.community_id = house.community_id.clone().unwrap_or_default();
+                self.house_address = house.house_address.clone().unwrap_or_default();
+                self.publish_at = Some(occurred_at);
+                self.status = house.house_status.as_deref().and_then(HouseStatus::parse);
+                self.sale_price = house.sale_price;
+                self.rent_price = house.rent_price;
+            }
+            HouseEvent::Updated(house) => {
+                if let Some(community_id) = &house.community_id {
+                    self.community_id = community_id.clone();
+                }
+                if let Some(house_address) = &house.house_address {
+                    self.house_address = house_address.clone();
+                }
+                // 早期的更新事件会带上状态
+                if let Some(status) = house.house_status.as_deref().and_then(HouseStatus::parse) {
+                    self.status = Some(status);
+                }
+                // 与读模型一致，价格为空表示清空
+                self.sale_price = house.sale_price;
+                self.rent_price = house.rent_price;
+            }
+            HouseEvent::Published(event) => {
+                self.publish_at = Some(event.published_at);
+            }
+            HouseEvent::Unpublished(event) => {
+                self.unpublish_at = Some(event.unpublish_at);
+            }
+            HouseEvent::Deleted(_) => {
+                self.deleted_at = Some(occurred_at);
+            }
+            HouseEvent::Restored(_) => {
+                self.deleted_at = None;
+            }
+            HouseEvent::Purged(_) => {
+                self.purged = true;
+            }
+            HouseEvent::Listed(event)
+            | HouseEvent::Reserved(event)
+            | HouseEvent::Sold(event)
+            | HouseEvent::Rented(event)
+            | HouseEvent::Suspended(event)
+            | HouseEvent::Expired(event) => {
+                self.status = Some(event.to);
+            }
+            HouseEvent::PriceChanged(event) => match event.field {
+                PriceField::SalePrice => self.sale_price = event.new,
+                PriceField::RentPrice => self.rent_price = event.new,
+            },
+            // 维护人、标签不在聚合里
+            HouseEvent::Patched(_) => {}
+        }
+
+        self.version += 1;
+    }
+
     // 创建房源
     pub fn create(mut house: House) -> anyhow::Result<(HouseAggregate, HouseEvent)> {
         let house_id = uuid::Uuid::new_v4().to_string();
 
-        house.validate()?;
+        house.prepare_create()?;
         house.id = Some(house_id.clone());
 
-        Ok((
-            HouseAggregate::new(
-                house_id.clone(),
-                house.community_id.clone().unwrap(),
-                house.house_address.clone().unwrap(),
-            ),
-            HouseEvent::Created(house.clone()),
-        ))
+        let mut aggregate = HouseAggregate::new(
+            house_id.clone(),
+            house.community_id.clone().unwrap(),
+            house.house_address.clone().unwrap(),
+        );
+        let event = HouseEvent::Created(house.clone());
+        aggregate.apply(&event, chrono::Utc::now());
+
+        Ok((aggregate, event))
     }
 
     // 删除房源
     pub fn delete(&mut self) -> HouseEvent {
-        self.deleted_at = Some(chrono::Utc::now());
-        HouseEvent::Deleted(HouseDeletedEvent::new(self.house_id.clone()))
+        let event = HouseEvent::Deleted(HouseDeletedEvent::new(self.house_id.clone()));
+        self.apply(&event, chrono::Utc::now());
+        event
+    }
+
+    // 从回收站恢复
+    pub fn restore(&mut self) -> anyhow::Result<HouseEvent> {
+        if !self.is_deleted() {
+            return Err(anyhow::anyhow!("房源未删除"));
+        }
+        if self.purged {
+            return Err(anyhow::anyhow!("房源已彻底删除"));
+        }
+
+        let event = HouseEvent::Restored(HouseRestoredEvent::new(self.house_id.clone()));
+        self.apply(&event, chrono::Utc::now());
+        Ok(event)
+    }
+
+    // 彻底删除回收站中的房源
+    pub fn purge(&mut self) -> anyhow::Result<HouseEvent> {
+        if !self.is_deleted() {
+            return Err(anyhow::anyhow!("房源未删除"));
+        }
+        if self.purged {
+            return Err(anyhow::anyhow!("房源已彻底删除"));
+        }
+
+        let event = HouseEvent::Purged(HousePurgedEvent::new(self.house_id.clone()));
+        self.apply(&event, chrono::Utc::now());
+        Ok(event)
     }
 
     // 更新房源
@@ -69,31 +190,72 @@ impl HouseAggregate {
             return Err(anyhow::anyhow!("house is offline"));
         }
 
+        let mut house = house.clone();
+        house.normalize();
+        house.validate_dictionary()?;
+
+        // 状态只能通过 change_status 变更，保存时带回原状态视为未修改
+        let status = house.house_status.take();
+        if status.is_some() && status.as_deref() != self.status.map(|s| s.as_str()) {
+            return Err(anyhow::anyhow!("房源状态不能直接修改，请使用状态变更"));
+        }
+
         // 处理事件
-        if house.external_sync == Some("published".to_string()) {
-            events.push(self.publish()?)
+        match house.external_sync_action() {
+            Some(ExternalSyncAction::Published) => events.push(self.publish()?),
+            Some(ExternalSyncAction::Unpublished) => {
+                events.push(self.unpublish(house.remark.clone().unwrap_or_default().as_str())?)
+            }
+            None => {}
         }
 
-        if house.external_sync == Some("unpublished".to_string()) {
-            events.push(self.unpublish(house.remark.clone().unwrap_or_default().as_str())?)
+        // 售价、租金变化单独记录，用于价格走势
+        let now = chrono::Utc::now();
+        for (field, old, new) in [
+            (PriceField::SalePrice, self.sale_price, house.sale_price),
+            (PriceField::RentPrice, self.rent_price, house.rent_price),
+        ] {
+            if old != new {
+                let event = HouseEvent::PriceChanged(HousePriceChangedEvent::new(
+                    self.house_id.clone(),
+                    field,
+                    old,
+                    new,
+                    now,
+                ));
+                self.apply(&event, now);
+                events.push(event);
+            }
         }
 
-        events.push(HouseEvent::Updated(house.clone()));
+        let event = HouseEvent::Updated(house);
+        self.apply(&event, now);
+        events.push(event);
 
         Ok(events)
     }
 
+    // 批量修改部分字段
+    pub fn patch(&mut self, patch: HousePatchedEvent) -> anyhow::Result<HouseEvent> {
+        if self.is_deleted() {
+            return Err(anyhow::anyhow!("house is deleted"));
+        }
+
+        let event = HouseEvent::Patched(patch);
+        self.apply(&event, chrono::Utc::now());
+        Ok(event)
+    }
+
     // 上架
     pub fn publish(&mut self) -> anyhow::Result<HouseEvent> {
         if self.is_deleted() {
             return Err(anyhow::anyhow!("house is deleted"));
         }
         let publish_at = chrono::Utc::now();
-        self.publish_at = Some(publish_at);
-        Ok(HouseEvent::Published(HousePublishedEvent::new(
-            self.house_id.clone(),
-            publish_at,
-        )))
+        let event =
+            HouseEvent::Published(HousePublishedEvent::new(self.house_id.clone(), publish_at));
+        self.apply(&event, publish_at);
+        Ok(event)
     }
 
     // 下架
@@ -103,13 +265,51 @@ impl HouseAggregate {
         }
 
         let unpublish_at = chrono::Utc::now();
-        self.unpublish_at = Some(unpublish_at);
-
-        Ok(HouseEvent::Unpublished(HouseUnpublishedEvent::new(
+        let event = HouseEvent::Unpublished(HouseUnpublishedEvent::new(
             self.house_id.clone(),
             unpublish_at,
             description,
-        )))
+        ));
+        self.apply(&event, unpublish_at);
+        Ok(event)
+    }
+
+    // 变更房源状态，只允许状态机中的转换
+    pub fn change_status(
+        &mut self,
+        to: HouseStatus,
+        reason: Option<String>,
+        actor_id: Option<String>,
+    ) -> anyhow::Result<HouseEvent> {
+        if self.is_deleted() {
+            return Err(anyhow::anyhow!("house is deleted"));
+        }
+
+        // 历史数据状态无法识别时允许转到任意状态进行修正
+        if let Some(from) = self.status {
+            if from == to {
+                return Err(anyhow::anyhow!("房源已经是{}状态", to.as_str()));
+            }
+            if !from.can_transition_to(to) {
+                return Err(anyhow::anyhow!(
+                    "房源状态不能从{}变更为{}",
+                    from.as_str(),
+                    to.as_str()
+                ));
+            }
+        }
+
+        let changed_at = chrono::Utc::now();
+        let event = HouseEvent::status_changed(HouseStatusChangedEvent::new(
+            self.house_id.clone(),
+            self.status,
+            to,
+            reason.filter(|reason| !reason.trim().is_empty()),
+            actor_id,
+            changed_at,
+        ))?;
+        self.apply(&event, changed_at);
+        Ok(event)
     }
 
     // 是否下架了
@@ -121,3 +321,171 @@ impl HouseAggregate {
         self.deleted_at.is_some()
     }
 }
+
+impl Aggregate for HouseAggregate {
+    type Event = HouseEvent;
+
+    fn initial(aggregate_id: &str) -> Self {
+        let mut aggregate =
+            HouseAggregate::new(aggregate_id.to_string(), String::new(), String::new());
+        aggregate.publish_at = None;
+        aggregate
+    }
+
+    fn apply(&mut self, event: &EventEnvelope<HouseEvent>) {
+        HouseAggregate::apply(self, &event.payload, event.occurred_at);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use event_bus::{Aggregate, EventEnvelope};
+    use serde_json::json;
+
+    use crate::domain::house::{
+        aggregates::house::HouseAggregate,
+        events::house::HouseEvent,
+        value_objects::{dictionary::HouseStatus, house::House},
+    };
+
+    fn house(id: &str, house_address: &str) -> House {
+        serde_json::from_value(json!({
+            "id": id,
+            "community_id": "c1",
+            "house_address": house_address,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_rebuild_from_events() {
+        let events = vec![
+            EventEnvelope::new(HouseEvent::Created(house("h1", "1栋101"))),
+            EventEnvelope::new(HouseEvent::Updated(house("h1", "1栋102"))),
+        ];
+        let mut aggregate = HouseAggregate::initial("h1");
+        for event in &events {
+            Aggregate::apply(&mut aggregate, event);
+        }
+
+        assert_eq!(aggregate.house_id, "h1");
+        assert_eq!(aggregate.house_address, "1栋102");
+        assert_eq!(aggregate.version, 2);
+
+        // 快照序列化后继续应用事件，版本号连续
+        let mut aggregate: HouseAggregate =
+            serde_json::from_value(serde_json::to_value(&aggregate).unwrap()).unwrap();
+        aggregate.delete();
+        assert!(aggregate.is_deleted());
+        assert_eq!(aggregate.version, 3);
+
+        // 恢复后可以继续修改，未删除的不能恢复
+        aggregate.restore().unwrap();
+        assert!(!aggregate.is_deleted());
+        assert_eq!(aggregate.version, 4);
+        assert!(aggregate.restore().is_err());
+        assert!(aggregate.update(&house("h1", "1栋103")).is_ok());
+
+        // 彻底删除后不能恢复，事件流里留下 Purged
+        assert!(aggregate.purge().is_err());
+        aggregate.delete();
+        assert!(matches!(aggregate.purge().unwrap(), HouseEvent::Purged(_)));
+        assert!(aggregate.restore().is_err());
+        assert!(aggregate.purge().is_err());
+    }
+
+    #[test]
+    fn test_baseline_snapshot() {
+        // 迁移为早于事件流的房源写入的基线快照
+        let mut aggregate: HouseAggregate = serde_json::from_value(json!({
+            "house_id": "h1",
+            "community_id": "c1",
+            "house_address": "1栋101",
+            "publish_at": "2025-06-20T08:00:00Z",
+            "unpublish_at": null,
+            "deleted_at": null,
+            "status": "出售中",
+            "sale_price": 120.0,
+            "rent_price": null,
+            "version": 0,
+        }))
+        .unwrap();
+        assert_eq!(aggregate.status, Some(HouseStatus::OnSale));
+
+        // 之后的事件在基线之上回放
+        Aggregate::apply(
+            &mut aggregate,
+            &EventEnvelope::new(HouseEvent::Updated(house("h1", "1栋102"))),
+        );
+        assert_eq!(aggregate.community_id, "c1");
+        assert_eq!(aggregate.house_address, "1栋102");
+        assert_eq!(aggregate.status, Some(HouseStatus::OnSale));
+        assert_eq!(aggregate.version, 1);
+    }
+
+    #[test]
+    fn test_change_status() {
+        let mut house: House = serde_json::from_value(json!({
+            "community_id": "c1",
+            "house_address": "1栋101",
+            "purpose": "住宅",
+            "transaction_type": "出售",
+            "house_status": "在售",
+        }))
+        .unwrap();
+        let (mut aggregate, _) = HouseAggregate::create(house.clone()).unwrap();
+        assert_eq!(aggregate.status, Some(HouseStatus::OnSale));
+
+        // 新建时只能是初始状态，不填默认在售
+        house.house_status = Some("已售".to_string());
+        assert!(HouseAggregate::create(house.clone()).is_err());
+        house.house_status = None;
+        let (created, _) = HouseAggregate::create(house.clone()).unwrap();
+        assert_eq!(created.status, Some(HouseStatus::OnSale));
+
+        let event = aggregate
+            .change_status(HouseStatus::Sold, Some("已签约".to_string()), None)
+            .unwrap();
+        assert!(matches!(event, HouseEvent::Sold(_)));
+        assert_eq!(aggregate.status, Some(HouseStatus::Sold));
+
+        // 已售是终态
+        assert!(aggregate
+            .change_status(HouseStatus::OnSale, None, None)
+            .is_err());
+
+        // 保存时不能顺带修改状态
+        house.id = Some(aggregate.house_id.clone());
+        house.house_status = Some("在售".to_string());
+        assert!(aggregate.update(&house).is_err());
+        house.house_status = Some("已售".to_string());
+        assert!(aggregate.update(&house).is_ok());
+    }
+
+    #[test]
+    fn test_price_changed() {
+        let mut house: House = serde_json::from_value(json!({
+            "community_id": "c1",
+            "house_address": "1栋101",
+            "purpose": "住宅",
+            "transaction_type": "出售",
+            "house_status": "在售",
+            "sale_price": 120,
+        }))
+        .unwrap();
+        let (mut aggregate, _) = HouseAggregate::create(house.clone()).unwrap();
+        house.id = Some(aggregate.house_id.clone());
+
+        // 价格不变只有 Updated
+        assert_eq!(aggregate.update(&house).unwrap().len(), 1);
+
+        house.sale_price = Some(100.0);
+        let events = aggregate.update(&house).unwrap();
+        assert_eq!(events.len(), 2);
+        let HouseEvent::PriceChanged(event) = &events[0] else {
+            panic!("expected PriceChanged");
+        };
+        assert_eq!((event.old, event.new), (Some(120.0), Some(100.0)));
+        assert_eq!(aggregate.sale_price, Some(100.0));
+    }
+}
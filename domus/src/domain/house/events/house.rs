@@ -1,19 +1,80 @@
-use serde::Serialize;
+use event_bus::Event;
+use serde::{Deserialize, Serialize};
 
 use crate::domain::house::{
     events::{
-        house_deleted::HouseDeletedEvent, house_published::HousePublishedEvent,
-        house_unpublished::HouseUnpublishedEvent,
+        house_deleted::HouseDeletedEvent, house_patched::HousePatchedEvent,
+        house_price_changed::HousePriceChangedEvent, house_published::HousePublishedEvent,
+        house_purged::HousePurgedEvent, house_restored::HouseRestoredEvent,
+        house_status_changed::HouseStatusChangedEvent, house_unpublished::HouseUnpublishedEvent,
     },
-    value_objects::house::House,
+    value_objects::{dictionary::HouseStatus, house::House},
 };
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum HouseEvent {
     Created(House),
     Updated(House),
     Published(HousePublishedEvent),
     Unpublished(HouseUnpublishedEvent),
     Deleted(HouseDeletedEvent),
+    // 从回收站恢复
+    Restored(HouseRestoredEvent),
+    // 超过回收站保留期后彻底删除，事件流保留
+    Purged(HousePurgedEvent),
+    // 状态变更，每个目标状态一个事件
+    Listed(HouseStatusChangedEvent),
+    Reserved(HouseStatusChangedEvent),
+    Sold(HouseStatusChangedEvent),
+    Rented(HouseStatusChangedEvent),
+    Suspended(HouseStatusChangedEvent),
+    Expired(HouseStatusChangedEvent),
+    // 售价或租金变化，紧跟在同一次保存的 Updated 之前
+    PriceChanged(HousePriceChangedEvent),
+    // 批量修改维护人、标签，只带修改的字段
+    Patched(HousePatchedEvent),
+}
+
+impl HouseEvent {
+    // 按目标状态生成事件，草稿只能是初始状态
+    pub fn status_changed(event: HouseStatusChangedEvent) -> anyhow::Result<Self> {
+        Ok(match event.to {
+            HouseStatus::OnSale => HouseEvent::Listed(event),
+            HouseStatus::Reserved => HouseEvent::Reserved(event),
+            HouseStatus::Sold => HouseEvent::Sold(event),
+            HouseStatus::Rented => HouseEvent::Rented(event),
+            HouseStatus::Suspended => HouseEvent::Suspended(event),
+            HouseStatus::Expired => HouseEvent::Expired(event),
+            HouseStatus::Draft => return Err(anyhow::anyhow!("房源不能变更为草稿")),
+        })
+    }
+}
+
+impl Event for HouseEvent {
+    const NAME: &'static str = "HouseEvent";
+    const AGGREGATE_TYPE: &'static str = "house";
+    // payload 内嵌整个 House，删除或修改字段时要加版本并在 upcasters 里迁移旧数据
+    const VERSION: i32 = 1;
+
+    fn aggregate_id(&self) -> String {
+        match self {
+            HouseEvent::Created(house) | HouseEvent::Updated(house) => {
+                house.id.clone().unwrap_or_default()
+            }
+            HouseEvent::Published(event) => event.house_id.clone(),
+            HouseEvent::Unpublished(event) => event.id.clone(),
+            HouseEvent::Deleted(event) => event.id.clone(),
+            HouseEvent::Restored(event) => event.id.clone(),
+            HouseEvent::Purged(event) => event.id.clone(),
+            HouseEvent::Listed(event)
+            | HouseEvent::Reserved(event)
+            | HouseEvent::Sold(event)
+            | HouseEvent::Rented(event)
+            | HouseEvent::Suspended(event)
+            | HouseEvent::Expired(event) => event.house_id.clone(),
+            HouseEvent::PriceChanged(event) => event.house_id.clone(),
+            HouseEvent::Patched(event) => event.house_id.clone(),
+        }
+    }
 }
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::house::value_objects::house::House;
+
+// 批量修改，只带本次修改的字段，不覆盖房源的其他内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HousePatchedEvent {
+    pub house_id: String,
+    // 新的维护人
+    pub maintainer_id: Option<String>,
+    // 添加的标签
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    // 移除的标签
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+}
+
+impl HousePatchedEvent {
+    pub fn new(house_id: String) -> Self {
+        Self {
+            house_id,
+            maintainer_id: None,
+            add_tags: Vec::new(),
+            remove_tags: Vec::new(),
+        }
+    }
+
+    // 应用到房源上，读模型和历史版本共用
+    pub fn apply(&self, house: &mut House) {
+        if let Some(maintainer_id) = &self.maintainer_id {
+            house.maintainer_id = Some(maintainer_id.clone());
+        }
+
+        if !self.add_tags.is_empty() || !self.remove_tags.is_empty() {
+            let tags = house.tags.get_or_insert_with(Vec::new);
+            for tag in &self.add_tags {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+            tags.retain(|tag| !self.remove_tags.contains(tag));
+        }
+    }
+}
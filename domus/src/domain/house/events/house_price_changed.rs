@@ -0,0 +1,47 @@
+use sea_orm::prelude::DateTimeUtc;
+use serde::{Deserialize, Serialize};
+
+// 变化的价格字段，与 House 上的字段名一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceField {
+    SalePrice,
+    RentPrice,
+}
+
+impl PriceField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceField::SalePrice => "sale_price",
+            PriceField::RentPrice => "rent_price",
+        }
+    }
+}
+
+// 售价或租金变化，清空价格时 new 为空
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HousePriceChangedEvent {
+    pub house_id: String,
+    pub field: PriceField,
+    pub old: Option<f64>,
+    pub new: Option<f64>,
+    pub changed_at: DateTimeUtc,
+}
+
+impl HousePriceChangedEvent {
+    pub fn new(
+        house_id: String,
+        field: PriceField,
+        old: Option<f64>,
+        new: Option<f64>,
+        changed_at: DateTimeUtc,
+    ) -> Self {
+        Self {
+            house_id,
+            field,
+            old,
+            new,
+            changed_at,
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use sea_orm::prelude::DateTimeUtc;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HousePublishedEvent {
     pub house_id: String,
     pub published_at: chrono::DateTime<chrono::Utc>,
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HousePurgedEvent {
+    pub id: String,
+}
+
+impl HousePurgedEvent {
+    pub fn new(id: String) -> Self {
+        Self { id }
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HouseRestoredEvent {
+    pub id: String,
+}
+
+impl HouseRestoredEvent {
+    pub fn new(id: String) -> Self {
+        Self { id }
+    }
+}
@@ -0,0 +1,38 @@
+use sea_orm::prelude::DateTimeUtc;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::house::value_objects::dictionary::HouseStatus;
+
+// 房源状态变更，HouseEvent 按目标状态区分为 Listed、Reserved、Sold 等事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HouseStatusChangedEvent {
+    pub house_id: String,
+    // 变更前的状态，历史数据无法识别时为空
+    pub from: Option<HouseStatus>,
+    pub to: HouseStatus,
+    // 变更原因
+    pub reason: Option<String>,
+    // 操作人
+    pub actor_id: Option<String>,
+    pub changed_at: DateTimeUtc,
+}
+
+impl HouseStatusChangedEvent {
+    pub fn new(
+        house_id: String,
+        from: Option<HouseStatus>,
+        to: HouseStatus,
+        reason: Option<String>,
+        actor_id: Option<String>,
+        changed_at: DateTimeUtc,
+    ) -> Self {
+        Self {
+            house_id,
+            from,
+            to,
+            reason,
+            actor_id,
+            changed_at,
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use sea_orm::prelude::DateTimeUtc;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HouseUnpublishedEvent {
     pub id: String,
     pub unpublish_at: DateTimeUtc,
@@ -1,4 +1,9 @@
 pub mod house;
 pub mod house_deleted;
+pub mod house_patched;
+pub mod house_price_changed;
 pub mod house_published;
+pub mod house_purged;
+pub mod house_restored;
+pub mod house_status_changed;
 pub mod house_unpublished;
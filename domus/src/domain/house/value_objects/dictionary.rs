@@ -0,0 +1,223 @@
+use serde::{Deserialize, Serialize};
+
+// 房源的枚举类字段。库里和事件里存的是规范值（中文），别名用于兼容历史数据和录入习惯
+pub trait Dictionary: Sized + Copy + 'static {
+    // 字段名称，用于提示
+    const LABEL: &'static str;
+    const ALL: &'static [Self];
+
+    fn as_str(&self) -> &'static str;
+
+    fn aliases(&self) -> &'static [&'static str];
+
+    // 规范值或别名都可以解析
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|item| item.as_str() == value || item.aliases().contains(&value))
+    }
+
+    fn items() -> Vec<DictionaryItem> {
+        Self::ALL
+            .iter()
+            .map(|item| DictionaryItem {
+                value: item.as_str(),
+                aliases: item.aliases(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DictionaryItem {
+    pub value: &'static str,
+    pub aliases: &'static [&'static str],
+}
+
+// 字典接口返回的一个字段
+#[derive(Debug, Clone, Serialize)]
+pub struct DictionaryField {
+    pub field: &'static str,
+    pub label: &'static str,
+    pub items: Vec<DictionaryItem>,
+}
+
+impl DictionaryField {
+    fn of<T: Dictionary>(field: &'static str) -> Self {
+        Self {
+            field,
+            label: T::LABEL,
+            items: T::items(),
+        }
+    }
+}
+
+// 所有字典字段，field 为 House 上的字段名
+pub fn house_dictionary() -> Vec<DictionaryField> {
+    vec![
+        DictionaryField::of::<Purpose>("purpose"),
+        DictionaryField::of::<TransactionType>("transaction_type"),
+        DictionaryField::of::<HouseStatus>("house_status"),
+        DictionaryField::of::<HouseDecoration>("house_decoration"),
+        DictionaryField::of::<HouseOrientation>("house_orientation"),
+        DictionaryField::of::<PropertyRights>("property_rights"),
+        DictionaryField::of::<ViewMethod>("view_method"),
+        DictionaryField::of::<BuildingStructure>("building_structure"),
+    ]
+}
+
+// 定义字典枚举：serde 按规范值序列化，别名也可以反序列化
+macro_rules! dictionary {
+    ($name:ident, $label:literal, { $($variant:ident => $value:literal $(| $alias:literal)*),+ $(,)? }) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        pub enum $name {
+            $(#[serde(rename = $value $(, alias = $alias)*)] $variant,)+
+        }
+
+        impl Dictionary for $name {
+            const LABEL: &'static str = $label;
+            const ALL: &'static [Self] = &[$($name::$variant),+];
+
+            fn as_str(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $value,)+
+                }
+            }
+
+            fn aliases(&self) -> &'static [&'static str] {
+                match self {
+                    $($name::$variant => &[$($alias),*],)+
+                }
+            }
+        }
+    };
+}
+
+dictionary!(Purpose, "用途", {
+    Residence => "住宅" | "普通住宅",
+    Villa => "别墅",
+    Commercial => "商住" | "商住两用",
+    Shop => "商铺" | "商业" | "门面",
+    Office => "写字楼" | "办公",
+    Factory => "厂房",
+    Warehouse => "仓库",
+    Parking => "车位" | "车库",
+    Other => "其他",
+});
+
+dictionary!(TransactionType, "交易类型", {
+    Sale => "出售" | "售",
+    Rent => "出租" | "租",
+    SaleOrRent => "租售" | "可租可售",
+});
+
+dictionary!(HouseStatus, "状态", {
+    Draft => "草稿",
+    OnSale => "在售" | "出售中",
+    Reserved => "已预定" | "已定" | "预定",
+    Sold => "已售" | "已出售" | "成交",
+    Rented => "已租" | "已出租",
+    Suspended => "暂停" | "暂缓" | "停售",
+    Expired => "已过期" | "过期" | "无效",
+});
+
+dictionary!(HouseDecoration, "装修", {
+    Rough => "毛坯" | "清水",
+    Simple => "简装修" | "简装",
+    Medium => "中装修" | "中装",
+    Fine => "精装修" | "精装",
+    Luxury => "豪华装修" | "豪装" | "豪华装",
+    Other => "其他",
+});
+
+dictionary!(HouseOrientation, "朝向", {
+    East => "东" | "朝东",
+    South => "南" | "朝南",
+    West => "西" | "朝西",
+    North => "北" | "朝北",
+    SouthEast => "东南",
+    NorthEast => "东北",
+    SouthWest => "西南",
+    NorthWest => "西北",
+    SouthNorth => "南北" | "南北通透",
+    EastWest => "东西",
+});
+
+dictionary!(PropertyRights, "产权性质", {
+    Commodity => "商品房",
+    Reformed => "房改房",
+    Affordable => "经济适用房" | "经适房",
+    Resettlement => "安置房" | "回迁房" | "拆迁安置房",
+    Collective => "集资房",
+    SmallProperty => "小产权房" | "小产权",
+    Other => "其他",
+});
+
+dictionary!(ViewMethod, "看房方式", {
+    Anytime => "随时看房" | "随时",
+    Appointment => "预约看房" | "预约" | "提前预约",
+    Key => "有钥匙" | "钥匙" | "钥匙房",
+    Inconvenient => "不便看房",
+});
+
+dictionary!(BuildingStructure, "建筑结构", {
+    ReinforcedConcrete => "钢混" | "钢混结构" | "钢筋混凝土",
+    BrickConcrete => "砖混" | "砖混结构",
+    Frame => "框架" | "框架结构",
+    Steel => "钢结构",
+    BrickWood => "砖木" | "砖木结构",
+    Other => "其他",
+});
+
+// 把别名改写为规范值，空字符串视为未填写，无法识别的值保持原样交给校验
+pub fn normalize<T: Dictionary>(value: &mut Option<String>) {
+    if value.as_deref().is_some_and(|v| v.trim().is_empty()) {
+        *value = None;
+    }
+
+    if let Some(item) = value.as_deref().and_then(T::parse) {
+        *value = Some(item.as_str().to_string());
+    }
+}
+
+// 填写了的值必须是规范值
+pub fn check<T: Dictionary>(value: &Option<String>) -> anyhow::Result<()> {
+    match value {
+        Some(v) if !T::ALL.iter().any(|item| item.as_str() == v) => {
+            let allowed: Vec<&str> = T::ALL.iter().map(|item| item.as_str()).collect();
+            Err(anyhow::anyhow!(
+                "{}「{}」无效，可选值：{}",
+                T::LABEL,
+                v,
+                allowed.join("、")
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, normalize, Dictionary, HouseDecoration};
+
+    #[test]
+    fn test_alias() {
+        assert_eq!(HouseDecoration::parse("精装"), Some(HouseDecoration::Fine));
+        assert_eq!(
+            serde_json::from_str::<HouseDecoration>("\"精装\"").unwrap(),
+            HouseDecoration::Fine
+        );
+        assert_eq!(
+            serde_json::to_string(&HouseDecoration::Fine).unwrap(),
+            "\"精装修\""
+        );
+
+        let mut value = Some(" 精装 ".to_string());
+        normalize::<HouseDecoration>(&mut value);
+        assert_eq!(value.as_deref(), Some("精装修"));
+        assert!(check::<HouseDecoration>(&value).is_ok());
+        assert!(check::<HouseDecoration>(&Some("精装x".to_string())).is_err());
+    }
+}
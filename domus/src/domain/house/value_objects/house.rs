@@ -3,7 +3,12 @@ use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
 use crate::domain::{
-    community::value_objects::commuity::Community, owner::value_objects::owner::HouseOwner,
+    community::value_objects::commuity::Community,
+    house::value_objects::dictionary::{
+        check, normalize, BuildingStructure, Dictionary, HouseDecoration, HouseOrientation,
+        HouseStatus, PropertyRights, Purpose, TransactionType, ViewMethod,
+    },
+    owner::value_objects::owner::HouseOwner,
 };
 
 #[skip_serializing_none]
@@ -16,6 +21,8 @@ pub struct HouseData {
     pub community: Option<Community>,
     // 所有者
     pub owner: Option<HouseOwner>,
+    // 编辑时读到的版本号，保存时与库里不一致说明有人改过
+    pub version: Option<i64>,
 }
 
 impl HouseData {
@@ -31,6 +38,8 @@ impl HouseData {
 pub struct House {
     pub id: Option<String>,
     pub created_by: Option<String>,
+    // 维护人，负责跟进房源的经纪人，新增时默认为录入者
+    pub maintainer_id: Option<String>,
     pub community_id: Option<String>,
     pub owner_id: Option<String>,
     // 房源标题
@@ -154,8 +163,72 @@ impl House {
         if self.house_address.is_none() {
             return Err(anyhow::anyhow!("房源地址不能为空"));
         }
+
+        self.validate_dictionary()
+    }
+
+    // 字典字段只能是规范值，先调用 normalize 处理别名
+    pub fn validate_dictionary(&self) -> anyhow::Result<()> {
+        check::<Purpose>(&self.purpose)?;
+        check::<TransactionType>(&self.transaction_type)?;
+        check::<HouseStatus>(&self.house_status)?;
+        check::<HouseDecoration>(&self.house_decoration)?;
+        check::<HouseOrientation>(&self.house_orientation)?;
+        check::<PropertyRights>(&self.property_rights)?;
+        check::<ViewMethod>(&self.view_method)?;
+        check::<BuildingStructure>(&self.building_structure)?;
         Ok(())
     }
+
+    // 新建房源前的规范和校验，新建和导入共用：状态不填默认在售，只能是草稿或在售
+    pub fn prepare_create(&mut self) -> anyhow::Result<()> {
+        self.normalize();
+        let status = self
+            .house_status
+            .get_or_insert_with(|| HouseStatus::OnSale.as_str().to_string());
+        if HouseStatus::parse(status).is_some_and(|status| !status.is_initial()) {
+            return Err(anyhow::anyhow!(
+                "新建房源的状态只能是草稿或在售，其他状态请创建后通过状态变更修改"
+            ));
+        }
+        self.validate()
+    }
+
+    // 单价 = 售价 / 建筑面积，缺少任一项或面积为 0 时为空
+    pub fn unit_price(&self) -> Option<f64> {
+        match (self.sale_price, self.building_area) {
+            (Some(price), Some(area)) if area > 0.0 => Some(price / area as f64),
+            _ => None,
+        }
+    }
+
+    // 把字典字段的别名改写为规范值
+    pub fn normalize(&mut self) {
+        normalize::<Purpose>(&mut self.purpose);
+        normalize::<TransactionType>(&mut self.transaction_type);
+        normalize::<HouseStatus>(&mut self.house_status);
+        normalize::<HouseDecoration>(&mut self.house_decoration);
+        normalize::<HouseOrientation>(&mut self.house_orientation);
+        normalize::<PropertyRights>(&mut self.property_rights);
+        normalize::<ViewMethod>(&mut self.view_method);
+        normalize::<BuildingStructure>(&mut self.building_structure);
+    }
+
+    // 保存时一并上架、下架
+    pub fn external_sync_action(&self) -> Option<ExternalSyncAction> {
+        match self.external_sync.as_deref()? {
+            "published" => Some(ExternalSyncAction::Published),
+            "unpublished" => Some(ExternalSyncAction::Unpublished),
+            _ => None,
+        }
+    }
+}
+
+// 保存房源时 external_sync 传 published / unpublished 表示上架、下架，其他值只作为字段保存
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalSyncAction {
+    Published,
+    Unpublished,
 }
 
 #[skip_serializing_none]
@@ -210,7 +283,7 @@ pub struct FileInfo {
 mod tests {
     use serde_json::json;
 
-    use crate::domain::house::value_objects::house::HouseData;
+    use crate::domain::house::value_objects::house::{House, HouseData};
 
     #[test]
     fn test_validate() {
@@ -271,5 +344,32 @@ mod tests {
         let data: HouseData = serde_json::from_value(json).unwrap();
 
         println!("data: {:#?}", data);
+
+        let mut house = data.house.unwrap();
+        house.house_decoration = Some("精装".to_string());
+        house.normalize();
+        assert_eq!(house.house_decoration.as_deref(), Some("精装修"));
+        assert!(house.validate().is_ok());
+
+        house.house_orientation = Some("朝天".to_string());
+        assert!(house.validate().is_err());
+    }
+
+    #[test]
+    fn test_prepare_create() {
+        let mut house: House = serde_json::from_value(json!({
+            "purpose": "住宅",
+            "transaction_type": "出售",
+            "house_address": "11栋104室",
+        }))
+        .unwrap();
+        house.prepare_create().unwrap();
+        assert_eq!(house.house_status.as_deref(), Some("在售"));
+
+        house.house_status = Some("已售".to_string());
+        assert!(house.prepare_create().is_err());
+
+        house.house_status = Some("草稿".to_string());
+        assert!(house.prepare_create().is_ok());
     }
 }
@@ -0,0 +1,284 @@
+// 相似度达到该值时视为疑似重复房源
+pub const DUPLICATE_THRESHOLD: f64 = 0.85;
+
+// 楼栋、单元、房号的标识
+const BUILDING_LABELS: &[&str] = &["号楼", "栋", "幢", "座"];
+const UNIT_LABELS: &[&str] = &["单元"];
+const ROOM_LABELS: &[&str] = &["室", "号", "户"];
+// 楼层与房号重复，不参与比较
+const FLOOR_LABELS: &[&str] = &["层", "楼"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Part {
+    Building,
+    Unit,
+    Room,
+    Floor,
+}
+
+// 房源地址（楼号/单元号/门牌号），把 "11栋104"、"11-104"、"11幢1单元104室" 解析为统一的结构
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HouseAddress {
+    pub building: Option<String>,
+    pub unit: Option<String>,
+    pub room: Option<String>,
+    // 无法识别的部分，如 "东区"
+    pub rest: String,
+}
+
+impl HouseAddress {
+    pub fn parse(address: &str) -> Self {
+        let text = chinese_numerals(&half_width(address));
+        let chars: Vec<char> = text.chars().collect();
+
+        let mut address = HouseAddress::default();
+        let mut unlabeled = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if c.is_ascii_alphanumeric() {
+                let start = i;
+                while i < chars.len() && chars[i].is_ascii_alphanumeric() {
+                    i += 1;
+                }
+                let token = trim_zeros(&chars[start..i].iter().collect::<String>());
+
+                let tail: String = chars[i..].iter().take(2).collect();
+                let label = [
+                    (BUILDING_LABELS, Part::Building),
+                    (UNIT_LABELS, Part::Unit),
+                    (ROOM_LABELS, Part::Room),
+                    (FLOOR_LABELS, Part::Floor),
+                ]
+                .into_iter()
+                .find_map(|(labels, part)| {
+                    labels
+                        .iter()
+                        .find(|label| tail.starts_with(*label))
+                        .map(|label| (part, label.chars().count()))
+                });
+
+                match label {
+                    Some((part, len)) => {
+                        i += len;
+                        let slot = match part {
+                            Part::Building => &mut address.building,
+                            Part::Unit => &mut address.unit,
+                            Part::Room => &mut address.room,
+                            Part::Floor => continue,
+                        };
+                        slot.get_or_insert(token);
+                    }
+                    None => unlabeled.push(token),
+                }
+            } else {
+                if !c.is_whitespace() && c != '-' {
+                    address.rest.push(c);
+                }
+                i += 1;
+            }
+        }
+
+        address.fill(unlabeled);
+        address
+    }
+
+    // 没有标识的数字按位置补齐：一个为房号，两个为楼栋、房号，三个为楼栋、单元、房号
+    fn fill(&mut self, unlabeled: Vec<String>) {
+        let mut slots: Vec<Part> = Vec::new();
+        if self.building.is_none() {
+            slots.push(Part::Building);
+        }
+        if self.unit.is_none() && unlabeled.len() >= 3 {
+            slots.push(Part::Unit);
+        }
+        if self.room.is_none() {
+            slots.push(Part::Room);
+        }
+
+        // 数字比空位少时优先填房号
+        let skip = slots.len().saturating_sub(unlabeled.len());
+        let slots = slots.split_off(skip);
+
+        let mut unlabeled = unlabeled.into_iter();
+        for (part, token) in slots.into_iter().zip(unlabeled.by_ref()) {
+            match part {
+                Part::Building => self.building = Some(token),
+                Part::Unit => self.unit = Some(token),
+                _ => self.room = Some(token),
+            }
+        }
+        for token in unlabeled {
+            self.rest.push_str(&token);
+        }
+    }
+
+    // 统一后的地址，如 "11栋1单元104室"
+    pub fn normalized(&self) -> String {
+        let mut text = self.rest.clone();
+        if let Some(building) = &self.building {
+            text.push_str(&format!("{}栋", building));
+        }
+        if let Some(unit) = &self.unit {
+            text.push_str(&format!("{}单元", unit));
+        }
+        if let Some(room) = &self.room {
+            text.push_str(&format!("{}室", room));
+        }
+        text
+    }
+
+    // 相似度 0~1，同一小区内达到 DUPLICATE_THRESHOLD 视为疑似重复
+    pub fn similarity(&self, other: &HouseAddress) -> f64 {
+        let (a, b) = (self.normalized(), other.normalized());
+        if a == b {
+            return 1.0;
+        }
+
+        match (&self.building, &self.room, &other.building, &other.room) {
+            (Some(building), Some(room), Some(other_building), Some(other_room)) => {
+                if building != other_building || room != other_room {
+                    // 楼栋或房号不同基本不是同一套，只保留笔误的可能
+                    return edit_similarity(&a, &b).min(0.6);
+                }
+                if !self.rest.is_empty() && !other.rest.is_empty() && self.rest != other.rest {
+                    return 0.5;
+                }
+                match (&self.unit, &other.unit) {
+                    // 不同单元的同一房号是不同的房子
+                    (Some(unit), Some(other_unit)) if unit != other_unit => 0.3,
+                    // 一边没写单元
+                    _ => 0.9,
+                }
+            }
+            _ => edit_similarity(&a, &b),
+        }
+    }
+}
+
+// 全角转半角，分隔符统一为 -，字母转小写
+fn half_width(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+            '\u{3000}' => ' ',
+            _ => c,
+        })
+        .map(|c| match c {
+            '#' | '/' | '\\' | '_' | '.' | '、' | '—' | '–' | '·' => '-',
+            _ => c.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+// 中文数字转为阿拉伯数字，"十一栋" -> "11栋"，"一零四" -> "104"
+fn chinese_numerals(text: &str) -> String {
+    let digit = |c: char| "零一二三四五六七八九".chars().position(|d| d == c);
+    let is_numeral = |c: char| digit(c).is_some() || c == '十' || c == '两';
+
+    let mut result = String::new();
+    let mut run = String::new();
+    for c in text.chars().chain(std::iter::once('\0')) {
+        if is_numeral(c) {
+            run.push(if c == '两' { '二' } else { c });
+            continue;
+        }
+
+        if !run.is_empty() {
+            let digits: Vec<Option<usize>> = run.chars().map(digit).collect();
+            let value = match run.find('十') {
+                // 十、十一、二十、二十三
+                Some(pos) => {
+                    let tens = if pos == 0 { 1 } else { digits[0].unwrap_or(1) };
+                    let ones = digits.last().copied().flatten().unwrap_or(0);
+                    (tens * 10 + ones).to_string()
+                }
+                None => digits
+                    .iter()
+                    .map(|d| char::from(b'0' + d.unwrap_or(0) as u8))
+                    .collect(),
+            };
+            result.push_str(&value);
+            run.clear();
+        }
+        if c != '\0' {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+fn trim_zeros(token: &str) -> String {
+    match token.trim_start_matches('0') {
+        "" if !token.is_empty() => "0".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+// 1 - 编辑距离 / 较长的长度
+fn edit_similarity(a: &str, b: &str) -> f64 {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let len = a.len().max(b.len());
+    if len == 0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    1.0 - previous[b.len()] as f64 / len as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HouseAddress, DUPLICATE_THRESHOLD};
+
+    #[test]
+    fn test_parse() {
+        for address in [
+            "11栋104",
+            "11-104",
+            "11#104",
+            "十一幢104室",
+            "１１栋　１０４",
+        ] {
+            assert_eq!(
+                HouseAddress::parse(address).normalized(),
+                "11栋104室",
+                "{}",
+                address
+            );
+        }
+        assert_eq!(
+            HouseAddress::parse("11号楼一单元10层1004").normalized(),
+            "11栋1单元1004室"
+        );
+        assert_eq!(
+            HouseAddress::parse("11-1-104").normalized(),
+            "11栋1单元104室"
+        );
+        assert_eq!(HouseAddress::parse("A座0803").normalized(), "a栋803室");
+    }
+
+    #[test]
+    fn test_similarity() {
+        let address = HouseAddress::parse("11栋1单元104室");
+        let is_duplicate =
+            |other: &str| address.similarity(&HouseAddress::parse(other)) >= DUPLICATE_THRESHOLD;
+        assert!(is_duplicate("11-104"));
+        assert!(is_duplicate("11幢1单元104"));
+        assert!(!is_duplicate("11栋2单元104"));
+        assert!(!is_duplicate("12栋104"));
+        assert!(!is_duplicate("11栋1单元105"));
+    }
+}
@@ -0,0 +1,49 @@
+use crate::domain::house::value_objects::dictionary::HouseStatus;
+
+// 房源状态机，草稿只能作为初始状态，已售为终态
+impl HouseStatus {
+    // 当前状态允许转到的状态
+    pub fn transitions(self) -> &'static [HouseStatus] {
+        use HouseStatus::*;
+
+        match self {
+            Draft => &[OnSale, Expired],
+            OnSale => &[Reserved, Sold, Rented, Suspended, Expired],
+            // 预定取消后重新在售
+            Reserved => &[OnSale, Sold, Rented, Suspended],
+            Suspended => &[OnSale, Expired],
+            // 租约到期后重新挂牌
+            Rented => &[OnSale, Expired],
+            Expired => &[OnSale],
+            Sold => &[],
+        }
+    }
+
+    pub fn can_transition_to(self, to: HouseStatus) -> bool {
+        self.transitions().contains(&to)
+    }
+
+    // 新建房源只能是草稿或在售，其他状态通过状态变更进入
+    pub fn is_initial(self) -> bool {
+        matches!(self, HouseStatus::Draft | HouseStatus::OnSale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::house::value_objects::dictionary::{Dictionary, HouseStatus};
+
+    #[test]
+    fn test_transitions() {
+        assert!(HouseStatus::OnSale.can_transition_to(HouseStatus::Sold));
+        assert!(HouseStatus::Reserved.can_transition_to(HouseStatus::OnSale));
+        assert!(!HouseStatus::Sold.can_transition_to(HouseStatus::OnSale));
+
+        // 任何状态都不能回到草稿
+        assert!(HouseStatus::ALL
+            .iter()
+            .all(|status| !status.can_transition_to(HouseStatus::Draft)));
+        assert!(HouseStatus::Draft.is_initial());
+        assert!(!HouseStatus::Sold.is_initial());
+    }
+}
@@ -1 +1,4 @@
+pub mod dictionary;
 pub mod house;
+pub mod house_address;
+pub mod house_status;
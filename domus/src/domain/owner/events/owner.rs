@@ -1,12 +1,29 @@
-use serde::Serialize;
+use event_bus::Event;
+use serde::{Deserialize, Serialize};
 
 use crate::domain::owner::{
     events::owner_deleted::OwnerDeletedEvent, value_objects::owner::HouseOwner,
 };
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum OwnerEvent {
     Created(HouseOwner),
     Updated(HouseOwner),
     Deleted(OwnerDeletedEvent),
 }
+
+impl Event for OwnerEvent {
+    const NAME: &'static str = "OwnerEvent";
+    const AGGREGATE_TYPE: &'static str = "owner";
+    // payload 内嵌整个 HouseOwner，删除或修改字段时要加版本并在 upcasters 里迁移旧数据
+    const VERSION: i32 = 1;
+
+    fn aggregate_id(&self) -> String {
+        match self {
+            OwnerEvent::Created(owner) | OwnerEvent::Updated(owner) => {
+                owner.id.clone().unwrap_or_default()
+            }
+            OwnerEvent::Deleted(event) => event.owner_id.clone(),
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use sea_orm::prelude::DateTimeUtc;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OwnerDeletedEvent {
     pub owner_id: String,
     pub deleted_at: DateTimeUtc,
@@ -1,5 +1,7 @@
+use event_bus::OutboxEvent;
 use sea_orm::{
     ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, DbConn, EntityTrait, QueryFilter,
+    TransactionTrait,
 };
 use std::sync::Arc;
 
@@ -21,7 +23,11 @@ impl MySqlCommunityAggregateRepository {
 #[async_trait::async_trait]
 impl CommunityRepositoryAggregate for MySqlCommunityAggregateRepository {
     // 创建小区
-    async fn create(&self, community: CommunityAggregate) -> anyhow::Result<()> {
+    async fn create(
+        &self,
+        community: CommunityAggregate,
+        events: &[OutboxEvent],
+    ) -> anyhow::Result<()> {
         let model = entitiy::community_aggregate::ActiveModel {
             id: Set(community.community_id),
             name: Set(community.name),
@@ -29,12 +35,19 @@ impl CommunityRepositoryAggregate for MySqlCommunityAggregateRepository {
             ..Default::default()
         };
 
-        model.insert(self.pool.as_ref()).await?;
+        let txn = self.pool.begin().await?;
+        model.insert(&txn).await?;
+        OutboxEvent::append(&txn, events).await?;
+        txn.commit().await?;
         Ok(())
     }
 
     // 更新小区
-    async fn save(&self, community: &CommunityAggregate) -> anyhow::Result<()> {
+    async fn save(
+        &self,
+        community: &CommunityAggregate,
+        events: &[OutboxEvent],
+    ) -> anyhow::Result<()> {
         let model = entitiy::community_aggregate::ActiveModel {
             id: Set(community.community_id.clone()),
             name: Set(community.name.clone()),
@@ -43,7 +56,10 @@ impl CommunityRepositoryAggregate for MySqlCommunityAggregateRepository {
             ..Default::default()
         };
 
-        model.update(self.pool.as_ref()).await?;
+        let txn = self.pool.begin().await?;
+        model.update(&txn).await?;
+        OutboxEvent::append(&txn, events).await?;
+        txn.commit().await?;
         Ok(())
     }
 
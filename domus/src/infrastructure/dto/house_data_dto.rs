@@ -1,10 +1,13 @@
+use std::collections::HashMap;
+
 use sea_orm::FromQueryResult;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
 use crate::{
+    application::queries::house_filter::NearFilter,
     domain::house::value_objects::house::{ApartmentType, FloorRange, House, Stairs},
-    infrastructure::entitiy::{self},
+    infrastructure::{entitiy, search::house_index::SearchHit},
 };
 
 #[skip_serializing_none]
@@ -13,20 +16,49 @@ pub struct HouseDataDto {
     // 房源
     #[serde(flatten)]
     pub house: House,
+    // 单价，售价 / 建筑面积
+    pub unit_price: Option<f64>,
     pub community: Option<entitiy::community_query::Model>,
     // 所有者
     pub owner: Option<entitiy::owner_query::Model>,
+    // 房源版本号，编辑后保存时带回
+    pub version: Option<i64>,
+    // 关键词搜索的相关度
+    pub score: Option<f32>,
+    // 关键词搜索命中的片段，key 为字段名
+    pub highlight: Option<HashMap<String, String>>,
+    // 到周边搜索中心点的距离，米
+    pub distance_m: Option<f64>,
 }
 
 impl HouseDataDto {
+    // 回填关键词搜索的相关度和高亮
+    pub fn with_search_hit(mut self, hit: Option<&SearchHit>) -> Self {
+        if let Some(hit) = hit {
+            self.score = Some(hit.score);
+            self.highlight = Some(hit.highlight.clone());
+        }
+        self
+    }
+
+    // 回填到周边搜索中心点的距离
+    pub fn with_distance(mut self, near: Option<&NearFilter>) -> Self {
+        if let (Some(near), Some(community)) = (near, &self.community) {
+            self.distance_m = Some(near.distance_to(community.lat, community.lng).round());
+        }
+        self
+    }
+
     pub fn new(
         house: entitiy::house_query::Model,
         community: Option<entitiy::community_query::Model>,
         owner: Option<entitiy::owner_query::Model>,
     ) -> Self {
+        let unit_price = house.unit_price;
         let house = House {
             id: Some(house.id),
             created_by: house.created_by,
+            maintainer_id: house.maintainer_id,
             community_id: Some(house.community_id),
             owner_id: house.owner_id,
             // 房源标题
@@ -161,8 +193,13 @@ impl HouseDataDto {
 
         Self {
             house,
+            unit_price,
             community,
             owner,
+            version: None,
+            score: None,
+            highlight: None,
+            distance_m: None,
         }
     }
 }
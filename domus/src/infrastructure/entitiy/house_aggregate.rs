@@ -2,7 +2,7 @@
 
 use sea_orm::entity::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
 #[sea_orm(table_name = "house_aggregate")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
@@ -12,6 +12,12 @@ pub struct Model {
     pub publish_at: Option<DateTimeUtc>,
     pub unpublish_at: Option<DateTimeUtc>,
     pub deleted_at: Option<DateTimeUtc>,
+    pub house_status: Option<String>,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub sale_price: Option<f64>,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub rent_price: Option<f64>,
+    pub version: i64,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
@@ -36,6 +36,8 @@ pub struct Model {
     #[sea_orm(column_type = "Double", nullable)]
     pub sale_price: Option<f64>,
     #[sea_orm(column_type = "Double", nullable)]
+    pub unit_price: Option<f64>,
+    #[sea_orm(column_type = "Double", nullable)]
     pub rent_price: Option<f64>,
     #[sea_orm(column_type = "Double", nullable)]
     pub rent_low_price: Option<f64>,
@@ -83,6 +85,7 @@ pub struct Model {
     pub remark: Option<String>,
     pub images: Option<Json>,
     pub created_by: Option<String>,
+    pub maintainer_id: Option<String>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
     pub deleted_at: Option<DateTimeUtc>,
@@ -7,7 +7,9 @@ pub mod community_query;
 pub mod favorite_categories;
 pub mod house_aggregate;
 pub mod house_comments;
+pub mod house_export_log;
 pub mod house_operation_log;
+pub mod house_price_history;
 pub mod house_query;
 pub mod owner;
 pub mod owner_query;
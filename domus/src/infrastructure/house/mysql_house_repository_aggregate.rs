@@ -1,58 +1,124 @@
 use std::sync::Arc;
 
+use event_bus::{OutboxEvent, SnapshotStore};
 use sea_orm::{
     ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, DbConn, EntityTrait,
-    PaginatorTrait, QueryFilter,
+    PaginatorTrait, QueryFilter, TransactionTrait,
 };
 
 use crate::{
-    application::repositories::house_repository_aggregate::HouseRepositoryAggregate,
-    domain::house::aggregates::house::HouseAggregate, infrastructure::entitiy,
+    application::repositories::house_repository_aggregate::{
+        HouseRepositoryAggregate, HouseVersionConflict,
+    },
+    domain::house::{
+        aggregates::house::HouseAggregate,
+        value_objects::dictionary::{Dictionary, HouseStatus},
+    },
+    infrastructure::entitiy,
 };
 
 pub struct MysqlHouseRepositoryAggregate {
     pub pool: Arc<DbConn>,
+    // 为 true 时 find_by_id 从事件流重建房源，否则读 house_aggregate 表
+    pub event_sourced: bool,
+    pub snapshots: SnapshotStore,
 }
 
 impl MysqlHouseRepositoryAggregate {
     pub fn new(pool: Arc<DbConn>) -> Self {
-        Self { pool }
+        Self {
+            snapshots: SnapshotStore::new(pool.clone()),
+            pool,
+            event_sourced: false,
+        }
+    }
+
+    pub fn event_sourced(mut self, event_sourced: bool) -> Self {
+        self.event_sourced = event_sourced;
+        self
+    }
+
+    // 每回放多少个事件写一次快照
+    pub fn snapshot_interval(mut self, interval: i64) -> Self {
+        self.snapshots = SnapshotStore::new(self.pool.clone()).interval(interval);
+        self
     }
 }
 
 #[async_trait::async_trait]
 impl HouseRepositoryAggregate for MysqlHouseRepositoryAggregate {
     // 创建小区
-    async fn create(&self, aggregate: HouseAggregate) -> anyhow::Result<()> {
+    async fn create(
+        &self,
+        aggregate: HouseAggregate,
+        events: &[OutboxEvent],
+    ) -> anyhow::Result<()> {
         let model = entitiy::house_aggregate::ActiveModel {
             house_id: Set(aggregate.house_id),
             community_id: Set(aggregate.community_id),
             house_address: Set(aggregate.house_address),
             publish_at: Set(aggregate.publish_at),
+            house_status: Set(aggregate.status.map(|status| status.as_str().to_string())),
+            sale_price: Set(aggregate.sale_price),
+            rent_price: Set(aggregate.rent_price),
+            version: Set(aggregate.version),
             ..Default::default()
         };
 
-        model.insert(self.pool.as_ref()).await?;
+        let txn = self.pool.begin().await?;
+        model.insert(&txn).await?;
+        OutboxEvent::append(&txn, events).await?;
+        txn.commit().await?;
         Ok(())
     }
 
     // 更新小区
-    async fn save(&self, aggregate: &HouseAggregate) -> anyhow::Result<()> {
+    async fn save(
+        &self,
+        aggregate: &HouseAggregate,
+        expected_version: i64,
+        events: &[OutboxEvent],
+    ) -> anyhow::Result<()> {
         let model = entitiy::house_aggregate::ActiveModel {
-            house_id: Set(aggregate.house_id.clone()),
             community_id: Set(aggregate.community_id.clone()),
             house_address: Set(aggregate.house_address.clone()),
             publish_at: Set(aggregate.publish_at.clone()),
             unpublish_at: Set(aggregate.unpublish_at.clone()),
             deleted_at: Set(aggregate.deleted_at.clone()),
+            house_status: Set(aggregate.status.map(|status| status.as_str().to_string())),
+            sale_price: Set(aggregate.sale_price),
+            rent_price: Set(aggregate.rent_price),
+            version: Set(aggregate.version),
             ..Default::default()
         };
 
-        model.update(self.pool.as_ref()).await?;
+        let txn = self.pool.begin().await?;
+        // 只有版本号未变时才更新，否则说明期间有人保存过
+        let result = entitiy::house_aggregate::Entity::update_many()
+            .set(model)
+            .filter(entitiy::house_aggregate::Column::HouseId.eq(aggregate.house_id.as_str()))
+            .filter(entitiy::house_aggregate::Column::Version.eq(expected_version))
+            .exec(&txn)
+            .await?;
+
+        if result.rows_affected == 0 {
+            return Err(HouseVersionConflict {
+                house_id: aggregate.house_id.clone(),
+                expected_version,
+            }
+            .into());
+        }
+
+        OutboxEvent::append(&txn, events).await?;
+        txn.commit().await?;
         Ok(())
     }
     // 获取小区
     async fn find_by_id(&self, id: &str) -> anyhow::Result<HouseAggregate> {
+        if self.event_sourced {
+            return self.load_from_events(id).await;
+        }
+
         entitiy::house_aggregate::Entity::find()
             .filter(
                 Condition::all()
@@ -61,14 +127,31 @@ impl HouseRepositoryAggregate for MysqlHouseRepositoryAggregate {
             )
             .one(self.pool.as_ref())
             .await?
-            .map(|model| HouseAggregate {
-                house_id: model.house_id,
-                community_id: model.community_id,
-                house_address: model.house_address,
-                publish_at: model.publish_at,
-                unpublish_at: model.unpublish_at,
-                deleted_at: model.deleted_at,
-            })
+            .map(HouseAggregate::from)
+            .ok_or_else(|| anyhow::anyhow!("房源不存在"))
+    }
+
+    // 获取回收站中的房源
+    async fn find_deleted(&self, id: &str) -> anyhow::Result<HouseAggregate> {
+        let aggregate = if self.event_sourced {
+            self.load_snapshot(id).await?
+        } else {
+            entitiy::house_aggregate::Entity::find_by_id(id)
+                .one(self.pool.as_ref())
+                .await?
+                .map(HouseAggregate::from)
+        };
+
+        aggregate
+            .filter(|aggregate| aggregate.is_deleted() && !aggregate.purged)
+            .ok_or_else(|| anyhow::anyhow!("回收站中没有该房源"))
+    }
+
+    // 从最新快照和之后的事件重建房源
+    async fn load_from_events(&self, id: &str) -> anyhow::Result<HouseAggregate> {
+        self.load_snapshot(id)
+            .await?
+            .filter(|aggregate| !aggregate.is_deleted())
             .ok_or_else(|| anyhow::anyhow!("房源不存在"))
     }
 
@@ -96,3 +179,44 @@ impl HouseRepositoryAggregate for MysqlHouseRepositoryAggregate {
         Ok(count > 0)
     }
 }
+
+impl MysqlHouseRepositoryAggregate {
+    // 早于事件流的房源没有事件，用 house_aggregate 的数据写入基线快照，之后的事件在它之上回放
+    async fn load_snapshot(&self, id: &str) -> anyhow::Result<Option<HouseAggregate>> {
+        if let Some(aggregate) = self.snapshots.load::<HouseAggregate>(id).await? {
+            return Ok(Some(aggregate));
+        }
+
+        let Some(model) = entitiy::house_aggregate::Entity::find_by_id(id)
+            .one(self.pool.as_ref())
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let aggregate = HouseAggregate::from(model);
+        if aggregate.version == 0 {
+            self.snapshots.seed(id, &aggregate).await?;
+        }
+
+        Ok(Some(aggregate))
+    }
+}
+
+impl From<entitiy::house_aggregate::Model> for HouseAggregate {
+    fn from(model: entitiy::house_aggregate::Model) -> Self {
+        HouseAggregate {
+            house_id: model.house_id,
+            community_id: model.community_id,
+            house_address: model.house_address,
+            publish_at: model.publish_at,
+            unpublish_at: model.unpublish_at,
+            deleted_at: model.deleted_at,
+            status: model.house_status.as_deref().and_then(HouseStatus::parse),
+            sale_price: model.sale_price,
+            rent_price: model.rent_price,
+            purged: false,
+            version: model.version,
+        }
+    }
+}
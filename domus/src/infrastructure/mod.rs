@@ -4,3 +4,6 @@ pub mod entitiy;
 pub mod house;
 pub mod mysql_pool;
 pub mod owner;
+pub mod search;
+pub mod spreadsheet;
+pub mod user_system;
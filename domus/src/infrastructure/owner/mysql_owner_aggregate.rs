@@ -1,8 +1,9 @@
 use std::sync::Arc;
 
+use event_bus::OutboxEvent;
 use sea_orm::{
     ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, DbConn, EntityTrait,
-    PaginatorTrait, QueryFilter,
+    PaginatorTrait, QueryFilter, TransactionTrait,
 };
 
 use crate::{
@@ -23,7 +24,11 @@ impl MySqlOwnerAggregateRepository {
 #[async_trait::async_trait]
 impl OwnerRepositoryAggregate for MySqlOwnerAggregateRepository {
     // 创建owner
-    async fn create(&self, aggregate: OwnerAggregate) -> anyhow::Result<()> {
+    async fn create(
+        &self,
+        aggregate: OwnerAggregate,
+        events: &[OutboxEvent],
+    ) -> anyhow::Result<()> {
         let model = entitiy::owner::ActiveModel {
             owner_id: Set(aggregate.owner_id),
             name: Set(aggregate.name),
@@ -32,11 +37,14 @@ impl OwnerRepositoryAggregate for MySqlOwnerAggregateRepository {
             ..Default::default()
         };
 
-        model.insert(self.pool.as_ref()).await?;
+        let txn = self.pool.begin().await?;
+        model.insert(&txn).await?;
+        OutboxEvent::append(&txn, events).await?;
+        txn.commit().await?;
         Ok(())
     }
     // 更新owner
-    async fn save(&self, aggregate: &OwnerAggregate) -> anyhow::Result<()> {
+    async fn save(&self, aggregate: &OwnerAggregate, events: &[OutboxEvent]) -> anyhow::Result<()> {
         let model = entitiy::owner::ActiveModel {
             owner_id: Set(aggregate.owner_id.clone()),
             name: Set(aggregate.name.clone()),
@@ -46,7 +54,10 @@ impl OwnerRepositoryAggregate for MySqlOwnerAggregateRepository {
             ..Default::default()
         };
 
-        model.update(self.pool.as_ref()).await?;
+        let txn = self.pool.begin().await?;
+        model.update(&txn).await?;
+        OutboxEvent::append(&txn, events).await?;
+        txn.commit().await?;
         Ok(())
     }
 
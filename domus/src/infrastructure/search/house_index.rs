@@ -0,0 +1,445 @@
+use std::{collections::HashMap, path::Path, sync::Mutex};
+
+use serde::{Deserialize, Serialize};
+use tantivy::{
+    collector::TopDocs,
+    directory::MmapDirectory,
+    query::{BooleanQuery, Occur, Query, TermQuery},
+    schema::{
+        Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, Value, STORED, STRING,
+    },
+    snippet::SnippetGenerator,
+    tokenizer::{LowerCaser, TextAnalyzer},
+    Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term,
+};
+
+use crate::{
+    domain::house::{events::house_patched::HousePatchedEvent, value_objects::house::House},
+    infrastructure::{entitiy::community_query, search::tokenizer::JiebaTokenizer},
+};
+
+const WRITER_HEAP_SIZE: usize = 50_000_000;
+
+// 索引里的一套房源，存原文用于增量更新时合并
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HouseSearchDocument {
+    pub id: String,
+    pub community_id: String,
+    pub title: String,
+    pub house_address: String,
+    pub community_name: String,
+    pub community_address: String,
+    pub district: String,
+    pub purpose: String,
+    pub transaction_type: String,
+    // 户型、装修、朝向等
+    pub keywords: String,
+    // 标签单独存放，批量修改标签时只替换这里，与关键词一起检索
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl HouseSearchDocument {
+    pub fn new(house: &House, community: Option<&community_query::Model>) -> Self {
+        let mut keywords = Vec::new();
+        if let Some(apartment_type) = &house.apartment_type {
+            if let Some(room) = apartment_type.room {
+                keywords.push(format!("{}室 {}室", room, chinese_number(room)));
+            }
+            if let Some(hall) = apartment_type.hall {
+                keywords.push(format!("{}厅 {}厅", hall, chinese_number(hall)));
+            }
+        }
+        keywords.extend(
+            [
+                &house.house_decoration,
+                &house.house_orientation,
+                &house.house_type,
+            ]
+            .into_iter()
+            .flatten()
+            .cloned(),
+        );
+
+        let mut document = Self {
+            id: house.id.clone().unwrap_or_default(),
+            community_id: house.community_id.clone().unwrap_or_default(),
+            title: house.title.clone().unwrap_or_default(),
+            house_address: house.house_address.clone().unwrap_or_default(),
+            purpose: house.purpose.clone().unwrap_or_default(),
+            transaction_type: house.transaction_type.clone().unwrap_or_default(),
+            keywords: keywords.join(" "),
+            tags: house
+                .tags
+                .iter()
+                .flatten()
+                .filter(|tag| !tag.is_empty())
+                .cloned()
+                .collect(),
+            ..Default::default()
+        };
+        document.set_community(community);
+        document
+    }
+
+    // 批量添加、移除标签，与 HousePatchedEvent::apply 的规则一致
+    pub fn patch_tags(&mut self, event: &HousePatchedEvent) {
+        for tag in &event.add_tags {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
+        }
+        self.tags.retain(|tag| !event.remove_tags.contains(tag));
+    }
+
+    pub fn set_community(&mut self, community: Option<&community_query::Model>) {
+        if let Some(community) = community {
+            self.community_name = community.name.clone();
+            self.community_address = community.address.clone();
+            self.district = community.district.clone().unwrap_or_default();
+        }
+    }
+}
+
+fn chinese_number(n: i32) -> String {
+    match n {
+        0 => "零".to_string(),
+        1 => "一".to_string(),
+        2 => "两".to_string(),
+        3 => "三".to_string(),
+        4 => "四".to_string(),
+        5 => "五".to_string(),
+        6 => "六".to_string(),
+        7 => "七".to_string(),
+        8 => "八".to_string(),
+        9 => "九".to_string(),
+        n => n.to_string(),
+    }
+}
+
+// 搜索结果，highlight 的 key 为字段名，命中的词用 <em> 包裹
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub id: String,
+    pub score: f32,
+    pub highlight: HashMap<String, String>,
+}
+
+struct Fields {
+    id: Field,
+    community_id: Field,
+    source: Field,
+    // 参与检索的字段，名称与 HouseSearchDocument 一致
+    text: Vec<(&'static str, Field)>,
+}
+
+// 房源全文索引，嵌入在进程内，由 HouseSearchListener 增量维护
+pub struct HouseSearchIndex {
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+    fields: Fields,
+    tokenizer: JiebaTokenizer,
+}
+
+impl HouseSearchIndex {
+    const TEXT_FIELDS: [&'static str; 8] = [
+        "title",
+        "house_address",
+        "community_name",
+        "community_address",
+        "district",
+        "purpose",
+        "transaction_type",
+        "keywords",
+    ];
+    // 返回高亮的字段
+    const HIGHLIGHT_FIELDS: [&'static str; 4] = [
+        "title",
+        "house_address",
+        "community_name",
+        "community_address",
+    ];
+
+    // 索引目录由 HOUSE_SEARCH_INDEX_DIR 指定，默认 data/house_index
+    pub fn from_env() -> anyhow::Result<Self> {
+        let path = std::env::var("HOUSE_SEARCH_INDEX_DIR")
+            .unwrap_or_else(|_| "data/house_index".to_string());
+        Self::open(path)
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(path.as_ref())?;
+        let directory = MmapDirectory::open(path)?;
+        let (schema, fields) = Self::schema();
+        Self::new(Index::open_or_create(directory, schema)?, fields)
+    }
+
+    #[cfg(test)]
+    pub fn in_memory() -> anyhow::Result<Self> {
+        let (schema, fields) = Self::schema();
+        Self::new(Index::create_in_ram(schema), fields)
+    }
+
+    fn new(index: Index, fields: Fields) -> anyhow::Result<Self> {
+        let tokenizer = JiebaTokenizer::new();
+        index.tokenizers().register(
+            JiebaTokenizer::NAME,
+            TextAnalyzer::builder(tokenizer.clone())
+                .filter(LowerCaser)
+                .build(),
+        );
+
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        let writer = index.writer(WRITER_HEAP_SIZE)?;
+
+        Ok(Self {
+            reader,
+            writer: Mutex::new(writer),
+            fields,
+            tokenizer,
+        })
+    }
+
+    fn schema() -> (Schema, Fields) {
+        let mut builder = Schema::builder();
+        let text_options = TextOptions::default().set_stored().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer(JiebaTokenizer::NAME)
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+
+        let fields = Fields {
+            id: builder.add_text_field("id", STRING | STORED),
+            community_id: builder.add_text_field("community_id", STRING),
+            source: builder.add_text_field("source", STORED),
+            text: Self::TEXT_FIELDS
+                .iter()
+                .map(|name| (*name, builder.add_text_field(name, text_options.clone())))
+                .collect(),
+        };
+
+        (builder.build(), fields)
+    }
+
+    fn to_document(&self, document: &HouseSearchDocument) -> anyhow::Result<TantivyDocument> {
+        let source = serde_json::to_value(document)?;
+        let mut doc = TantivyDocument::default();
+        doc.add_text(self.fields.id, &document.id);
+        doc.add_text(self.fields.community_id, &document.community_id);
+        doc.add_text(self.fields.source, source.to_string());
+        for (name, field) in &self.fields.text {
+            if let Some(text) = source.get(name).and_then(|v| v.as_str()) {
+                doc.add_text(*field, text);
+            }
+            // 标签写入关键词字段检索
+            if *name == "keywords" {
+                for tag in &document.tags {
+                    doc.add_text(*field, tag);
+                }
+            }
+        }
+        Ok(doc)
+    }
+
+    fn read_document(&self, doc: &TantivyDocument) -> anyhow::Result<HouseSearchDocument> {
+        let source = doc
+            .get_first(self.fields.source)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("索引文档缺少原文"))?;
+        Ok(serde_json::from_str(source)?)
+    }
+
+    fn commit(&self, writer: &mut IndexWriter) -> anyhow::Result<()> {
+        writer.commit()?;
+        self.reader.reload()?;
+        Ok(())
+    }
+
+    // 新增或替换
+    pub fn upsert(&self, documents: &[HouseSearchDocument]) -> anyhow::Result<()> {
+        let mut writer = self.writer.lock().unwrap();
+        for document in documents {
+            writer.delete_term(Term::from_field_text(self.fields.id, &document.id));
+            writer.add_document(self.to_document(document)?)?;
+        }
+        self.commit(&mut writer)
+    }
+
+    pub fn delete(&self, house_id: &str) -> anyhow::Result<()> {
+        let mut writer = self.writer.lock().unwrap();
+        writer.delete_term(Term::from_field_text(self.fields.id, house_id));
+        self.commit(&mut writer)
+    }
+
+    // 清空后重新写入
+    pub fn rebuild(
+        &self,
+        documents: impl IntoIterator<Item = HouseSearchDocument>,
+    ) -> anyhow::Result<u64> {
+        let mut writer = self.writer.lock().unwrap();
+        writer.delete_all_documents()?;
+        let mut count = 0;
+        for document in documents {
+            writer.add_document(self.to_document(&document)?)?;
+            count += 1;
+        }
+        self.commit(&mut writer)?;
+        Ok(count)
+    }
+
+    pub fn find(&self, house_id: &str) -> anyhow::Result<Option<HouseSearchDocument>> {
+        Ok(self
+            .find_by_term(Term::from_field_text(self.fields.id, house_id), 1)?
+            .pop())
+    }
+
+    // 小区下的所有房源，小区改名时用
+    pub fn find_by_community(
+        &self,
+        community_id: &str,
+    ) -> anyhow::Result<Vec<HouseSearchDocument>> {
+        let term = Term::from_field_text(self.fields.community_id, community_id);
+        let searcher = self.reader.searcher();
+        let limit = (searcher.num_docs() as usize).max(1);
+        self.find_by_term(term, limit)
+    }
+
+    fn find_by_term(&self, term: Term, limit: usize) -> anyhow::Result<Vec<HouseSearchDocument>> {
+        let searcher = self.reader.searcher();
+        let query = TermQuery::new(term, IndexRecordOption::Basic);
+        searcher
+            .search(&query, &TopDocs::with_limit(limit))?
+            .into_iter()
+            .map(|(_, address)| self.read_document(&searcher.doc(address)?))
+            .collect()
+    }
+
+    // 按空格分组，每组的词都要命中（任一字段），按相关度排序
+    pub fn search(&self, q: &str, limit: usize) -> anyhow::Result<Vec<SearchHit>> {
+        let groups: Vec<Box<dyn Query>> = q
+            .split_whitespace()
+            .flat_map(|group| self.tokenizer.cut(group))
+            .map(|word| {
+                let fields: Vec<(Occur, Box<dyn Query>)> = self
+                    .fields
+                    .text
+                    .iter()
+                    .map(|(_, field)| {
+                        let query: Box<dyn Query> = Box::new(TermQuery::new(
+                            Term::from_field_text(*field, &word),
+                            IndexRecordOption::WithFreqsAndPositions,
+                        ));
+                        (Occur::Should, query)
+                    })
+                    .collect();
+                Box::new(BooleanQuery::new(fields)) as Box<dyn Query>
+            })
+            .collect();
+
+        if groups.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = BooleanQuery::new(groups.into_iter().map(|q| (Occur::Must, q)).collect());
+        let searcher = self.reader.searcher();
+
+        let mut snippets = Vec::new();
+        for (name, field) in &self.fields.text {
+            if Self::HIGHLIGHT_FIELDS.contains(name) {
+                let mut generator = SnippetGenerator::create(&searcher, &query, *field)?;
+                generator.set_max_num_chars(100);
+                snippets.push((*name, generator));
+            }
+        }
+
+        searcher
+            .search(&query, &TopDocs::with_limit(limit))?
+            .into_iter()
+            .map(|(score, address)| {
+                let doc: TantivyDocument = searcher.doc(address)?;
+                let id = doc
+                    .get_first(self.fields.id)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+
+                let mut highlight = HashMap::new();
+                for (name, generator) in &snippets {
+                    let mut snippet = generator.snippet_from_doc(&doc);
+                    if snippet.highlighted().is_empty() {
+                        continue;
+                    }
+                    snippet.set_snippet_prefix_postfix("<em>", "</em>");
+                    highlight.insert(name.to_string(), snippet.to_html());
+                }
+
+                Ok(SearchHit {
+                    id,
+                    score,
+                    highlight,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HouseSearchDocument, HouseSearchIndex};
+    use crate::domain::house::events::house_patched::HousePatchedEvent;
+
+    fn document(id: &str, community_name: &str, keywords: &str) -> HouseSearchDocument {
+        HouseSearchDocument {
+            id: id.to_string(),
+            community_id: format!("c-{}", community_name),
+            community_name: community_name.to_string(),
+            keywords: keywords.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let index = HouseSearchIndex::in_memory().unwrap();
+        index
+            .upsert(&[
+                document("1", "皖江华府", "3室 三室 精装修"),
+                document("2", "皖江华府", "2室 两室"),
+                document("3", "碧桂园", "3室 三室"),
+            ])
+            .unwrap();
+
+        let hits = index.search("皖江华府 三室", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, "1");
+        assert!(hits[0].highlight["community_name"].contains("<em>"));
+
+        assert_eq!(index.search("华府", 10).unwrap().len(), 2);
+        assert_eq!(index.find_by_community("c-皖江华府").unwrap().len(), 2);
+
+        // 批量修改标签只替换标签，关键词不变
+        let mut document = index.find("3").unwrap().unwrap();
+        document.patch_tags(&HousePatchedEvent {
+            add_tags: vec!["学区房".to_string()],
+            ..HousePatchedEvent::new("3".to_string())
+        });
+        index.upsert(&[document]).unwrap();
+        assert_eq!(index.search("学区房 三室", 10).unwrap()[0].id, "3");
+
+        let mut document = index.find("3").unwrap().unwrap();
+        document.patch_tags(&HousePatchedEvent {
+            remove_tags: vec!["学区房".to_string()],
+            ..HousePatchedEvent::new("3".to_string())
+        });
+        index.upsert(&[document]).unwrap();
+        assert!(index.search("学区房", 10).unwrap().is_empty());
+        assert_eq!(index.search("碧桂园 三室", 10).unwrap().len(), 1);
+
+        index.delete("1").unwrap();
+        assert!(index.find("1").unwrap().is_none());
+        assert!(index.search("皖江华府 三室", 10).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod house_index;
+pub mod tokenizer;
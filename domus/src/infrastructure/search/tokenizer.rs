@@ -0,0 +1,93 @@
+use std::sync::{Arc, LazyLock};
+
+use jieba_rs::{Jieba, TokenizeMode};
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+// 词典加载较慢，进程内共用一份
+static JIEBA: LazyLock<Arc<Jieba>> = LazyLock::new(|| Arc::new(Jieba::new()));
+
+// 中文分词，建索引时用搜索模式切出长词里的短词，“皖江华府”也能被“华府”搜到
+#[derive(Clone)]
+pub struct JiebaTokenizer {
+    jieba: Arc<Jieba>,
+}
+
+impl JiebaTokenizer {
+    pub const NAME: &'static str = "jieba";
+
+    pub fn new() -> Self {
+        Self {
+            jieba: JIEBA.clone(),
+        }
+    }
+
+    // 搜索词用精确模式切分，避免“三室”切出的单字把不相关的房源也带出来
+    pub fn cut(&self, text: &str) -> Vec<String> {
+        self.jieba
+            .cut(text, true)
+            .into_iter()
+            .filter(|word| is_word(word))
+            .map(str::to_lowercase)
+            .collect()
+    }
+}
+
+impl Default for JiebaTokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_word(word: &str) -> bool {
+    word.chars().any(char::is_alphanumeric)
+}
+
+pub struct JiebaTokenStream {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Tokenizer for JiebaTokenizer {
+    type TokenStream<'a> = JiebaTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> JiebaTokenStream {
+        // jieba 返回字符下标，tantivy 高亮需要字节偏移
+        let offsets: Vec<usize> = text
+            .char_indices()
+            .map(|(offset, _)| offset)
+            .chain(std::iter::once(text.len()))
+            .collect();
+
+        let tokens = self
+            .jieba
+            .tokenize(text, TokenizeMode::Search, true)
+            .into_iter()
+            .filter(|token| is_word(token.word))
+            .enumerate()
+            .map(|(position, token)| Token {
+                offset_from: offsets[token.start],
+                offset_to: offsets[token.end],
+                position,
+                text: token.word.to_lowercase(),
+                position_length: 1,
+            })
+            .collect();
+
+        JiebaTokenStream { tokens, index: 0 }
+    }
+}
+
+impl TokenStream for JiebaTokenStream {
+    fn advance(&mut self) -> bool {
+        self.index += 1;
+        self.index <= self.tokens.len()
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
@@ -0,0 +1,221 @@
+use std::io::Cursor;
+
+use calamine::{Data, Reader, Xlsx};
+use rust_xlsxwriter::Workbook;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// xlsx 是 zip 包，以 PK 开头
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const UTF8_BOM: &str = "\u{feff}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpreadsheetFormat {
+    #[default]
+    Csv,
+    Xlsx,
+}
+
+impl SpreadsheetFormat {
+    // 按文件内容判断格式，不依赖文件名
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(ZIP_MAGIC) {
+            SpreadsheetFormat::Xlsx
+        } else {
+            SpreadsheetFormat::Csv
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SpreadsheetFormat::Csv => "csv",
+            SpreadsheetFormat::Xlsx => "xlsx",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            SpreadsheetFormat::Csv => "text/csv; charset=utf-8",
+            SpreadsheetFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+        }
+    }
+}
+
+// 表格内容，第一行为表头，单元格统一转为去掉首尾空白的字符串
+#[derive(Debug, Clone, Default)]
+pub struct Spreadsheet {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Spreadsheet {
+    pub fn read(bytes: &[u8]) -> anyhow::Result<Self> {
+        let mut rows = match SpreadsheetFormat::detect(bytes) {
+            SpreadsheetFormat::Csv => read_csv(bytes)?,
+            SpreadsheetFormat::Xlsx => read_xlsx(bytes)?,
+        };
+
+        if rows.is_empty() {
+            return Err(anyhow::anyhow!("表格为空"));
+        }
+
+        let mut headers = rows.remove(0);
+        if let Some(first) = headers.first_mut() {
+            *first = first.trim_start_matches(UTF8_BOM).to_string();
+        }
+
+        Ok(Self { headers, rows })
+    }
+}
+
+fn read_csv(bytes: &[u8]) -> anyhow::Result<Vec<Vec<String>>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(bytes);
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| anyhow::anyhow!("CSV 解析失败: {}", e))?;
+        rows.push(record.iter().map(|cell| cell.trim().to_string()).collect());
+    }
+
+    Ok(rows)
+}
+
+// 只读取第一个工作表
+fn read_xlsx(bytes: &[u8]) -> anyhow::Result<Vec<Vec<String>>> {
+    let mut workbook =
+        Xlsx::new(Cursor::new(bytes)).map_err(|e| anyhow::anyhow!("Excel 解析失败: {}", e))?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| anyhow::anyhow!("Excel 中没有工作表"))?
+        .map_err(|e| anyhow::anyhow!("Excel 解析失败: {}", e))?;
+
+    Ok(range
+        .rows()
+        .map(|row| row.iter().map(cell_to_string).collect())
+        .collect())
+}
+
+fn cell_to_string(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        // 整数在 Excel 里也是浮点数，去掉多余的 .0
+        Data::Float(value) if value.fract() == 0.0 && value.abs() < 1e15 => {
+            format!("{}", *value as i64)
+        }
+        Data::DateTime(value) => value
+            .as_datetime()
+            .map(|datetime| datetime.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| value.to_string()),
+        _ => cell.to_string().trim().to_string(),
+    }
+}
+
+// 单元格的文本，数组用顿号连接
+pub fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Bool(true) => "是".to_string(),
+        Value::Bool(false) => "否".to_string(),
+        Value::Array(values) => values
+            .iter()
+            .map(cell_text)
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("、"),
+        _ => value.to_string(),
+    }
+}
+
+// 写出若干行 CSV，with_bom 时在开头加 BOM，方便 Excel 识别 UTF-8
+pub fn write_csv(rows: &[Vec<Value>], with_bom: bool) -> anyhow::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    if with_bom {
+        buffer.extend_from_slice(UTF8_BOM.as_bytes());
+    }
+
+    let mut writer = csv::Writer::from_writer(buffer);
+    for row in rows {
+        writer.write_record(row.iter().map(cell_text))?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| anyhow::anyhow!("CSV 写入失败: {}", e))
+}
+
+// 写出只有一个工作表的 Excel，数字保留为数字单元格
+pub fn write_xlsx(rows: &[Vec<Value>]) -> anyhow::Result<Vec<u8>> {
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+
+    for (row_index, row) in rows.iter().enumerate() {
+        for (col_index, value) in row.iter().enumerate() {
+            let (row_index, col_index) = (row_index as u32, col_index as u16);
+            match value {
+                Value::Null => {}
+                Value::Number(number) => {
+                    worksheet.write_number(
+                        row_index,
+                        col_index,
+                        number.as_f64().unwrap_or_default(),
+                    )?;
+                }
+                _ => {
+                    worksheet.write_string(row_index, col_index, cell_text(value))?;
+                }
+            }
+        }
+    }
+
+    Ok(workbook.save_to_buffer()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{write_csv, write_xlsx, Spreadsheet, SpreadsheetFormat};
+
+    #[test]
+    fn test_read_csv() {
+        let csv = "\u{feff}小区,地址,售价\n皖江华府, 11栋104室 ,120\n\"滨江, 花园\",2栋\n";
+        assert_eq!(
+            SpreadsheetFormat::detect(csv.as_bytes()),
+            SpreadsheetFormat::Csv
+        );
+
+        let sheet = Spreadsheet::read(csv.as_bytes()).unwrap();
+        assert_eq!(sheet.headers, vec!["小区", "地址", "售价"]);
+        assert_eq!(sheet.rows.len(), 2);
+        assert_eq!(sheet.rows[0], vec!["皖江华府", "11栋104室", "120"]);
+        assert_eq!(sheet.rows[1], vec!["滨江, 花园", "2栋"]);
+        assert!(Spreadsheet::read(b"").is_err());
+    }
+
+    #[test]
+    fn test_write() {
+        let rows = vec![
+            vec![json!("小区"), json!("售价"), json!("标签")],
+            vec![json!("皖江华府"), json!(120), json!(["满五", "唯一"])],
+            vec![json!("滨江, 花园"), json!(null), json!([])],
+        ];
+
+        let csv = write_csv(&rows, true).unwrap();
+        let sheet = Spreadsheet::read(&csv).unwrap();
+        assert_eq!(sheet.headers, vec!["小区", "售价", "标签"]);
+        assert_eq!(sheet.rows[0], vec!["皖江华府", "120", "满五、唯一"]);
+        assert_eq!(sheet.rows[1], vec!["滨江, 花园", "", ""]);
+
+        let xlsx = write_xlsx(&rows).unwrap();
+        assert_eq!(SpreadsheetFormat::detect(&xlsx), SpreadsheetFormat::Xlsx);
+        let sheet = Spreadsheet::read(&xlsx).unwrap();
+        assert_eq!(sheet.rows[0], vec!["皖江华府", "120", "满五、唯一"]);
+    }
+}
@@ -0,0 +1,60 @@
+use event_bus::Event;
+use serde::{Deserialize, Serialize};
+
+// user_system 发布的事件在 domus 中的镜像，事件名称需要与 user_system 保持一致。
+// 只解析 domus 关心的变体，其它变体反序列化后为空。
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserEvent {
+    #[serde(rename = "UserDeleted", skip_serializing_if = "Option::is_none")]
+    pub user_deleted: Option<UserDeletedEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserDeletedEvent {
+    pub id: String,
+}
+
+impl Event for UserEvent {
+    const NAME: &'static str = "UserEvent";
+    const AGGREGATE_TYPE: &'static str = "user";
+
+    fn aggregate_id(&self) -> String {
+        self.user_deleted
+            .as_ref()
+            .map(|event| event.id.clone())
+            .unwrap_or_default()
+    }
+}
+
+// 角色、权限的任何变化都需要重新加载 casbin 策略，因此保留原始内容即可
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RoleEvent(pub serde_json::Value);
+
+impl Event for RoleEvent {
+    const NAME: &'static str = "RoleEvent";
+    const AGGREGATE_TYPE: &'static str = "role";
+
+    fn aggregate_id(&self) -> String {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_user_system_payload() {
+        let deleted: UserEvent =
+            serde_json::from_value(serde_json::json!({"UserDeleted": {"id": "u1"}})).unwrap();
+        assert_eq!(deleted.aggregate_id(), "u1");
+
+        let updated: UserEvent = serde_json::from_value(
+            serde_json::json!({"UserUpdated": {"id": "u1", "username": "test"}}),
+        )
+        .unwrap();
+        assert!(updated.user_deleted.is_none());
+    }
+}
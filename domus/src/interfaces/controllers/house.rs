@@ -1,24 +1,59 @@
-use actix_web::{get, post, web, HttpMessage, HttpRequest, HttpResponse};
+use actix_web::{
+    get,
+    http::header::{ContentDisposition, DispositionParam, DispositionType},
+    post, web, HttpMessage, HttpRequest, HttpResponse,
+};
+use sea_orm::prelude::DateTimeUtc;
 use serde::{Deserialize, Serialize};
 use user_system::shared::claims::Claims;
 
 use crate::{
     application::{
-        queries::house::{HouseQueryService, HouseRequest},
+        commands::change_house_status::ChangeHouseStatusCommand,
+        queries::{
+            house::{HouseQueryService, HouseRequest},
+            house_duplicate::{DuplicateHouse, DuplicateReportRequest, HouseDuplicateQueryService},
+            house_export::{
+                HouseExportBody, HouseExportOperator, HouseExportRequest, HouseExportService,
+            },
+            house_history::HouseHistoryQueryService,
+            house_map::HouseMapRequest,
+            house_price::HousePriceQueryService,
+        },
         services::{
             delete_house::DeleteHouseService,
+            house_batch::{HouseBatchOperator, HouseBatchRequest, HouseBatchService},
+            house_import::{HouseImportQuery, HouseImportService},
             house_operation_log::{HouseOperationLogDto, HouseOperationLogService},
+            house_recycle_bin::HouseRecycleBinService,
+            house_status::HouseStatusService,
             save_house::SaveHouseService,
         },
     },
-    domain::house::value_objects::house::{House, HouseData},
+    domain::house::{
+        events::house_price_changed::PriceField,
+        value_objects::{
+            dictionary::{house_dictionary, Dictionary},
+            house::{House, HouseData},
+        },
+    },
     interfaces::dtos::response::ResponseBody,
 };
 
+#[derive(Debug, Serialize)]
+pub struct SaveHouseResponse {
+    #[serde(flatten)]
+    pub house: House,
+    // 同一小区内地址相似的房源，只做提示，不阻止保存
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<DuplicateHouse>,
+}
+
 #[post("/save")]
 pub async fn save_house(
     body: web::Json<HouseData>,
     save_house_service: web::Data<SaveHouseService>,
+    house_duplicate_query_service: web::Data<HouseDuplicateQueryService>,
     house_operation_log_service: web::Data<HouseOperationLogService>,
     req: HttpRequest,
 ) -> HttpResponse {
@@ -70,7 +105,18 @@ pub async fn save_house(
                 record_operation_log(1, house.clone()).await;
             }
 
-            ResponseBody::success(house)
+            let duplicates = match (&house.community_id, &house.house_address) {
+                (Some(community_id), Some(house_address)) => house_duplicate_query_service
+                    .find_similar(community_id, house_address, house.id.as_deref())
+                    .await
+                    .unwrap_or_else(|err| {
+                        tracing::error!("查询相似房源失败:{}", err);
+                        Vec::new()
+                    }),
+                _ => Vec::new(),
+            };
+
+            ResponseBody::success(SaveHouseResponse { house, duplicates })
         }
         Err(e) => ResponseBody::error(e.to_string()),
     };
@@ -127,6 +173,207 @@ pub async fn delete_house(
     HttpResponse::Ok().json(res)
 }
 
+// 从回收站恢复房源
+#[post("/restore/{house_id}")]
+pub async fn restore_house(
+    path: web::Path<String>,
+    house_recycle_bin_service: web::Data<HouseRecycleBinService>,
+    house_operation_log_service: web::Data<HouseOperationLogService>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user = req.extensions().get::<Claims>().cloned();
+    let Some(user) = user else {
+        return HttpResponse::Forbidden().finish();
+    };
+
+    let ip_address = req.peer_addr().map(|addr| addr.to_string());
+    let user_agent = req
+        .headers()
+        .get("User-Agent")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+
+    let house_id = path.into_inner();
+    let res = match house_recycle_bin_service.restore(house_id.clone()).await {
+        Ok(data) => {
+            let house = House {
+                id: Some(house_id),
+                ..Default::default()
+            };
+
+            if let Err(err) = house_operation_log_service
+                .save_record(HouseOperationLogDto {
+                    operation_type: 7,
+                    operation_content: house,
+                    operator_id: user.user_id.clone(),
+                    ip_address: ip_address.clone(),
+                    user_agent: user_agent.clone(),
+                })
+                .await
+            {
+                tracing::error!("记录操作日志失败:{}", err);
+            }
+
+            ResponseBody::success(data)
+        }
+        Err(e) => ResponseBody::error(e.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
+
+#[post("/status/{house_id}")]
+pub async fn change_house_status(
+    path: web::Path<String>,
+    body: web::Json<ChangeHouseStatusCommand>,
+    house_status_service: web::Data<HouseStatusService>,
+    house_operation_log_service: web::Data<HouseOperationLogService>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user = req.extensions().get::<Claims>().cloned();
+    let Some(user) = user else {
+        return HttpResponse::Forbidden().finish();
+    };
+
+    let ip_address = req.peer_addr().map(|addr| addr.to_string());
+    let user_agent = req
+        .headers()
+        .get("User-Agent")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+
+    let house_id = path.into_inner();
+    let mut command = body.into_inner();
+    command.house_id = house_id.clone();
+    command.actor_id = Some(user.user_id.clone());
+    let status = command.status;
+
+    let res = match house_status_service.change_status(command).await {
+        Ok(data) => {
+            let house = House {
+                id: Some(house_id),
+                house_status: Some(status.as_str().to_string()),
+                ..Default::default()
+            };
+
+            if let Err(err) = house_operation_log_service
+                .save_record(HouseOperationLogDto {
+                    operation_type: 6,
+                    operation_content: house,
+                    operator_id: user.user_id.clone(),
+                    ip_address: ip_address.clone(),
+                    user_agent: user_agent.clone(),
+                })
+                .await
+            {
+                tracing::error!("记录操作日志失败:{}", err);
+            }
+
+            ResponseBody::success(data)
+        }
+        Err(e) => ResponseBody::error(e.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
+
+// 批量导入房源，请求体为 CSV 或 Excel 文件内容
+#[post("/import")]
+pub async fn import_houses(
+    body: web::Bytes,
+    query: web::Query<HouseImportQuery>,
+    house_import_service: web::Data<HouseImportService>,
+    house_operation_log_service: web::Data<HouseOperationLogService>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user = req.extensions().get::<Claims>().cloned();
+    let Some(user) = user else {
+        return HttpResponse::Forbidden().finish();
+    };
+
+    let ip_address = req.peer_addr().map(|addr| addr.to_string());
+    let user_agent = req
+        .headers()
+        .get("User-Agent")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+
+    let dry_run = query.dry_run.unwrap_or(true);
+    let res = match house_import_service
+        .execute(&body, dry_run, &user.user_id)
+        .await
+    {
+        Ok(report) => {
+            // 与单条新增一样，每套导入的房源记录一条操作日志
+            for house in &report.houses {
+                if let Err(err) = house_operation_log_service
+                    .save_record(HouseOperationLogDto {
+                        operation_type: 1,
+                        operation_content: house.clone(),
+                        operator_id: user.user_id.clone(),
+                        ip_address: ip_address.clone(),
+                        user_agent: user_agent.clone(),
+                    })
+                    .await
+                {
+                    tracing::error!("记录操作日志失败:{}", err);
+                }
+            }
+
+            ResponseBody::success(report)
+        }
+        Err(e) => ResponseBody::error(e.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
+
+// 批量上下架、变更状态、删除、更换维护人、增删标签
+#[post("/batch")]
+pub async fn batch_houses(
+    body: web::Json<HouseBatchRequest>,
+    house_batch_service: web::Data<HouseBatchService>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user = req.extensions().get::<Claims>().cloned();
+    let Some(user) = user else {
+        return HttpResponse::Forbidden().finish();
+    };
+
+    let operator = HouseBatchOperator {
+        operator_id: user.user_id.clone(),
+        ip_address: req.peer_addr().map(|addr| addr.to_string()),
+        user_agent: req
+            .headers()
+            .get("User-Agent")
+            .and_then(|h| h.to_str().ok())
+            .map(|s| s.to_string()),
+    };
+
+    let res = match house_batch_service
+        .execute(body.into_inner(), operator)
+        .await
+    {
+        Ok(data) => ResponseBody::success(data),
+        Err(e) => ResponseBody::error(e.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
+
+#[get("/{house_id}/status")]
+pub async fn get_house_status(
+    path: web::Path<String>,
+    house_status_service: web::Data<HouseStatusService>,
+) -> HttpResponse {
+    let res = match house_status_service.transitions(&path.into_inner()).await {
+        Ok(data) => ResponseBody::success(data),
+        Err(e) => ResponseBody::error(e.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
+
 #[post("/list")]
 pub async fn list_houses(
     query: web::Json<HouseRequest>,
@@ -140,6 +387,20 @@ pub async fn list_houses(
     HttpResponse::Ok().json(res)
 }
 
+// 回收站，筛选条件与房源列表相同
+#[post("/recycle_bin")]
+pub async fn list_recycle_bin(
+    query: web::Json<HouseRequest>,
+    house_recycle_bin_service: web::Data<HouseRecycleBinService>,
+) -> HttpResponse {
+    let res = match house_recycle_bin_service.list(query.into_inner()).await {
+        Ok(data) => ResponseBody::success(data),
+        Err(e) => ResponseBody::error(e.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
+
 #[post("/group_by_community")]
 pub async fn group_by_community(
     house_query_service: web::Data<HouseQueryService>,
@@ -156,12 +417,109 @@ pub async fn group_by_community(
     HttpResponse::Ok().json(res)
 }
 
+#[post("/map")]
+pub async fn map_aggregate(
+    house_query_service: web::Data<HouseQueryService>,
+    query: web::Json<HouseMapRequest>,
+) -> HttpResponse {
+    let res = match house_query_service.map_aggregate(query.into_inner()).await {
+        Ok(data) => ResponseBody::success(data),
+        Err(e) => ResponseBody::error(e.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
+
+// 按列表的筛选条件导出 CSV 或 Excel
+#[post("/export")]
+pub async fn export_houses(
+    body: web::Json<HouseExportRequest>,
+    house_export_service: web::Data<HouseExportService>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let user = req.extensions().get::<Claims>().cloned();
+    let Some(user) = user else {
+        return HttpResponse::Forbidden().finish();
+    };
+
+    let operator = HouseExportOperator {
+        operator_id: user.user_id.clone(),
+        ip_address: req.peer_addr().map(|addr| addr.to_string()),
+        user_agent: req
+            .headers()
+            .get("User-Agent")
+            .and_then(|h| h.to_str().ok())
+            .map(|s| s.to_string()),
+    };
+
+    let include_owner = house_export_service.include_owner(&user).await;
+    let export = match house_export_service
+        .export(body.into_inner(), include_owner, operator)
+        .await
+    {
+        Ok(export) => export,
+        Err(e) => return HttpResponse::Ok().json(ResponseBody::<()>::error(e.to_string())),
+    };
+
+    let mut response = HttpResponse::Ok();
+    response
+        .content_type(export.format.content_type())
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(export.filename)],
+        });
+
+    match export.body {
+        HouseExportBody::Csv(stream) => response.streaming(stream),
+        HouseExportBody::Xlsx(bytes) => response.body(bytes),
+    }
+}
+
+// 疑似重复房源，同一小区内地址相似的分为一组
+#[get("/duplicates")]
+pub async fn list_duplicate_houses(
+    query: web::Query<DuplicateReportRequest>,
+    house_duplicate_query_service: web::Data<HouseDuplicateQueryService>,
+) -> HttpResponse {
+    let res = match house_duplicate_query_service
+        .report(query.into_inner())
+        .await
+    {
+        Ok(data) => ResponseBody::success(data),
+        Err(e) => ResponseBody::error(e.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HouseDetailQuery {
+    // 查看房源在这一时刻的样子
+    pub as_of: Option<DateTimeUtc>,
+}
+
 #[get("/detail/{house_id}")]
 pub async fn get_house_detail(
     path: web::Path<String>,
+    query: web::Query<HouseDetailQuery>,
     house_query_service: web::Data<HouseQueryService>,
+    house_history_query_service: web::Data<HouseHistoryQueryService>,
 ) -> HttpResponse {
-    let res = match house_query_service.find_by_id(&path.into_inner()).await {
+    let house_id = path.into_inner();
+
+    if let Some(as_of) = query.as_of {
+        let res = match house_history_query_service
+            .find_as_of(&house_id, as_of)
+            .await
+        {
+            Ok(data) => ResponseBody::success(data),
+            Err(e) => ResponseBody::error(e.to_string()),
+        };
+
+        return HttpResponse::Ok().json(res);
+    }
+
+    let res = match house_query_service.find_by_id(&house_id).await {
         Some(data) => ResponseBody::success(data),
         None => ResponseBody::error("not found".to_string()),
     };
@@ -169,12 +527,58 @@ pub async fn get_house_detail(
     HttpResponse::Ok().json(res)
 }
 
+// 房源的历史版本及每次修改的字段差异
+#[get("/{house_id}/history")]
+pub async fn get_house_history(
+    path: web::Path<String>,
+    house_history_query_service: web::Data<HouseHistoryQueryService>,
+) -> HttpResponse {
+    let res = match house_history_query_service
+        .history(&path.into_inner())
+        .await
+    {
+        Ok(data) => ResponseBody::success(data),
+        Err(e) => ResponseBody::error(e.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriceHistoryQuery {
+    // 只看售价或租金，不传返回全部
+    pub field: Option<PriceField>,
+}
+
+#[get("/{house_id}/price_history")]
+pub async fn get_house_price_history(
+    path: web::Path<String>,
+    query: web::Query<PriceHistoryQuery>,
+    house_price_query_service: web::Data<HousePriceQueryService>,
+) -> HttpResponse {
+    let res = match house_price_query_service
+        .find_by_house(&path.into_inner(), query.field)
+        .await
+    {
+        Ok(data) => ResponseBody::success(data),
+        Err(e) => ResponseBody::error(e.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 struct UploadUrlRequest {
     directory: String,
     filename: String,
 }
 
+// 房源字典，前端下拉框和导入校验使用
+#[get("/dictionary")]
+pub async fn get_house_dictionary() -> HttpResponse {
+    HttpResponse::Ok().json(ResponseBody::success(house_dictionary()))
+}
+
 #[get("/house_operation_log/list/{house_id}")]
 pub async fn list_house_operation_log(
     house_id: web::Path<String>,
@@ -1,6 +1,8 @@
 pub mod community;
+pub mod event;
 pub mod favorites;
 pub mod house;
 pub mod house_comment;
 pub mod owner;
 pub mod public_house;
+pub mod sync;
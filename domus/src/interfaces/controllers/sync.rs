@@ -0,0 +1,27 @@
+use actix_web::{get, web, HttpMessage, HttpRequest, HttpResponse};
+use user_system::shared::claims::Claims;
+
+use crate::{
+    application::queries::sync::{SyncQueryService, SyncRequest},
+    interfaces::dtos::response::ResponseBody,
+};
+
+#[get("/changes")]
+async fn sync_changes(
+    req: HttpRequest,
+    query: web::Query<SyncRequest>,
+    service: web::Data<SyncQueryService>,
+) -> HttpResponse {
+    let user = req.extensions().get::<Claims>().cloned();
+    let Some(user) = user else {
+        return HttpResponse::Forbidden().finish();
+    };
+
+    let scope = service.scope(&user).await;
+    let res = match service.changes(query.into_inner(), scope).await {
+        Ok(data) => ResponseBody::success(data),
+        Err(err) => ResponseBody::error(err.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
@@ -7,6 +7,10 @@ mod domain;
 mod infrastructure;
 mod init_system;
 mod interfaces;
+mod normalize_house_dictionary;
+mod rebuild_projections;
+mod rebuild_search_index;
+mod snapshots;
 mod start_http_server;
 
 #[actix_web::main]
@@ -18,6 +22,18 @@ async fn main() -> std::io::Result<()> {
             init_system::execute().await.unwrap();
             Ok(())
         }
+        Some(InitSystemCommand::RebuildProjections {
+            projection,
+            dry_run,
+            force,
+        }) => rebuild_projections::execute(projection, dry_run, force).await,
+        Some(InitSystemCommand::Snapshots { action, aggregate }) => {
+            snapshots::execute(action, aggregate).await
+        }
+        Some(InitSystemCommand::NormalizeHouseDictionary { dry_run }) => {
+            normalize_house_dictionary::execute(dry_run).await
+        }
+        Some(InitSystemCommand::RebuildSearchIndex) => rebuild_search_index::execute().await,
         None => start_http_server::execute().await,
     }
 }
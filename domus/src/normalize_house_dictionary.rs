@@ -0,0 +1,18 @@
+use crate::{
+    application::commands::normalize_house_dictionary::{
+        NormalizeHouseDictionaryCommand, NormalizeHouseDictionaryCommandHandler,
+    },
+    infrastructure::mysql_pool::create_mysql_pool,
+};
+
+pub async fn execute(dry_run: bool) -> std::io::Result<()> {
+    dotenvy::dotenv().ok();
+    let pool = create_mysql_pool().await;
+
+    NormalizeHouseDictionaryCommandHandler::new(pool)
+        .handle(NormalizeHouseDictionaryCommand { dry_run })
+        .await
+        .unwrap();
+
+    Ok(())
+}
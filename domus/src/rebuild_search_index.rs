@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use crate::{
+    application::commands::rebuild_search_index::RebuildSearchIndexCommandHandler,
+    infrastructure::{mysql_pool::create_mysql_pool, search::house_index::HouseSearchIndex},
+};
+
+pub async fn execute() -> std::io::Result<()> {
+    dotenvy::dotenv().ok();
+    let pool = create_mysql_pool().await;
+    let search_index = Arc::new(HouseSearchIndex::from_env().map_err(std::io::Error::other)?);
+
+    RebuildSearchIndexCommandHandler::new(pool, search_index)
+        .handle()
+        .await
+        .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use crate::{
+    application::commands::snapshots::{
+        SnapshotAction, SnapshotAggregate, SnapshotsCommand, SnapshotsCommandHandler,
+    },
+    infrastructure::mysql_pool::create_mysql_pool,
+};
+
+pub async fn execute(action: SnapshotAction, aggregate: SnapshotAggregate) -> std::io::Result<()> {
+    dotenvy::dotenv().ok();
+    let pool = create_mysql_pool().await;
+
+    SnapshotsCommandHandler::new(pool)
+        .handle(SnapshotsCommand { action, aggregate })
+        .await
+        .unwrap();
+
+    Ok(())
+}
@@ -1,53 +1,84 @@
 use std::{env, sync::Arc};
 
 use actix_web::{web, App, HttpServer};
-use event_bus::{AsyncEventBus, EventListener};
+use event_bus::{
+    dead_letter::DeadLetterService, store::EventStoreService, AsyncEventBus, EventListener,
+    MysqlPollingTransport, OutboxDispatcher, TransportConsumer,
+};
 use tokio::sync::Mutex;
 use user_system::shared::{auth_middleware::AuthMiddleware, casbin::init_casbin::init_casbin};
 
 use crate::{
     application::{
         commands::{
+            batch_house::BatchHouseCommandHandler,
+            change_house_status::ChangeHouseStatusCommandHandler,
             delete_community::DeleteCommunityCommandHandler,
             delete_house::DeleteHouseCommandHandler, delete_owner::DeleteOwnerCommandHandler,
+            restore_house::RestoreHouseCommandHandler,
             save_community_handler::SaveCommunityCommandHandler,
             save_house_handler::SaveHouseCommandHandler,
             save_owner_handler::SaveOwnerCommandHandler,
         },
         listeners::{
-            community::CommunityEventListener, house::HouseEventListener, owner::OwnerEventListener,
+            community::CommunityEventListener,
+            house::HouseEventListener,
+            house_price::HousePriceListener,
+            house_search::{CommunitySearchListener, HouseSearchListener},
+            owner::OwnerEventListener,
+            user_system::{RoleEventListener, UserEventListener},
         },
         queries::{
-            community::CommunityQueryService, house::HouseQueryService, owner::OwnerQueryService,
-            public_house::PublicHouseQueryService,
+            community::CommunityQueryService, house::HouseQueryService,
+            house_duplicate::HouseDuplicateQueryService, house_export::HouseExportService,
+            house_history::HouseHistoryQueryService, house_price::HousePriceQueryService,
+            owner::OwnerQueryService, public_house::PublicHouseQueryService,
+            sync::SyncQueryService,
         },
         services::{
-            delete_community::DeleteCommunityService, delete_house::DeleteHouseService,
-            delete_owner::DeleteOwnerService, favorite::FavoriteService,
-            house_comment::HouseCommentService, house_operation_log,
-            save_community::SaveCommunityService, save_house::SaveHouseService,
+            delete_community::DeleteCommunityService,
+            delete_house::DeleteHouseService,
+            delete_owner::DeleteOwnerService,
+            favorite::FavoriteService,
+            house_batch::HouseBatchService,
+            house_comment::HouseCommentService,
+            house_import::{HouseImportService, IMPORT_MAX_BYTES},
+            house_operation_log,
+            house_recycle_bin::{HouseRecycleBinService, DEFAULT_RETENTION_DAYS},
+            house_status::HouseStatusService,
+            save_community::SaveCommunityService,
+            save_house::SaveHouseService,
             save_owner::SaveOwnerService,
         },
     },
     infrastructure::{
         community::mysql_community_aggregate::MySqlCommunityAggregateRepository,
         house::mysql_house_repository_aggregate::MysqlHouseRepositoryAggregate,
-        mysql_pool::create_mysql_pool, owner::mysql_owner_aggregate::MySqlOwnerAggregateRepository,
+        mysql_pool::{create_mysql_pool, create_user_system_pool},
+        owner::mysql_owner_aggregate::MySqlOwnerAggregateRepository,
+        search::house_index::HouseSearchIndex,
     },
     interfaces::controllers::{
         community::{delete_community, list_community, save_community},
+        event::{
+            get_event, list_consumer_dead_letters, list_dead_letters, list_events,
+            replay_consumer_dead_letter, replay_dead_letter, skip_consumer_dead_letter,
+        },
         favorites::{
             add_favorite_categories, add_user_favorites, cancel_user_favorites,
             check_user_favorites, delete_favorite_categories, find_favorite_categories,
             find_user_favorite, update_favorite_categories,
         },
         house::{
-            delete_house, get_house_detail, group_by_community, list_house_operation_log,
-            list_houses, save_house,
+            batch_houses, change_house_status, delete_house, export_houses, get_house_detail,
+            get_house_dictionary, get_house_history, get_house_price_history, get_house_status,
+            group_by_community, import_houses, list_duplicate_houses, list_house_operation_log,
+            list_houses, list_recycle_bin, map_aggregate, restore_house, save_house,
         },
         house_comment::{add_comment, delete_comment, get_comments, update_comment},
         owner::{delete_owner, owner_list, save_owner},
         public_house,
+        sync::sync_changes,
     },
 };
 
@@ -71,8 +102,13 @@ pub async fn execute() -> std::io::Result<()> {
         house_operation_log::HouseOperationLogService::new(pool.clone()),
     );
 
+    // 房源全文索引，目录由 HOUSE_SEARCH_INDEX_DIR 指定
+    let house_search_index = Arc::new(HouseSearchIndex::from_env().map_err(std::io::Error::other)?);
+
     // 公开房源信息
-    let public_house_service = web::Data::new(PublicHouseQueryService::new(pool.clone()));
+    let public_house_service = web::Data::new(
+        PublicHouseQueryService::new(pool.clone()).search_index(house_search_index.clone()),
+    );
 
     // 房源仓储
     let favorite_service = web::Data::new(FavoriteService::new(pool.clone()));
@@ -116,8 +152,19 @@ pub async fn execute() -> std::io::Result<()> {
     ));
 
     // 创建房源
-    let mysql_house_repository_aggregate =
-        Arc::new(MysqlHouseRepositoryAggregate::new(pool.clone()));
+    // HOUSE_EVENT_SOURCED=true 时从事件流重建房源聚合
+    let house_event_sourced = env::var("HOUSE_EVENT_SOURCED")
+        .map(|value| value == "true")
+        .unwrap_or(false);
+    let snapshot_interval = env::var("SNAPSHOT_INTERVAL")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(100);
+    let mysql_house_repository_aggregate = Arc::new(
+        MysqlHouseRepositoryAggregate::new(pool.clone())
+            .event_sourced(house_event_sourced)
+            .snapshot_interval(snapshot_interval),
+    );
 
     let save_house_command_handler =
         SaveHouseCommandHandler::new(mysql_house_repository_aggregate.clone(), event_bus.clone());
@@ -128,6 +175,28 @@ pub async fn execute() -> std::io::Result<()> {
         save_owner_service.clone().into_inner(),
     ));
 
+    // 疑似重复房源
+    let house_duplicate_query_service =
+        web::Data::new(HouseDuplicateQueryService::new(pool.clone()));
+
+    // 批量导入房源
+    let house_import_service = web::Data::new(HouseImportService::new(
+        pool.clone(),
+        save_house_service.clone().into_inner(),
+        mysql_house_repository_aggregate.clone(),
+        owner_repo.clone(),
+        house_duplicate_query_service.clone().into_inner(),
+    ));
+
+    // 房源状态变更
+    let house_status_service = web::Data::new(HouseStatusService::new(
+        ChangeHouseStatusCommandHandler::new(
+            mysql_house_repository_aggregate.clone(),
+            event_bus.clone(),
+        ),
+        mysql_house_repository_aggregate.clone(),
+    ));
+
     // 删除房源
     let delete_house_service = web::Data::new(DeleteHouseService::new(
         DeleteHouseCommandHandler::new(mysql_house_repository_aggregate.clone(), event_bus.clone()),
@@ -150,12 +219,92 @@ pub async fn execute() -> std::io::Result<()> {
     .subscribe(event_bus.clone());
 
     // 房源仓储
-    let house_query_service = web::Data::new(HouseQueryService::new(pool.clone()));
+    let house_query_service = web::Data::new(
+        HouseQueryService::new(pool.clone()).search_index(house_search_index.clone()),
+    );
     // 房源事件
     Arc::new(HouseEventListener::new(
         house_query_service.clone().into_inner(),
     ))
     .subscribe(event_bus.clone());
+    // 房源全文索引
+    Arc::new(HouseSearchListener::new(
+        pool.clone(),
+        house_search_index.clone(),
+    ))
+    .subscribe(event_bus.clone());
+    Arc::new(CommunitySearchListener::new(house_search_index.clone())).subscribe(event_bus.clone());
+
+    // 房源回收站，HOUSE_RETENTION_DAYS 天后彻底删除，配置 FILESTORE_URL 时一并删除图片，
+    // 调用时带上 FILESTORE_INTERNAL_TOKEN
+    let house_retention_days = env::var("HOUSE_RETENTION_DAYS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_RETENTION_DAYS);
+    let house_recycle_bin_service = web::Data::new(
+        HouseRecycleBinService::new(
+            pool.clone(),
+            RestoreHouseCommandHandler::new(
+                mysql_house_repository_aggregate.clone(),
+                event_bus.clone(),
+            ),
+            house_query_service.clone().into_inner(),
+        )
+        .retention_days(house_retention_days)
+        .filestore_url(env::var("FILESTORE_URL").ok())
+        .filestore_token(env::var("FILESTORE_INTERNAL_TOKEN").ok()),
+    );
+    house_recycle_bin_service.clone().into_inner().spawn();
+
+    // 房源导出
+    let house_export_service = web::Data::new(HouseExportService::new(
+        pool.clone(),
+        house_query_service.clone().into_inner(),
+        enforcer.clone(),
+    ));
+
+    // 批量操作房源
+    let house_batch_service = web::Data::new(HouseBatchService::new(
+        BatchHouseCommandHandler::new(mysql_house_repository_aggregate.clone(), event_bus.clone()),
+        house_query_service.clone().into_inner(),
+        house_operation_log_service.clone().into_inner(),
+    ));
+
+    // 房源历史版本
+    let house_history_query_service = web::Data::new(HouseHistoryQueryService::new(pool.clone()));
+
+    // 房源价格走势
+    let house_price_query_service = web::Data::new(HousePriceQueryService::new(pool.clone()));
+    Arc::new(HousePriceListener::new(
+        house_price_query_service.clone().into_inner(),
+    ))
+    .subscribe(event_bus.clone());
+
+    // 死信事件
+    let dead_letter_service =
+        web::Data::new(DeadLetterService::new(pool.clone(), event_bus.clone()));
+
+    // 事件浏览
+    let event_store_service = web::Data::new(EventStoreService::new(pool.clone()));
+
+    // 移动端增量同步
+    let sync_query_service = web::Data::new(SyncQueryService::new(pool.clone(), enforcer.clone()));
+
+    // 投递 outbox 中的事件
+    OutboxDispatcher::new(event_bus.clone(), pool.clone()).spawn();
+
+    // 消费 user_system 的事件，使用独立的事件总线，避免本地 outbox 投递认领这些事件类型
+    let user_system_bus = Arc::new(AsyncEventBus::new(None));
+    Arc::new(UserEventListener::new(
+        favorite_service.clone().into_inner(),
+    ))
+    .subscribe(user_system_bus.clone());
+    Arc::new(RoleEventListener::new(enforcer.clone())).subscribe(user_system_bus.clone());
+    let user_system_transport = Arc::new(MysqlPollingTransport::new(
+        create_user_system_pool().await,
+        pool.clone(),
+    ));
+    TransportConsumer::new("domus", user_system_transport, user_system_bus).spawn();
 
     HttpServer::new(move || {
         App::new()
@@ -165,13 +314,24 @@ pub async fn execute() -> std::io::Result<()> {
             .app_data(delete_owner_service.clone())
             .app_data(save_house_service.clone())
             .app_data(delete_house_service.clone())
+            .app_data(house_status_service.clone())
+            .app_data(house_import_service.clone())
+            .app_data(house_duplicate_query_service.clone())
+            .app_data(house_batch_service.clone())
+            .app_data(house_recycle_bin_service.clone())
             .app_data(community_query_service.clone())
             .app_data(owner_query_service.clone())
             .app_data(house_query_service.clone())
+            .app_data(house_history_query_service.clone())
+            .app_data(house_export_service.clone())
+            .app_data(house_price_query_service.clone())
             .app_data(hosue_comment_service.clone())
             .app_data(favorite_service.clone())
             .app_data(public_house_service.clone())
             .app_data(house_operation_log_service.clone())
+            .app_data(dead_letter_service.clone())
+            .app_data(event_store_service.clone())
+            .app_data(sync_query_service.clone())
             .service(
                 web::scope("/api/domus/management")
                     .service(
@@ -189,8 +349,13 @@ pub async fn execute() -> std::io::Result<()> {
                     .service(
                         web::scope("/house")
                             .wrap(auth_middleware.clone())
+                            .app_data(web::PayloadConfig::new(IMPORT_MAX_BYTES))
                             .service(save_house)
+                            .service(import_houses)
+                            .service(batch_houses)
                             .service(delete_house)
+                            .service(restore_house)
+                            .service(change_house_status)
                             .service(add_comment)
                             .service(update_comment)
                             .service(delete_comment)
@@ -211,19 +376,44 @@ pub async fn execute() -> std::io::Result<()> {
                             .service(list_houses)
                             .service(get_house_detail)
                             .service(group_by_community)
+                            .service(map_aggregate)
+                            .service(list_duplicate_houses)
+                            .service(list_recycle_bin)
+                            .service(export_houses)
                             .service(get_comments)
                             .service(find_favorite_categories)
                             .service(find_user_favorite)
                             .service(check_user_favorites)
-                            .service(list_house_operation_log),
+                            .service(list_house_operation_log)
+                            .service(get_house_history)
+                            .service(get_house_status)
+                            .service(get_house_price_history)
+                            .service(get_house_dictionary),
                     ),
             )
+            .service(
+                web::scope("/api/domus/event")
+                    .wrap(auth_middleware.clone())
+                    .service(list_dead_letters)
+                    .service(replay_dead_letter)
+                    .service(list_consumer_dead_letters)
+                    .service(replay_consumer_dead_letter)
+                    .service(skip_consumer_dead_letter)
+                    .service(list_events)
+                    .service(get_event),
+            )
+            .service(
+                web::scope("/api/domus/sync")
+                    .wrap(auth_middleware.clone())
+                    .service(sync_changes),
+            )
             .service(web::scope("/api/domus/house_comment").wrap(auth_middleware.clone()))
             .service(
                 web::scope("/api/domus/public").service(
                     web::scope("/house")
                         .service(public_house::list_houses)
-                        .service(public_house::get_house_detail),
+                        .service(public_house::get_house_detail)
+                        .service(get_house_dictionary),
                 ),
             )
     })
@@ -1,4 +1,4 @@
-use actix_web::{HttpResponse, Responder, get, post, web};
+use actix_web::{HttpRequest, HttpResponse, Responder, get, post, web};
 
 use crate::{
     api::dtos::response::ResponseBody,
@@ -14,6 +14,11 @@ struct Metadata {
     name: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct DeleteRequest {
+    names: Vec<String>,
+}
+
 #[derive(Debug, MultipartForm)]
 struct UploadForm {
     #[multipart(limit = "100MB")]
@@ -54,6 +59,44 @@ pub async fn upload_house_media(
     HttpResponse::Ok().json(ResponseBody::success("".to_string()))
 }
 
+// 服务间调用的令牌，请求头 X-Internal-Token 需要与 FILESTORE_INTERNAL_TOKEN 一致，未配置时拒绝
+fn is_internal(req: &HttpRequest) -> bool {
+    let Ok(token) = env::var("FILESTORE_INTERNAL_TOKEN") else {
+        return false;
+    };
+
+    !token.is_empty()
+        && req
+            .headers()
+            .get("X-Internal-Token")
+            .and_then(|value| value.to_str().ok())
+            == Some(token.as_str())
+}
+
+// 删除房源多媒体资源，房源彻底删除时由 domus 调用
+#[post("/delete_house_media")]
+pub async fn delete_house_media(
+    req: HttpRequest,
+    service: web::Data<UploadHouseMediaResourceService>,
+    body: web::Json<DeleteRequest>,
+) -> impl Responder {
+    if !is_internal(&req) {
+        return HttpResponse::Unauthorized()
+            .json(ResponseBody::<String>::error("Unauthorized".to_string()));
+    }
+
+    let names = body.into_inner().names;
+    tracing::info!("delete_house_media {} files", names.len());
+
+    match service.delete(names).await {
+        Ok(()) => HttpResponse::Ok().json(ResponseBody::success("".to_string())),
+        Err(err) => {
+            tracing::error!("delete_house_media error {}", err);
+            HttpResponse::Ok().json(ResponseBody::<String>::error(err.to_string()))
+        }
+    }
+}
+
 // 获取资源路径 bucket
 #[get("/get_house_media_resource_path")]
 pub async fn get_house_media_resource_path() -> impl Responder {
@@ -1,5 +1,5 @@
 use crate::{
-    api::upload::{get_house_media_resource_path, upload_house_media},
+    api::upload::{delete_house_media, get_house_media_resource_path, upload_house_media},
     service::upload_house_images::UploadHouseMediaResourceService,
 };
 use actix_multipart::form::{MultipartFormConfig, tempfile::TempFileConfig};
@@ -47,6 +47,7 @@ async fn main() -> std::io::Result<()> {
                 web::scope("/api/filestore")
                     // .wrap(auth_middleware.clone())
                     .service(upload_house_media)
+                    .service(delete_house_media)
                     .service(get_house_media_resource_path),
             )
             .wrap(Logger::default())
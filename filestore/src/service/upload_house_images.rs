@@ -1,4 +1,4 @@
-use minio::s3::{client::Client, types::S3Api};
+use minio::s3::{builders::ObjectToDelete, client::Client, types::S3Api};
 
 static MEIDA_HOUSE_IMAGES_BUCKET: &str = "domus-houses-images";
 
@@ -34,4 +34,44 @@ impl UploadHouseMediaResourceService {
 
         Ok(())
     }
+
+    // 删除房源多媒体资源，不存在的文件视为已删除。只接受房源图片桶内的对象名，有一个不合法时都不删除
+    pub async fn delete(&self, filenames: Vec<String>) -> anyhow::Result<()> {
+        if let Some(filename) = filenames.iter().find(|name| !is_media_object(name)) {
+            return Err(anyhow::anyhow!("文件名不合法: {}", filename));
+        }
+
+        for filename in filenames {
+            self.client
+                .delete_object(MEIDA_HOUSE_IMAGES_BUCKET, ObjectToDelete::from(filename))
+                .send()
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+// 房源图片桶内的对象名：相对路径，不能包含 .. 跳出前缀
+fn is_media_object(name: &str) -> bool {
+    !name.trim().is_empty()
+        && !name.starts_with(['/', '\\'])
+        && !name.chars().any(char::is_control)
+        && name.split(['/', '\\']).all(|part| part != "..")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_media_object;
+
+    #[test]
+    fn test_is_media_object() {
+        assert!(is_media_object("2025/b.jpg"));
+        assert!(is_media_object("a.jpg"));
+        assert!(!is_media_object(""));
+        assert!(!is_media_object("/etc/passwd"));
+        assert!(!is_media_object("../other-bucket/a.jpg"));
+        assert!(!is_media_object("2025/../../a.jpg"));
+        assert!(!is_media_object("2025\\..\\a.jpg"));
+    }
 }
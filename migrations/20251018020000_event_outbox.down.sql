@@ -0,0 +1 @@
+../crates/event_bus/migrations/20251018020000_event_outbox.down.sql
\ No newline at end of file
@@ -0,0 +1 @@
+../crates/event_bus/migrations/20251018030000_event_dead_letter.up.sql
\ No newline at end of file
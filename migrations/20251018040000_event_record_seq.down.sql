@@ -0,0 +1 @@
+../crates/event_bus/migrations/20251018040000_event_record_seq.down.sql
\ No newline at end of file
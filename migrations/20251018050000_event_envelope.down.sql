@@ -0,0 +1 @@
+../crates/event_bus/migrations/20251018050000_event_envelope.down.sql
\ No newline at end of file
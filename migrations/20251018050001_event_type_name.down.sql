@@ -0,0 +1,4 @@
+-- Add down migration script here
+UPDATE `event_record` SET `event_type` = 'user_system::domain::user::events::user::UserEvent' WHERE `event_type` = 'UserEvent';
+UPDATE `event_record` SET `event_type` = 'user_system::domain::user::events::login::LoginEvent' WHERE `event_type` = 'LoginEvent';
+UPDATE `event_record` SET `event_type` = 'user_system::domain::roles::events::role::RoleEvent' WHERE `event_type` = 'RoleEvent';
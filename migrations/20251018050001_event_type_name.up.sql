@@ -0,0 +1,32 @@
+-- Add up migration script here
+-- 事件名从 Rust 类型路径改为稳定名称，并补齐已有事件的聚合 id
+UPDATE `event_record`
+SET `event_type` = 'UserEvent',
+    `aggregate_type` = 'user',
+    `aggregate_id` = COALESCE(
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.UserRegistered.id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.UserUpdated.id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.UserBindedToRoles.user_id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.UserDeleted.id')),
+      '')
+WHERE `event_type` = 'user_system::domain::user::events::user::UserEvent';
+
+UPDATE `event_record`
+SET `event_type` = 'LoginEvent',
+    `aggregate_type` = 'user',
+    `aggregate_id` = COALESCE(
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.Success.user_id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.Fail.user_id')),
+      '')
+WHERE `event_type` = 'user_system::domain::user::events::login::LoginEvent';
+
+UPDATE `event_record`
+SET `event_type` = 'RoleEvent',
+    `aggregate_type` = 'role',
+    `aggregate_id` = COALESCE(
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.RoleCreated.id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.RoleUpdated.id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.RoleDeleted.id')),
+      JSON_UNQUOTE(JSON_EXTRACT(`payload`, '$.PermissionGrantedToRole.role_id')),
+      '')
+WHERE `event_type` = 'user_system::domain::roles::events::role::RoleEvent';
@@ -0,0 +1 @@
+../crates/event_bus/migrations/20251018060000_event_schema_version.up.sql
\ No newline at end of file
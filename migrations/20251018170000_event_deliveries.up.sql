@@ -0,0 +1 @@
+../crates/event_bus/migrations/20251018170000_event_deliveries.up.sql
\ No newline at end of file
@@ -0,0 +1 @@
+../crates/event_bus/migrations/20251018180000_event_status_published.up.sql
\ No newline at end of file
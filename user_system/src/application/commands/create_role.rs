@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 use serde::Deserialize;
 
 use crate::{
@@ -32,8 +32,10 @@ impl CreateRoleCommandHandler {
 
     pub async fn handle(&self, command: CreateRoleCommand) -> anyhow::Result<String> {
         let (role, event) = RoleAggregate::create(command.name, command.description);
-        self.role_repository.create(&role).await?;
-        self.event_bus.persist_and_publish(event).await?;
+        self.role_repository
+            .create(&role, &[OutboxEvent::from_event(&event)])
+            .await?;
+        self.event_bus.notify();
 
         Ok(role.id)
     }
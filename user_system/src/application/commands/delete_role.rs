@@ -1,4 +1,4 @@
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 use std::sync::Arc;
 
 use crate::application::repositories::role::RoleRepository;
@@ -30,8 +30,10 @@ impl DeleteRoleCommandHandler {
         let mut role = self.role_repository.find_by_id(&command.id).await?;
         let event = role.delete();
 
-        self.role_repository.save(&role).await?;
-        self.event_bus.persist_and_publish(event).await?;
+        self.role_repository
+            .save(&role, &[OutboxEvent::from_event(&event)])
+            .await?;
+        self.event_bus.notify();
 
         Ok(())
     }
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 
 use crate::application::repositories::user_aggreate_repository::UserAggregateRepository;
 
@@ -32,10 +32,13 @@ impl DeleteUserCommandHandler {
 
         let user_deleted_event = user_aggregate.delete();
 
-        self.user_poll.save(&user_aggregate).await?;
-        self.event_bus
-            .persist_and_publish(user_deleted_event)
+        self.user_poll
+            .save(
+                &user_aggregate,
+                &[OutboxEvent::from_event(&user_deleted_event)],
+            )
             .await?;
+        self.event_bus.notify();
         Ok(())
     }
 }
@@ -5,7 +5,10 @@ use sea_orm::{ActiveValue::Set, DbConn, EntityTrait};
 
 use crate::{
     application::{
-        commands::{create_role::CreateRoleCommand, register_user::RegisterUserCommand},
+        commands::{
+            create_role::CreateRoleCommand, rebuild_projections::Projection,
+            register_user::RegisterUserCommand,
+        },
         services::{create_role::CreateRoleService, register_user::RegisterUserService},
     },
     domain::roles::events::permission_granted_to_role::Permission,
@@ -28,6 +31,17 @@ pub enum InitSystemCommand {
         #[arg(long, default_value = "admin")]
         admin_password: String,
     },
+    /// 清空读模型，按顺序回放 event_record 重建
+    RebuildProjections {
+        #[arg(long, value_enum, default_value = "all")]
+        projection: Projection,
+        /// 只统计，不写入
+        #[arg(long)]
+        dry_run: bool,
+        /// 读模型中有没有事件的数据时仍然重建，这些数据会丢失
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 pub struct InitSystemCommandHandler {
@@ -135,6 +149,30 @@ impl InitSystemCommandHandler {
                     description: Set(Some("获取用户信息".to_string())),
                     ..Default::default()
                 },
+                permissions_detail::ActiveModel {
+                    id: Set(uuid::Uuid::new_v4().to_string()),
+                    name: Set("事件管理".to_string()),
+                    source: Set("^/api/user_system/event/dead_letter/*".to_string()),
+                    action: Set("POST".to_string()),
+                    description: Set(Some("重新投递死信事件".to_string())),
+                    ..Default::default()
+                },
+                permissions_detail::ActiveModel {
+                    id: Set(uuid::Uuid::new_v4().to_string()),
+                    name: Set("事件查询".to_string()),
+                    source: Set("^/api/user_system/event/dead_letter/*".to_string()),
+                    action: Set("GET".to_string()),
+                    description: Set(Some("查询死信事件".to_string())),
+                    ..Default::default()
+                },
+                permissions_detail::ActiveModel {
+                    id: Set(uuid::Uuid::new_v4().to_string()),
+                    name: Set("事件浏览".to_string()),
+                    source: Set("^/api/user_system/event/store/*".to_string()),
+                    action: Set("GET".to_string()),
+                    description: Set(Some("按条件浏览事件存储".to_string())),
+                    ..Default::default()
+                },
             ];
 
             permissions_detail::Entity::insert_many(permissions)
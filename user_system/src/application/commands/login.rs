@@ -3,7 +3,7 @@ use crate::{
     application::repositories::user_aggreate_repository::UserAggregateRepository,
     domain::user::aggregates::user::UserAggregate,
 };
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use user_system::shared::claims::Claims;
@@ -75,12 +75,12 @@ impl LoginCommandHandler {
         let login_event = user.login(command.username.as_str(), command.password.as_str());
 
         // 保存用户聚合
-        self.user_repo.save(&user).await?;
+        self.user_repo
+            .save(&user, &[OutboxEvent::from_event(&login_event)])
+            .await?;
 
         // 发送事件
-        self.event_bus
-            .persist_and_publish(login_event.clone())
-            .await?;
+        self.event_bus.notify();
 
         if let LoginEvent::Success(_) = login_event {
             // 生成token
@@ -4,6 +4,7 @@ pub mod delete_user;
 pub mod init_system;
 pub mod login;
 pub mod permission_granted_to_role;
+pub mod rebuild_projections;
 pub mod register_user;
 pub mod update_role;
 pub mod update_user;
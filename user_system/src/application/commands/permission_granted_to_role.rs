@@ -4,7 +4,7 @@ use crate::{
     application::repositories::role::RoleRepository,
     domain::roles::events::permission_granted_to_role::Permission,
 };
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 use serde::Deserialize;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -44,8 +44,10 @@ impl PermissionGrantedToRoleCommandHandler {
 
         let event = role_aggregate.grant_permissions(command.permissions);
 
-        self.role_repository.save(&role_aggregate).await?;
-        self.event_bus.persist_and_publish(event).await?;
+        self.role_repository
+            .save(&role_aggregate, &[OutboxEvent::from_event(&event)])
+            .await?;
+        self.event_bus.notify();
         Ok(())
     }
 }
@@ -0,0 +1,196 @@
+use std::sync::Arc;
+
+use clap::ValueEnum;
+use event_bus::{AsyncEventBus, EventListener, EventReplayer, TableBackup};
+use sea_orm::{DbConn, EntityName, EntityTrait, PaginatorTrait};
+
+use crate::{
+    application::{
+        listeners::{role::RoleEventListener, user::UserEventListener},
+        queries::{role_query_service::RoleQueryService, user_query_service::UserQueryService},
+    },
+    infrastructure::entitiy::{role_detail_read_model, user_query},
+};
+
+// 可以重建的读模型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Projection {
+    All,
+    User,
+    Role,
+}
+
+impl Projection {
+    fn expand(self) -> Vec<Projection> {
+        match self {
+            Projection::All => vec![Projection::User, Projection::Role],
+            projection => vec![projection],
+        }
+    }
+
+    fn table_name(self) -> &'static str {
+        match self {
+            Projection::All => "*",
+            Projection::User => user_query::Entity.table_name(),
+            Projection::Role => role_detail_read_model::Entity.table_name(),
+        }
+    }
+}
+
+pub struct RebuildProjectionsCommand {
+    pub projection: Projection,
+    pub dry_run: bool,
+    // 有事件的聚合少于读模型的行数时仍然重建
+    pub force: bool,
+}
+
+pub struct RebuildProjectionsCommandHandler {
+    pool: Arc<DbConn>,
+}
+
+impl RebuildProjectionsCommandHandler {
+    pub fn new(pool: Arc<DbConn>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn handle(&self, command: RebuildProjectionsCommand) -> anyhow::Result<()> {
+        let projections = command.projection.expand();
+
+        // 先检查全部读模型，避免重建到一半才发现事件不全
+        for projection in &projections {
+            self.check(*projection, command.dry_run, command.force)
+                .await?;
+        }
+        if command.dry_run {
+            return Ok(());
+        }
+
+        for projection in projections {
+            self.rebuild(projection).await?;
+        }
+
+        Ok(())
+    }
+
+    fn event_bus(&self, projection: Projection) -> Arc<AsyncEventBus> {
+        // 只订阅这个读模型的监听器，登录历史不是由事件重建的
+        let event_bus = Arc::new(AsyncEventBus::new(None));
+        self.subscribe(projection, event_bus.clone());
+        event_bus
+    }
+
+    // 读模型里的聚合比有事件的聚合多，说明有数据早于事件流，清空后回放会丢失
+    async fn check(
+        &self,
+        projection: Projection,
+        dry_run: bool,
+        force: bool,
+    ) -> anyhow::Result<()> {
+        let table_name = projection.table_name();
+        let event_bus = self.event_bus(projection);
+
+        let replayer = EventReplayer::new(self.pool.clone());
+        let total = replayer.count(&event_bus).await?;
+        let aggregates = replayer.count_aggregates(&event_bus).await?;
+        // 用户和角色的读模型都是一个聚合一行
+        let rows = self.count_rows(projection).await?;
+        let read_aggregates = rows;
+
+        if dry_run {
+            println!(
+                "[dry-run] {table_name}: 将清空 {rows} 行，回放 {aggregates} 个聚合的 {total} 个事件"
+            );
+        }
+
+        if aggregates < read_aggregates {
+            let message = format!(
+                "{table_name}: 读模型中有 {read_aggregates} 个聚合，只有 {aggregates} 个聚合有事件，重建会丢失其余数据"
+            );
+            if !force {
+                return Err(anyhow::anyhow!("{message}，确认后加 --force"));
+            }
+            println!("{message}，已指定 --force");
+        }
+
+        Ok(())
+    }
+
+    async fn rebuild(&self, projection: Projection) -> anyhow::Result<()> {
+        let table_name = projection.table_name();
+        let event_bus = self.event_bus(projection);
+
+        let replayer = EventReplayer::new(self.pool.clone());
+        let total = replayer.count(&event_bus).await?;
+        let rows = self.count_rows(projection).await?;
+
+        // 先备份，回放失败时换回原表
+        let backup = TableBackup::create(self.pool.as_ref(), table_name).await?;
+        self.truncate(projection).await?;
+        println!("{table_name}: 已清空 {rows} 行，开始回放 {total} 个事件");
+
+        let result = replayer
+            .replay(&event_bus, |replayed| {
+                println!("{table_name}: 已回放 {replayed}/{total}");
+            })
+            .await;
+
+        match result {
+            Ok(replayed) => {
+                backup.drop(self.pool.as_ref()).await?;
+                println!("{table_name}: 重建完成，共回放 {replayed} 个事件");
+                Ok(())
+            }
+            Err(err) => {
+                backup.restore(self.pool.as_ref()).await?;
+                println!("{table_name}: 回放失败，已恢复原数据");
+                Err(err)
+            }
+        }
+    }
+
+    fn subscribe(&self, projection: Projection, event_bus: Arc<AsyncEventBus>) {
+        match projection {
+            Projection::All => {}
+            Projection::User => {
+                Arc::new(UserEventListener::new(Arc::new(UserQueryService::new(
+                    self.pool.clone(),
+                ))))
+                .subscribe(event_bus);
+            }
+            Projection::Role => {
+                Arc::new(RoleEventListener::new(Arc::new(RoleQueryService::new(
+                    self.pool.clone(),
+                ))))
+                .subscribe(event_bus);
+            }
+        }
+    }
+
+    async fn count_rows(&self, projection: Projection) -> anyhow::Result<u64> {
+        let pool = self.pool.as_ref();
+        let rows = match projection {
+            Projection::All => 0,
+            Projection::User => user_query::Entity::find().count(pool).await?,
+            Projection::Role => role_detail_read_model::Entity::find().count(pool).await?,
+        };
+
+        Ok(rows)
+    }
+
+    async fn truncate(&self, projection: Projection) -> anyhow::Result<()> {
+        let pool = self.pool.as_ref();
+        match projection {
+            Projection::All => {}
+            Projection::User => {
+                user_query::Entity::delete_many().exec(pool).await?;
+            }
+            Projection::Role => {
+                role_detail_read_model::Entity::delete_many()
+                    .exec(pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
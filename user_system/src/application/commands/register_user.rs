@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -58,10 +58,12 @@ impl UserRegistrationHandler {
         )?;
 
         // 创建新的用户聚合
-        self.user_repo.create(&user).await?;
+        self.user_repo
+            .create(&user, &[OutboxEvent::from_event(&event)])
+            .await?;
 
-        // 发布事件到事件总线（简化的逻辑）
-        self.event_bus.persist_and_publish(event).await?;
+        // 唤醒 outbox 投递事件
+        self.event_bus.notify();
 
         Ok(user)
     }
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 use serde::Deserialize;
 
 use crate::{
@@ -32,8 +32,10 @@ impl UpdateRoleCommandHandler {
     pub async fn handle(&self, command: UpdateRoleCommand) -> anyhow::Result<()> {
         let mut role = self.role_repository.find_by_id(&command.id).await?;
         let event = role.update(command.name, command.description);
-        self.role_repository.save(&role).await?;
-        self.event_bus.persist_and_publish(event).await?;
+        self.role_repository
+            .save(&role, &[OutboxEvent::from_event(&event)])
+            .await?;
+        self.event_bus.notify();
 
         Ok(())
     }
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 use serde::Deserialize;
 
 use crate::{
@@ -42,8 +42,10 @@ impl UpdateUserCommandHandler {
             command.roles,
         );
 
-        self.user_pool.save(&user_aggregate).await?;
-        self.event_bus.persist_and_publish(user_event).await?;
+        self.user_pool
+            .save(&user_aggregate, &[OutboxEvent::from_event(&user_event)])
+            .await?;
+        self.event_bus.notify();
 
         Ok(user_aggregate)
     }
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use event_bus::AsyncEventBus;
+use event_bus::{AsyncEventBus, OutboxEvent};
 
 use crate::application::repositories::user_aggreate_repository::UserAggregateRepository;
 
@@ -34,8 +34,10 @@ impl UserBindedToRolesHandler {
 
         user_aggregate.bind_roles(command.roles);
 
-        self.user_pool.save(&user_aggregate).await?;
-        self.event_bus.persist_and_publish(event).await?;
+        self.user_pool
+            .save(&user_aggregate, &[OutboxEvent::from_event(&event)])
+            .await?;
+        self.event_bus.notify();
         Ok(())
     }
 }
@@ -2,7 +2,7 @@ use crate::{
     application::queries::user_query_service::UserQueryService,
     domain::user::events::login::LoginEvent,
 };
-use event_bus::EventListener;
+use event_bus::{EventEnvelope, EventListener};
 use std::sync::Arc;
 
 pub struct LoginEventListener {
@@ -17,10 +17,11 @@ impl LoginEventListener {
 
 #[async_trait::async_trait]
 impl EventListener<LoginEvent> for LoginEventListener {
-    async fn handle(&self, event: LoginEvent) {
+    async fn handle(&self, event: EventEnvelope<LoginEvent>) -> anyhow::Result<()> {
         self.user_query_service
-            .save_user_login_history(&event)
-            .await
-            .unwrap();
+            .save_user_login_history(&event.payload)
+            .await?;
+
+        Ok(())
     }
 }
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use event_bus::EventListener;
+use event_bus::{EventEnvelope, EventListener};
 
 use crate::{
     application::queries::role_query_service::RoleQueryService,
@@ -19,23 +19,22 @@ impl RoleEventListener {
 
 #[async_trait::async_trait]
 impl EventListener<RoleEvent> for RoleEventListener {
-    async fn handle(&self, event: RoleEvent) {
-        match event {
+    async fn handle(&self, event: EventEnvelope<RoleEvent>) -> anyhow::Result<()> {
+        match event.payload {
             RoleEvent::RoleCreated(event) => {
-                self.role_query_service.create(event).await.unwrap();
+                self.role_query_service.create(event).await?;
             }
             RoleEvent::RoleUpdated(event) => {
-                self.role_query_service.update(event).await.unwrap();
+                self.role_query_service.update(event).await?;
             }
             RoleEvent::RoleDeleted(event) => {
-                self.role_query_service.delete(event).await.unwrap();
+                self.role_query_service.delete(event).await?;
             }
             RoleEvent::PermissionGrantedToRole(event) => {
-                self.role_query_service
-                    .bind_permissions(&event)
-                    .await
-                    .unwrap();
+                self.role_query_service.bind_permissions(&event).await?;
             }
         }
+
+        Ok(())
     }
 }
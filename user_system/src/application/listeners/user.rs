@@ -1,4 +1,4 @@
-use event_bus::EventListener;
+use event_bus::{EventEnvelope, EventListener};
 use std::sync::Arc;
 
 use crate::{
@@ -18,26 +18,25 @@ impl UserEventListener {
 
 #[async_trait::async_trait]
 impl EventListener<UserEvent> for UserEventListener {
-    async fn handle(&self, event: UserEvent) {
-        match event {
+    async fn handle(&self, event: EventEnvelope<UserEvent>) -> anyhow::Result<()> {
+        match event.payload {
             UserEvent::UserRegistered(event) => {
-                self.user_query_service.save_user(&event).await.unwrap();
+                self.user_query_service.save_user(&event).await?;
             }
 
             UserEvent::UserUpdated(event) => {
-                self.user_query_service.update_user(&event).await.unwrap();
+                self.user_query_service.update_user(&event).await?;
             }
 
             UserEvent::UserDeleted(event) => {
-                self.user_query_service
-                    .delete_user(&event.id)
-                    .await
-                    .unwrap();
+                self.user_query_service.delete_user(&event.id).await?;
             }
 
             UserEvent::UserBindedToRoles(event) => {
-                self.user_query_service.bind_roles(&event).await.unwrap();
+                self.user_query_service.bind_roles(&event).await?;
             }
         }
+
+        Ok(())
     }
 }
@@ -1,8 +1,19 @@
+use event_bus::OutboxEvent;
+
 use crate::domain::roles::aggregates::role::RoleAggregate;
 
 #[async_trait::async_trait]
 pub trait RoleRepository: Send + Sync {
-    async fn create(&self, command: &RoleAggregate) -> anyhow::Result<RoleAggregate>;
-    async fn save(&self, command: &RoleAggregate) -> anyhow::Result<RoleAggregate>;
+    // 事件和聚合在同一个事务里写入 outbox
+    async fn create(
+        &self,
+        command: &RoleAggregate,
+        events: &[OutboxEvent],
+    ) -> anyhow::Result<RoleAggregate>;
+    async fn save(
+        &self,
+        command: &RoleAggregate,
+        events: &[OutboxEvent],
+    ) -> anyhow::Result<RoleAggregate>;
     async fn find_by_id(&self, id: &str) -> anyhow::Result<RoleAggregate>;
 }
@@ -1,14 +1,29 @@
-use serde::Serialize;
+use event_bus::Event;
+use serde::{Deserialize, Serialize};
 
 use crate::domain::roles::events::{
     permission_granted_to_role::PermissionGrantedToRoleEvent, role_created::RoleCreatedEvent,
     role_deleted::RoleDeletedEvent, role_updated::RoleUpdatedEvent,
 };
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RoleEvent {
     RoleCreated(RoleCreatedEvent),
     RoleUpdated(RoleUpdatedEvent),
     RoleDeleted(RoleDeletedEvent),
     PermissionGrantedToRole(PermissionGrantedToRoleEvent),
 }
+
+impl Event for RoleEvent {
+    const NAME: &'static str = "RoleEvent";
+    const AGGREGATE_TYPE: &'static str = "role";
+
+    fn aggregate_id(&self) -> String {
+        match self {
+            RoleEvent::RoleCreated(event) => event.id.clone(),
+            RoleEvent::RoleUpdated(event) => event.id.clone(),
+            RoleEvent::RoleDeleted(event) => event.id.clone(),
+            RoleEvent::PermissionGrantedToRole(event) => event.role_id.clone(),
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use event_bus::Event;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoginEventSuccess {
     pub user_id: String,
     pub username: String,
@@ -25,7 +26,7 @@ impl LoginEventSuccess {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoginEventFail {
     pub user_id: String,
     pub username: String,
@@ -49,8 +50,20 @@ impl LoginEventFail {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum LoginEvent {
     Success(LoginEventSuccess),
     Fail(LoginEventFail),
 }
+
+impl Event for LoginEvent {
+    const NAME: &'static str = "LoginEvent";
+    const AGGREGATE_TYPE: &'static str = "user";
+
+    fn aggregate_id(&self) -> String {
+        match self {
+            LoginEvent::Success(event) => event.user_id.clone(),
+            LoginEvent::Fail(event) => event.user_id.clone(),
+        }
+    }
+}
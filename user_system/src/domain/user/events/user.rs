@@ -1,14 +1,29 @@
-use serde::Serialize;
+use event_bus::Event;
+use serde::{Deserialize, Serialize};
 
 use crate::domain::user::events::{
     user_binded_to_roles::UserBindedToRolesEvent, user_deleted::UserDeletedEvent,
     user_registered::UserRegisteredEvent, user_updated::UserUpdatedEvent,
 };
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum UserEvent {
     UserRegistered(UserRegisteredEvent),
     UserUpdated(UserUpdatedEvent),
     UserBindedToRoles(UserBindedToRolesEvent),
     UserDeleted(UserDeletedEvent),
 }
+
+impl Event for UserEvent {
+    const NAME: &'static str = "UserEvent";
+    const AGGREGATE_TYPE: &'static str = "user";
+
+    fn aggregate_id(&self) -> String {
+        match self {
+            UserEvent::UserRegistered(event) => event.id.to_string(),
+            UserEvent::UserUpdated(event) => event.id.clone(),
+            UserEvent::UserBindedToRoles(event) => event.user_id.clone(),
+            UserEvent::UserDeleted(event) => event.id.clone(),
+        }
+    }
+}
@@ -6,11 +6,13 @@ use crate::{
     domain::roles::aggregates::role::RoleAggregate, infrastructure::entitiy::role_aggregate,
 };
 use casbin::{Enforcer, MgmtApi};
+use event_bus::OutboxEvent;
 use sea_orm::ActiveModelTrait;
 use sea_orm::ColumnTrait;
 use sea_orm::Condition;
 use sea_orm::EntityTrait;
 use sea_orm::QueryFilter;
+use sea_orm::TransactionTrait;
 use sea_orm::{ActiveValue::Set, DbConn};
 use tokio::sync::Mutex;
 use user_system::shared::entitiy::casbin_rules;
@@ -40,7 +42,11 @@ impl MySqlRoleAggregateRepository {
 
 #[async_trait::async_trait]
 impl RoleRepository for MySqlRoleAggregateRepository {
-    async fn create(&self, command: &RoleAggregate) -> anyhow::Result<RoleAggregate> {
+    async fn create(
+        &self,
+        command: &RoleAggregate,
+        events: &[OutboxEvent],
+    ) -> anyhow::Result<RoleAggregate> {
         let model = role_aggregate::ActiveModel {
             id: Set(command.id.clone()),
             name: Set(command.name.clone()),
@@ -48,7 +54,11 @@ impl RoleRepository for MySqlRoleAggregateRepository {
             ..Default::default()
         };
 
-        let mut result: RoleAggregate = model.insert(self.pool.as_ref()).await?.into();
+        let txn = self.pool.begin().await?;
+        let mut result: RoleAggregate = model.insert(&txn).await?.into();
+        OutboxEvent::append(&txn, events).await?;
+        txn.commit().await?;
+
         let mut paramss = vec![];
         // 创建权限
         for p in command.permissions.iter() {
@@ -61,7 +71,11 @@ impl RoleRepository for MySqlRoleAggregateRepository {
         Ok(result)
     }
 
-    async fn save(&self, command: &RoleAggregate) -> anyhow::Result<RoleAggregate> {
+    async fn save(
+        &self,
+        command: &RoleAggregate,
+        events: &[OutboxEvent],
+    ) -> anyhow::Result<RoleAggregate> {
         let model = role_aggregate::ActiveModel {
             id: Set(command.id.clone()),
             name: Set(command.name.clone()),
@@ -70,7 +84,10 @@ impl RoleRepository for MySqlRoleAggregateRepository {
             ..Default::default()
         };
 
-        let mut result: RoleAggregate = model.update(self.pool.as_ref()).await?.into();
+        let txn = self.pool.begin().await?;
+        let mut result: RoleAggregate = model.update(&txn).await?.into();
+        OutboxEvent::append(&txn, events).await?;
+        txn.commit().await?;
 
         let remove_rules = casbin_rules::Entity::find()
             .filter(casbin_rules::Column::V0.eq(command.id.clone()))
@@ -3,6 +3,7 @@ use crate::{
     domain::user::aggregates::user::UserAggregate, infrastructure::entitiy,
 };
 use casbin::MgmtApi;
+use event_bus::OutboxEvent;
 use sea_orm::*;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -33,7 +34,11 @@ impl MySqlUserAggregateRepository {
 
 #[async_trait::async_trait]
 impl UserAggregateRepository for MySqlUserAggregateRepository {
-    async fn create(&self, input_user: &UserAggregate) -> anyhow::Result<()> {
+    async fn create(
+        &self,
+        input_user: &UserAggregate,
+        events: &[OutboxEvent],
+    ) -> anyhow::Result<()> {
         // 保存用户聚合
         let user = entitiy::user_aggregate::ActiveModel {
             id: Set(input_user.id.to_string()),
@@ -55,11 +60,14 @@ impl UserAggregateRepository for MySqlUserAggregateRepository {
             let _ = enforcer.add_grouping_policy(vec![v0, v1]).await?;
         }
 
-        user.insert(self.pool.as_ref()).await?;
+        let txn = self.pool.begin().await?;
+        user.insert(&txn).await?;
+        OutboxEvent::append(&txn, events).await?;
+        txn.commit().await?;
         Ok(())
     }
 
-    async fn save(&self, input_user: &UserAggregate) -> anyhow::Result<()> {
+    async fn save(&self, input_user: &UserAggregate, events: &[OutboxEvent]) -> anyhow::Result<()> {
         // 保存用户聚合
         let user = entitiy::user_aggregate::ActiveModel {
             id: Set(input_user.id.to_string()),
@@ -88,7 +96,10 @@ impl UserAggregateRepository for MySqlUserAggregateRepository {
             let _ = enforcer.add_grouping_policy(vec![v0, v1]).await?;
         }
 
-        user.update(self.pool.as_ref()).await?;
+        let txn = self.pool.begin().await?;
+        user.update(&txn).await?;
+        OutboxEvent::append(&txn, events).await?;
+        txn.commit().await?;
         Ok(())
     }
 
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use actix_web::web;
-use event_bus::{AsyncEventBus, EventListener};
+use event_bus::{AsyncEventBus, EventListener, OutboxDispatcher};
 use sea_orm::DbConn;
 use tokio::sync::Mutex;
 use user_system::shared::casbin::init_casbin::init_casbin;
@@ -45,6 +45,11 @@ pub async fn execute(admin_name: String, admin_password: String) -> std::io::Res
         InitSystemCommandHandler::new(pool.clone(), register_user_services, create_role_services);
 
     handler.handle(admin_name, admin_password).await.unwrap();
+
+    // 退出前把 outbox 中的事件投递完，保证读模型已更新
+    let dispatcher = OutboxDispatcher::new(event_bus.clone(), pool.clone());
+    while dispatcher.dispatch_pending().await.unwrap() > 0 {}
+
     Ok(())
 }
 
@@ -0,0 +1,68 @@
+use actix_web::{get, post, web, HttpResponse};
+use event_bus::{
+    dead_letter::DeadLetterService,
+    store::{EventQuery, EventStoreService},
+};
+use shared_dto::table_data::TableDataRequest;
+
+use crate::interfaces::dtos::response::ResponseBody;
+
+/// 查询死信事件
+#[get("/dead_letter/list")]
+async fn list_dead_letters(
+    table_data_request: web::Query<TableDataRequest>,
+    service: web::Data<DeadLetterService>,
+) -> HttpResponse {
+    let res = match service.find_all(table_data_request.into_inner()).await {
+        Ok(data) => ResponseBody::success(data),
+        Err(err) => ResponseBody::error(err.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
+
+/// 重新投递死信事件
+#[post("/dead_letter/replay/{event_id}")]
+async fn replay_dead_letter(
+    event_id: web::Path<String>,
+    service: web::Data<DeadLetterService>,
+) -> HttpResponse {
+    let res = match service.replay(&event_id).await {
+        Ok(data) => ResponseBody::success(data),
+        Err(err) => ResponseBody::error(err.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
+
+/// 按条件浏览事件
+#[get("/store/list")]
+async fn list_events(
+    table_data_request: web::Query<TableDataRequest>,
+    query: web::Query<EventQuery>,
+    service: web::Data<EventStoreService>,
+) -> HttpResponse {
+    let res = match service
+        .find_all(query.into_inner(), table_data_request.into_inner())
+        .await
+    {
+        Ok(data) => ResponseBody::success(data),
+        Err(err) => ResponseBody::error(err.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
+
+/// 查看单个事件
+#[get("/store/{event_id}")]
+async fn get_event(
+    event_id: web::Path<String>,
+    service: web::Data<EventStoreService>,
+) -> HttpResponse {
+    let res = match service.find_by_id(&event_id).await {
+        Ok(data) => ResponseBody::success(data),
+        Err(err) => ResponseBody::error(err.to_string()),
+    };
+
+    HttpResponse::Ok().json(res)
+}
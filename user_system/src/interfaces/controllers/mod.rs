@@ -1,3 +1,4 @@
+pub mod event;
 pub mod role;
 pub mod user;
 pub mod user_query;
@@ -3,6 +3,7 @@ mod domain;
 mod infrastructure;
 mod init_system;
 mod interfaces;
+mod rebuild_projections;
 mod start_http_server;
 use clap::Parser;
 
@@ -22,6 +23,11 @@ async fn main() -> std::io::Result<()> {
                 .unwrap();
             Ok(())
         }
+        Some(InitSystemCommand::RebuildProjections {
+            projection,
+            dry_run,
+            force,
+        }) => rebuild_projections::execute(projection, dry_run, force).await,
         None => {
             // 启动 HTTP 服务
 
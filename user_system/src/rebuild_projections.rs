@@ -0,0 +1,26 @@
+use crate::{
+    application::commands::rebuild_projections::{
+        Projection, RebuildProjectionsCommand, RebuildProjectionsCommandHandler,
+    },
+    infrastructure::mysql_pool::create_mysql_pool,
+};
+
+pub async fn execute(projection: Projection, dry_run: bool, force: bool) -> std::io::Result<()> {
+    dotenvy::dotenv().ok();
+    let pool = create_mysql_pool().await;
+
+    // 回放期间不要有新的事件写入读模型
+    println!("开始重建读模型，请先停止 HTTP 服务...");
+    let handler = RebuildProjectionsCommandHandler::new(pool);
+
+    handler
+        .handle(RebuildProjectionsCommand {
+            projection,
+            dry_run,
+            force,
+        })
+        .await
+        .unwrap();
+
+    Ok(())
+}
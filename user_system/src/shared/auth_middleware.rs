@@ -6,6 +6,7 @@ use actix_web::{
 };
 use casbin::CoreApi;
 use casbin::Enforcer;
+use event_bus::EventContext;
 use futures::future::{ok, LocalBoxFuture, Ready};
 use std::rc::Rc;
 use std::sync::Arc;
@@ -83,10 +84,19 @@ where
             // 2. 保存 Claims 到请求中
             req.extensions_mut().insert(claims.clone());
 
+            // 请求内产生的事件记录操作人和请求 id
+            let request_id = req
+                .headers()
+                .get("X-Request-Id")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+            let context = EventContext::new(Some(claims.user_id.clone()), Some(request_id));
+
             // 开始验证权限
             // 如果是个人信息,直接放行
             if req.path().contains("/api/user_system/user_profile") {
-                return srv.call(req).await;
+                return context.scope(srv.call(req)).await;
             }
 
             // 3. 构造 Casbin 的三元组 (sub, obj, act)
@@ -103,7 +113,7 @@ where
                     .unwrap();
 
                 if allowed {
-                    return srv.call(req).await;
+                    return context.scope(srv.call(req)).await;
                 }
             }
 
@@ -27,6 +27,7 @@ use crate::{
         user::user_aggregate_repository::MySqlUserAggregateRepository,
     },
     interfaces::controllers::{
+        event::{get_event, list_dead_letters, list_events, replay_dead_letter},
         role::{
             create_role, delete_role, detail_role, list_role, permissions_details_list,
             save_permission_detail, update_role,
@@ -36,7 +37,10 @@ use crate::{
     },
 };
 use actix_web::{web, App, HttpServer};
-use event_bus::{AsyncEventBus, EventListener};
+use event_bus::{
+    dead_letter::DeadLetterService, store::EventStoreService, AsyncEventBus, EventListener,
+    OutboxDispatcher,
+};
 
 pub async fn run() -> std::io::Result<()> {
     log::init_tracing();
@@ -119,6 +123,16 @@ pub async fn run() -> std::io::Result<()> {
         role_query_service,
     } = QueryService::register_event_handlers(pool.clone(), event_bus.clone());
 
+    // 死信事件
+    let dead_letter_service =
+        web::Data::new(DeadLetterService::new(pool.clone(), event_bus.clone()));
+
+    // 事件浏览
+    let event_store_service = web::Data::new(EventStoreService::new(pool.clone()));
+
+    // 投递 outbox 中的事件
+    OutboxDispatcher::new(event_bus.clone(), pool.clone()).spawn();
+
     HttpServer::new(move || {
         App::new()
             .app_data(register_user_services.clone())
@@ -131,6 +145,8 @@ pub async fn run() -> std::io::Result<()> {
             .app_data(user_query_service.clone())
             .app_data(role_query_service.clone())
             .app_data(permissions_detail_service.clone())
+            .app_data(dead_letter_service.clone())
+            .app_data(event_store_service.clone())
             .service(
                 web::scope("/api/user_system/user")
                     .wrap(auth_middleware.clone())
@@ -157,6 +173,14 @@ pub async fn run() -> std::io::Result<()> {
                     .service(update_profile)
                     .service(get_user),
             )
+            .service(
+                web::scope("/api/user_system/event")
+                    .wrap(auth_middleware.clone())
+                    .service(list_dead_letters)
+                    .service(replay_dead_letter)
+                    .service(list_events)
+                    .service(get_event),
+            )
             .service(web::scope("/api/user_system").service(login))
     })
     .bind(server_url)?